@@ -0,0 +1,520 @@
+use std::fmt::Write;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+use base64::Engine;
+use clap::{Arg, ArgMatches};
+use serde::Deserialize;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair};
+use solana_sdk::system_instruction;
+
+use multisig::{MultisigBackend, MultisigClient};
+
+pub const DEFAULT_URL: &str = "http://localhost:8899";
+
+pub enum Command {
+    Create {
+        seed: u128,
+        owners: Vec<Pubkey>,
+        threshold: u64,
+        max_signers: u32,
+    },
+    Show {
+        multisig: Pubkey,
+    },
+    Propose {
+        multisig: Pubkey,
+        proposer: Keypair,
+        proposal: Proposal,
+    },
+    Approve {
+        multisig: Pubkey,
+        transaction: Pubkey,
+        owner: Keypair,
+    },
+    Execute {
+        multisig: Pubkey,
+        transaction: Pubkey,
+    },
+    ListPending {
+        multisig: Pubkey,
+    },
+    DeletePending {
+        multisig: Pubkey,
+        proposer: Keypair,
+        transaction: Pubkey,
+    },
+}
+
+/// Instruction to be proposed to the multisig
+pub enum Proposal {
+    Raw(Instruction),
+    /// Lamports sent from the vault of the multisig
+    Transfer {
+        to: Pubkey,
+        lamports: u64,
+    },
+    Upgrade {
+        program: Pubkey,
+        buffer: Pubkey,
+        spill: Pubkey,
+    },
+    AddOwner {
+        owner: Pubkey,
+    },
+    DeleteOwner {
+        owner: Pubkey,
+    },
+    UpdateThreshold {
+        threshold: u64,
+    },
+}
+
+impl Proposal {
+    pub fn into_instruction(self, multisig_pubkey: &Pubkey) -> Instruction {
+        match self {
+            Proposal::Raw(ix) => ix,
+            Proposal::Transfer { to, lamports } => system_instruction::transfer(
+                &multisig::get_vault_address(multisig_pubkey),
+                &to,
+                lamports,
+            ),
+            Proposal::Upgrade {
+                program,
+                buffer,
+                spill,
+            } => multisig::upgrade_program(&program, &buffer, multisig_pubkey, &spill),
+            Proposal::AddOwner { owner } => multisig::add_owner(multisig_pubkey, owner),
+            Proposal::DeleteOwner { owner } => multisig::delete_owner(multisig_pubkey, owner),
+            Proposal::UpdateThreshold { threshold } => {
+                multisig::update_threshold(multisig_pubkey, threshold)
+            }
+        }
+    }
+}
+
+/// Instruction as read from a JSON file, with base58 keys and base64 data
+#[derive(Deserialize)]
+struct RawInstruction {
+    program_id: String,
+    accounts: Vec<RawAccount>,
+    data: String,
+}
+
+#[derive(Deserialize)]
+struct RawAccount {
+    pubkey: String,
+    is_signer: bool,
+    is_writable: bool,
+}
+
+impl TryFrom<RawInstruction> for Instruction {
+    type Error = anyhow::Error;
+
+    fn try_from(raw: RawInstruction) -> Result<Self> {
+        let accounts = raw
+            .accounts
+            .into_iter()
+            .map(|account| {
+                Ok(AccountMeta {
+                    pubkey: parse_pubkey(&account.pubkey)?,
+                    is_signer: account.is_signer,
+                    is_writable: account.is_writable,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Instruction {
+            program_id: parse_pubkey(&raw.program_id)?,
+            accounts,
+            data: base64::engine::general_purpose::STANDARD
+                .decode(raw.data)
+                .context("invalid instruction data")?,
+        })
+    }
+}
+
+pub fn app() -> clap::Command<'static> {
+    let multisig = Arg::new("multisig")
+        .long("multisig")
+        .takes_value(true)
+        .required(true)
+        .help("Multisig address");
+    let transaction = Arg::new("transaction")
+        .long("transaction")
+        .takes_value(true)
+        .required(true)
+        .help("Transaction address");
+    let signer = Arg::new("signer")
+        .long("signer")
+        .takes_value(true)
+        .help("Owner keypair file, the fee payer keypair by default");
+    let pubkey_arg = |name: &'static str, help: &'static str| {
+        Arg::new(name)
+            .long(name)
+            .takes_value(true)
+            .required(true)
+            .help(help)
+    };
+
+    // Proposals share the multisig and the proposer
+    let proposal = |name: &'static str| {
+        clap::Command::new(name)
+            .arg(multisig.clone())
+            .arg(signer.clone())
+    };
+
+    clap::Command::new("multisig-cli")
+        .about("Manage multisig accounts")
+        .subcommand_required(true)
+        .arg(
+            Arg::new("url")
+                .long("url")
+                .short('u')
+                .global(true)
+                .takes_value(true)
+                .default_value(DEFAULT_URL)
+                .help("RPC endpoint"),
+        )
+        .arg(
+            Arg::new("keypair")
+                .long("keypair")
+                .short('k')
+                .global(true)
+                .takes_value(true)
+                .help("Fee payer keypair file, ~/.config/solana/id.json by default"),
+        )
+        .subcommand(
+            clap::Command::new("create")
+                .about("Create a multisig")
+                .arg(
+                    Arg::new("owner")
+                        .long("owner")
+                        .takes_value(true)
+                        .multiple_occurrences(true)
+                        .required(true)
+                        .help("Owner address, repeated for every owner"),
+                )
+                .arg(
+                    Arg::new("threshold")
+                        .long("threshold")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("max-signers")
+                        .long("max-signers")
+                        .takes_value(true)
+                        .help("Capacity of the multisig, the maximum by default"),
+                )
+                .arg(
+                    Arg::new("seed")
+                        .long("seed")
+                        .takes_value(true)
+                        .help("Multisig seed as UUID, random by default"),
+                ),
+        )
+        .subcommand(
+            clap::Command::new("show")
+                .about("Print multisig state")
+                .arg(multisig.clone()),
+        )
+        .subcommand(
+            clap::Command::new("propose")
+                .about("Propose a transaction")
+                .subcommand_required(true)
+                .subcommand(
+                    proposal("raw")
+                        .about("Propose an instruction read from a JSON file")
+                        .arg(
+                            Arg::new("instruction")
+                                .long("instruction")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Path to the instruction JSON"),
+                        ),
+                )
+                .subcommand(
+                    proposal("transfer")
+                        .about("Propose a SOL transfer from the multisig vault")
+                        .arg(pubkey_arg("to", "Recipient address"))
+                        .arg(
+                            Arg::new("lamports")
+                                .long("lamports")
+                                .takes_value(true)
+                                .required(true),
+                        ),
+                )
+                .subcommand(
+                    proposal("upgrade")
+                        .about("Propose a program upgrade, the multisig being its authority")
+                        .arg(pubkey_arg("program", "Program address"))
+                        .arg(pubkey_arg("buffer", "Buffer with the new program"))
+                        .arg(pubkey_arg("spill", "Recipient of the buffer lamports")),
+                )
+                .subcommand(
+                    proposal("add-owner")
+                        .about("Propose adding an owner")
+                        .arg(pubkey_arg("owner", "Owner address")),
+                )
+                .subcommand(
+                    proposal("delete-owner")
+                        .about("Propose deleting an owner")
+                        .arg(pubkey_arg("owner", "Owner address")),
+                )
+                .subcommand(
+                    proposal("update-threshold")
+                        .about("Propose changing the threshold")
+                        .arg(
+                            Arg::new("threshold")
+                                .long("threshold")
+                                .takes_value(true)
+                                .required(true),
+                        ),
+                ),
+        )
+        .subcommand(
+            clap::Command::new("approve")
+                .about("Approve a transaction")
+                .arg(multisig.clone())
+                .arg(transaction.clone())
+                .arg(signer.clone()),
+        )
+        .subcommand(
+            clap::Command::new("execute")
+                .about("Execute an approved transaction")
+                .arg(multisig.clone())
+                .arg(transaction.clone()),
+        )
+        .subcommand(
+            clap::Command::new("list-pending")
+                .about("List transactions neither executed nor deleted, with their accounts")
+                .arg(multisig.clone()),
+        )
+        .subcommand(
+            clap::Command::new("delete-pending")
+                .about("Propose deleting a pending transaction")
+                .arg(multisig)
+                .arg(transaction)
+                .arg(signer),
+        )
+}
+
+impl Command {
+    /// Parse subcommand arguments, with owner keypairs defaulting to `default_signer`
+    pub fn from_matches(matches: &ArgMatches, default_signer: &str) -> Result<Self> {
+        let read_signer = |matches: &ArgMatches| {
+            let path = matches.value_of("signer").unwrap_or(default_signer);
+            read_keypair_file(path).map_err(|e| anyhow!("failed to read {}: {}", path, e))
+        };
+
+        let command = match matches.subcommand() {
+            Some(("create", matches)) => Command::Create {
+                seed: match matches.value_of("seed") {
+                    Some(seed) => uuid::Uuid::from_str(seed)?.as_u128(),
+                    None => uuid::Uuid::new_v4().as_u128(),
+                },
+                owners: matches
+                    .values_of("owner")
+                    .unwrap_or_default()
+                    .map(parse_pubkey)
+                    .collect::<Result<_>>()?,
+                threshold: parse_value(matches, "threshold")?,
+                max_signers: match matches.value_of("max-signers") {
+                    Some(_) => parse_value(matches, "max-signers")?,
+                    None => multisig::MAX_SIGNERS as u32,
+                },
+            },
+            Some(("show", matches)) => Command::Show {
+                multisig: parse_value(matches, "multisig")?,
+            },
+            Some(("propose", matches)) => {
+                let (name, args) = matches
+                    .subcommand()
+                    .ok_or_else(|| anyhow!("missing proposal"))?;
+
+                let proposal = match name {
+                    "raw" => {
+                        let path = args.value_of("instruction").unwrap_or_default();
+                        let json = std::fs::read_to_string(path)
+                            .with_context(|| format!("failed to read {}", path))?;
+                        let raw: RawInstruction = serde_json::from_str(&json)?;
+                        Proposal::Raw(raw.try_into()?)
+                    }
+                    "transfer" => Proposal::Transfer {
+                        to: parse_value(args, "to")?,
+                        lamports: parse_value(args, "lamports")?,
+                    },
+                    "upgrade" => Proposal::Upgrade {
+                        program: parse_value(args, "program")?,
+                        buffer: parse_value(args, "buffer")?,
+                        spill: parse_value(args, "spill")?,
+                    },
+                    "add-owner" => Proposal::AddOwner {
+                        owner: parse_value(args, "owner")?,
+                    },
+                    "delete-owner" => Proposal::DeleteOwner {
+                        owner: parse_value(args, "owner")?,
+                    },
+                    "update-threshold" => Proposal::UpdateThreshold {
+                        threshold: parse_value(args, "threshold")?,
+                    },
+                    name => return Err(anyhow!("unknown proposal {}", name)),
+                };
+
+                Command::Propose {
+                    multisig: parse_value(args, "multisig")?,
+                    proposer: read_signer(args)?,
+                    proposal,
+                }
+            }
+            Some(("approve", matches)) => Command::Approve {
+                multisig: parse_value(matches, "multisig")?,
+                transaction: parse_value(matches, "transaction")?,
+                owner: read_signer(matches)?,
+            },
+            Some(("execute", matches)) => Command::Execute {
+                multisig: parse_value(matches, "multisig")?,
+                transaction: parse_value(matches, "transaction")?,
+            },
+            Some(("list-pending", matches)) => Command::ListPending {
+                multisig: parse_value(matches, "multisig")?,
+            },
+            Some(("delete-pending", matches)) => Command::DeletePending {
+                multisig: parse_value(matches, "multisig")?,
+                proposer: read_signer(matches)?,
+                transaction: parse_value(matches, "transaction")?,
+            },
+            _ => return Err(anyhow!("missing command")),
+        };
+
+        Ok(command)
+    }
+}
+
+/// Run the command and return its output
+pub async fn run<B: MultisigBackend + Send>(
+    client: &mut MultisigClient<B>,
+    command: Command,
+) -> Result<String> {
+    let mut output = String::new();
+
+    match command {
+        Command::Create {
+            seed,
+            owners,
+            threshold,
+            max_signers,
+        } => {
+            let multisig_pubkey = client
+                .create_multisig(seed, owners, threshold, max_signers)
+                .await?;
+            writeln!(output, "{}", multisig_pubkey)?;
+        }
+        Command::Show { multisig } => {
+            let multisig_data = client.get_multisig(&multisig).await?;
+
+            writeln!(output, "Address: {}", multisig)?;
+            writeln!(output, "Vault: {}", multisig::get_vault_address(&multisig))?;
+            writeln!(output, "Threshold: {}", multisig_data.threshold)?;
+            writeln!(output, "Owners:")?;
+            for owner in &multisig_data.owners {
+                writeln!(output, "  {}", owner)?;
+            }
+            writeln!(output, "Max signers: {}", multisig_data.max_signers)?;
+            writeln!(
+                output,
+                "Pending transactions: {}",
+                multisig_data.pending_transaction_count
+            )?;
+            writeln!(
+                output,
+                "Transaction index: {}",
+                multisig_data.transaction_index
+            )?;
+        }
+        Command::Propose {
+            multisig,
+            proposer,
+            proposal,
+        } => {
+            let ix = proposal.into_instruction(&multisig);
+            let transaction_pubkey = client.propose(&proposer, &multisig, ix).await?;
+            writeln!(output, "{}", transaction_pubkey)?;
+        }
+        Command::Approve {
+            multisig,
+            transaction,
+            owner,
+        } => {
+            client.approve(&owner, &multisig, &transaction).await?;
+        }
+        Command::Execute {
+            multisig,
+            transaction,
+        } => {
+            client.execute(&multisig, &transaction).await?;
+        }
+        Command::ListPending { multisig } => {
+            let multisig_data = client.get_multisig(&multisig).await?;
+
+            for (transaction_pubkey, transaction) in client.get_proposals(&multisig).await? {
+                if transaction.did_execute || transaction.is_cancelled {
+                    continue;
+                }
+
+                let approvals = transaction.get_approval_count();
+                writeln!(
+                    output,
+                    "{} index {} program {} approvals {}/{}",
+                    transaction_pubkey,
+                    transaction.index,
+                    transaction.program_id,
+                    approvals,
+                    multisig_data.get_threshold(&transaction.program_id, &transaction.data),
+                )?;
+
+                // Including the accounts resolved through lookup tables
+                for account in transaction.get_accounts() {
+                    writeln!(
+                        output,
+                        "  account {}{}{}",
+                        account.pubkey,
+                        if account.is_signer { " signer" } else { "" },
+                        if account.is_writable { " writable" } else { "" },
+                    )?;
+                }
+            }
+        }
+        Command::DeletePending {
+            multisig,
+            proposer,
+            transaction,
+        } => {
+            let ix = multisig::delete_pending_transaction(&multisig, transaction);
+            let transaction_pubkey = client.propose(&proposer, &multisig, ix).await?;
+            writeln!(output, "{}", transaction_pubkey)?;
+        }
+    }
+
+    Ok(output)
+}
+
+fn parse_pubkey(value: &str) -> Result<Pubkey> {
+    Pubkey::from_str(value).with_context(|| format!("invalid address {}", value))
+}
+
+fn parse_value<T>(matches: &ArgMatches, name: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let value = matches
+        .value_of(name)
+        .ok_or_else(|| anyhow!("missing --{}", name))?;
+    value
+        .parse()
+        .with_context(|| format!("invalid --{} {}", name, value))
+}
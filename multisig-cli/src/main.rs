@@ -0,0 +1,34 @@
+use anyhow::{anyhow, Result};
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::read_keypair_file;
+
+use multisig::MultisigClient;
+use multisig_cli::{app, run, Command};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let matches = app().get_matches();
+
+    let keypair_path = match matches.value_of("keypair") {
+        Some(path) => path.to_string(),
+        None => {
+            let home = std::env::var("HOME").map_err(|_| anyhow!("HOME is not set"))?;
+            format!("{}/.config/solana/id.json", home)
+        }
+    };
+
+    let command = Command::from_matches(&matches, &keypair_path)?;
+
+    let payer = read_keypair_file(&keypair_path)
+        .map_err(|e| anyhow!("failed to read {}: {}", keypair_path, e))?;
+
+    let url = matches.value_of("url").unwrap_or(multisig_cli::DEFAULT_URL);
+    let rpc_client = RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed());
+
+    let mut client = MultisigClient::new(rpc_client, payer);
+
+    print!("{}", run(&mut client, command).await?);
+
+    Ok(())
+}
@@ -0,0 +1,333 @@
+use std::path::PathBuf;
+
+use solana_program_test::{processor, tokio, BanksClient, ProgramTest};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+use solana_sdk::{bpf_loader_upgradeable, system_instruction, system_program};
+
+use multisig::MultisigClient;
+use multisig_cli::{app, run, Command};
+
+struct Cli {
+    client: MultisigClient<BanksClient>,
+    payer_path: String,
+    dir: PathBuf,
+}
+
+impl Cli {
+    async fn new() -> Self {
+        let program_test = ProgramTest::new(
+            "multisig",
+            multisig::id(),
+            processor!(multisig::Processor::process),
+        );
+
+        let (banks_client, payer, _) = program_test.start().await;
+
+        let dir = std::env::temp_dir().join(format!("multisig-cli-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create_dir_all");
+
+        let mut cli = Self {
+            client: MultisigClient::new(banks_client, payer.insecure_clone()),
+            payer_path: String::new(),
+            dir,
+        };
+        cli.payer_path = cli.write_keypair("payer", &payer);
+
+        cli
+    }
+
+    fn write_keypair(&self, name: &str, keypair: &Keypair) -> String {
+        let path = self.dir.join(format!("{}.json", name));
+        write_keypair_file(keypair, &path).expect("write_keypair_file");
+        path.to_string_lossy().into_owned()
+    }
+
+    fn write_file(&self, name: &str, content: &str) -> String {
+        let path = self.dir.join(name);
+        std::fs::write(&path, content).expect("write");
+        path.to_string_lossy().into_owned()
+    }
+
+    async fn run(&mut self, args: &[&str]) -> anyhow::Result<String> {
+        let args = std::iter::once("multisig-cli").chain(args.iter().copied());
+
+        let matches = app().try_get_matches_from(args)?;
+        let command = Command::from_matches(&matches, &self.payer_path)?;
+        run(&mut self.client, command).await
+    }
+}
+
+impl Drop for Cli {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[tokio::test]
+async fn test_cli() {
+    let mut cli = Cli::new().await;
+
+    let custodian_1 = Keypair::new();
+    let custodian_2 = Keypair::new();
+
+    let custodian_1_path = cli.write_keypair("custodian_1", &custodian_1);
+    let custodian_2_path = cli.write_keypair("custodian_2", &custodian_2);
+
+    // Create
+    let output = cli
+        .run(&[
+            "create",
+            "--owner",
+            &custodian_1.pubkey().to_string(),
+            "--owner",
+            &custodian_2.pubkey().to_string(),
+            "--threshold",
+            "2",
+        ])
+        .await
+        .expect("create");
+    let multisig = output.trim().to_string();
+    let vault = multisig::get_vault_address(&multisig.parse().unwrap());
+
+    let output = cli
+        .run(&["show", "--multisig", &multisig])
+        .await
+        .expect("show");
+    assert!(output.contains("Threshold: 2"));
+    assert!(output.contains(&format!("Vault: {}", vault)));
+    assert!(output.contains(&custodian_1.pubkey().to_string()));
+
+    // Propose, approve and execute an owner change
+    let new_owner = Pubkey::new_unique().to_string();
+
+    let output = cli
+        .run(&[
+            "propose",
+            "add-owner",
+            "--multisig",
+            &multisig,
+            "--signer",
+            &custodian_1_path,
+            "--owner",
+            &new_owner,
+        ])
+        .await
+        .expect("propose add-owner");
+    let add_owner = output.trim().to_string();
+
+    let output = cli
+        .run(&["list-pending", "--multisig", &multisig])
+        .await
+        .expect("list-pending");
+    assert!(output.contains(&add_owner));
+    assert!(output.contains("approvals 1/2"));
+    assert!(output.contains(&format!("account {} signer writable", multisig)));
+
+    // Not enough signers yet
+    assert!(cli
+        .run(&[
+            "execute",
+            "--multisig",
+            &multisig,
+            "--transaction",
+            &add_owner
+        ])
+        .await
+        .is_err());
+
+    cli.run(&[
+        "approve",
+        "--multisig",
+        &multisig,
+        "--transaction",
+        &add_owner,
+        "--signer",
+        &custodian_2_path,
+    ])
+    .await
+    .expect("approve");
+
+    cli.run(&[
+        "execute",
+        "--multisig",
+        &multisig,
+        "--transaction",
+        &add_owner,
+    ])
+    .await
+    .expect("execute");
+
+    let output = cli
+        .run(&["list-pending", "--multisig", &multisig])
+        .await
+        .expect("list-pending");
+    assert!(output.is_empty());
+
+    let output = cli
+        .run(&["show", "--multisig", &multisig])
+        .await
+        .expect("show");
+    assert!(output.contains(&new_owner));
+
+    // Other proposals
+    let recipient = Pubkey::new_unique();
+
+    let instruction = cli.write_file(
+        "instruction.json",
+        &format!(
+            r#"{{
+                "program_id": "{}",
+                "accounts": [
+                    {{ "pubkey": "{}", "is_signer": false, "is_writable": true }}
+                ],
+                "data": "AQID"
+            }}"#,
+            system_program::id(),
+            recipient,
+        ),
+    );
+
+    let mut proposals = Vec::new();
+    for args in [
+        vec!["raw", "--instruction", &instruction],
+        vec!["transfer", "--to", &new_owner, "--lamports", "1000000000"],
+        vec![
+            "upgrade",
+            "--program",
+            &new_owner,
+            "--buffer",
+            &new_owner,
+            "--spill",
+            &new_owner,
+        ],
+    ] {
+        let args = [
+            vec!["propose"],
+            args,
+            vec!["--multisig", &multisig, "--signer", &custodian_1_path],
+        ]
+        .concat();
+        let output = cli.run(&args).await.expect("propose");
+        proposals.push(
+            output
+                .trim()
+                .parse::<Pubkey>()
+                .expect("transaction address"),
+        );
+    }
+
+    let raw = cli
+        .client
+        .get_transaction(&proposals[0])
+        .await
+        .expect("get_transaction");
+    assert_eq!(raw.program_id, system_program::id());
+    assert_eq!(raw.accounts[0].pubkey, recipient);
+    assert_eq!(raw.data, vec![1, 2, 3]);
+
+    let transfer = cli
+        .client
+        .get_transaction(&proposals[1])
+        .await
+        .expect("get_transaction");
+    assert_eq!(transfer.program_id, system_program::id());
+    assert_eq!(transfer.accounts[0].pubkey, vault);
+
+    let upgrade = cli
+        .client
+        .get_transaction(&proposals[2])
+        .await
+        .expect("get_transaction");
+    assert_eq!(upgrade.program_id, bpf_loader_upgradeable::id());
+    assert_eq!(upgrade.proposer, custodian_1.pubkey());
+
+    // Delete the raw proposal
+    let output = cli
+        .run(&[
+            "delete-pending",
+            "--multisig",
+            &multisig,
+            "--transaction",
+            &proposals[0].to_string(),
+            "--signer",
+            &custodian_1_path,
+        ])
+        .await
+        .expect("delete-pending");
+    let delete = output.trim().to_string();
+
+    cli.run(&[
+        "approve",
+        "--multisig",
+        &multisig,
+        "--transaction",
+        &delete,
+        "--signer",
+        &custodian_2_path,
+    ])
+    .await
+    .expect("approve");
+
+    cli.run(&["execute", "--multisig", &multisig, "--transaction", &delete])
+        .await
+        .expect("execute");
+
+    let output = cli
+        .run(&["list-pending", "--multisig", &multisig])
+        .await
+        .expect("list-pending");
+    assert!(!output.contains(&proposals[0].to_string()));
+    assert!(output.contains(&proposals[1].to_string()));
+    assert!(output.contains(&proposals[2].to_string()));
+
+    // Execute the transfer once the vault is funded
+    let payer = cli.client.payer().pubkey();
+    cli.client
+        .send(
+            &[system_instruction::transfer(&payer, &vault, 2_000_000_000)],
+            &[],
+        )
+        .await
+        .expect("fund vault");
+
+    let transfer = proposals[1].to_string();
+
+    cli.run(&[
+        "approve",
+        "--multisig",
+        &multisig,
+        "--transaction",
+        &transfer,
+        "--signer",
+        &custodian_2_path,
+    ])
+    .await
+    .expect("approve");
+
+    cli.run(&[
+        "execute",
+        "--multisig",
+        &multisig,
+        "--transaction",
+        &transfer,
+    ])
+    .await
+    .expect("execute");
+
+    let recipient_balance = cli
+        .client
+        .backend()
+        .get_balance(new_owner.parse().unwrap())
+        .await
+        .expect("get_balance");
+    assert_eq!(recipient_balance, 1_000_000_000);
+
+    let vault_balance = cli
+        .client
+        .backend()
+        .get_balance(vault)
+        .await
+        .expect("get_balance");
+    assert_eq!(vault_balance, 1_000_000_000);
+}
@@ -6,7 +6,8 @@ use syn::DeriveInput;
 #[derive(FromDeriveInput, Default)]
 #[darling(default, attributes(multisig_pack), forward_attrs(allow, doc, cfg))]
 struct Opts {
-    length: usize,
+    /// Account length of an earlier layout, such accounts fail to unpack
+    legacy_length: Option<syn::Expr>,
 }
 
 #[proc_macro_derive(MultisigPack, attributes(multisig_pack))]
@@ -19,19 +20,36 @@ fn impl_derive_multisig_pack(input: syn::DeriveInput) -> TokenStream {
     let opts = Opts::from_derive_input(&input).expect("Wrong options");
     let DeriveInput { ident, .. } = input;
 
-    let len = opts.length;
+    let legacy_check = opts.legacy_length.map(|legacy_length| {
+        quote! {
+            if src.len() == #legacy_length {
+                return Err(ProgramError::InvalidAccountData);
+            }
+        }
+    });
 
     let output = quote! {
-        impl Pack for #ident {
-            const LEN: usize = #len;
-
-            fn pack_into_slice(&self, dst: &mut [u8]) {
-                let mut data = self.try_to_vec().unwrap();
+        impl #ident {
+            pub fn pack(src: Self, dst: &mut [u8]) -> Result<(), ProgramError> {
+                let data = src.try_to_vec()?;
+                if data.len() > dst.len() {
+                    return Err(ProgramError::AccountDataTooSmall);
+                }
                 let (left, _) = dst.split_at_mut(data.len());
                 left.copy_from_slice(&data);
+                Ok(())
+            }
+
+            pub fn unpack(src: &[u8]) -> Result<Self, ProgramError> {
+                let unpacked = Self::unpack_unchecked(src)?;
+                if !IsInitialized::is_initialized(&unpacked) {
+                    return Err(ProgramError::UninitializedAccount);
+                }
+                Ok(unpacked)
             }
 
-            fn unpack_from_slice(mut src: &[u8]) -> Result<Self, ProgramError> {
+            pub fn unpack_unchecked(mut src: &[u8]) -> Result<Self, ProgramError> {
+                #legacy_check
                 let unpacked = Self::deserialize(&mut src)?;
                 Ok(unpacked)
             }
@@ -1,15 +1,28 @@
-use borsh::BorshSerialize;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::address_lookup_table::AddressLookupTableAccount;
+use solana_program::hash::{self, Hash};
 use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::message::{v0, VersionedMessage};
+use solana_program::program_error::ProgramError;
 use solana_program::pubkey::Pubkey;
-use solana_program::{system_program, sysvar};
+use solana_program::{
+    bpf_loader_upgradeable, ed25519_program, secp256k1_program, system_program, sysvar,
+};
 
 use crate::*;
 
+/// Default size of instruction data chunks uploaded to a transaction buffer
+pub const TRANSACTION_BUFFER_CHUNK_SIZE: usize = 900;
+
+/// Upper bound of the nonces `find_multisig_creator_nonce` searches
+pub const MAX_MULTISIG_CREATOR_NONCE: u64 = 1024;
+
 pub fn create_multisig(
     funder_pubkey: &Pubkey,
     seed: u128,
     owners: Vec<Pubkey>,
     threshold: u64,
+    max_signers: u32,
 ) -> Instruction {
     let multisig_pubkey = get_multisig_address(seed);
 
@@ -17,6 +30,7 @@ pub fn create_multisig(
         seed,
         owners,
         threshold,
+        max_signers,
     }
     .try_to_vec()
     .expect("pack");
@@ -37,31 +51,234 @@ pub fn create_transaction(
     funder_pubkey: &Pubkey,
     proposer_pubkey: &Pubkey,
     multisig_pubkey: &Pubkey,
-    seed: u128,
+    transaction_index: u64,
     ix: Instruction,
 ) -> Instruction {
-    let mut accounts = ix
+    let accounts = get_transaction_accounts(&ix);
+
+    let transaction_pubkey = get_transaction_address(multisig_pubkey, transaction_index);
+
+    let data = MultisigInstruction::CreateTransaction {
+        pid: ix.program_id,
+        accs: accounts,
+        data: ix.data,
+    }
+    .try_to_vec()
+    .expect("pack");
+
+    Instruction {
+        program_id: id(),
+        accounts: vec![
+            AccountMeta::new(*funder_pubkey, true),
+            AccountMeta::new(*proposer_pubkey, true),
+            AccountMeta::new(*multisig_pubkey, false),
+            AccountMeta::new(transaction_pubkey, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data,
+    }
+}
+
+pub fn update_transaction(
+    funder_pubkey: &Pubkey,
+    proposer_pubkey: &Pubkey,
+    multisig_pubkey: &Pubkey,
+    transaction_pubkey: &Pubkey,
+    ix: Instruction,
+) -> Instruction {
+    let accounts = get_transaction_accounts(&ix);
+
+    let data = MultisigInstruction::UpdateTransaction {
+        pid: ix.program_id,
+        accs: accounts,
+        data: ix.data,
+    }
+    .try_to_vec()
+    .expect("pack");
+
+    Instruction {
+        program_id: id(),
+        accounts: vec![
+            AccountMeta::new(*funder_pubkey, true),
+            AccountMeta::new_readonly(*proposer_pubkey, true),
+            AccountMeta::new_readonly(*multisig_pubkey, false),
+            AccountMeta::new(*transaction_pubkey, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data,
+    }
+}
+
+/// Same as `create_transaction`, with the trailing accounts of the instruction
+/// found in the lookup tables stored as lookup table indices
+pub fn create_transaction_with_lookups(
+    funder_pubkey: &Pubkey,
+    proposer_pubkey: &Pubkey,
+    multisig_pubkey: &Pubkey,
+    transaction_index: u64,
+    ix: Instruction,
+    lookup_tables: &[AddressLookupTableAccount],
+) -> Instruction {
+    let (accounts, tables, lookup_accounts) = get_transaction_lookup_accounts(&ix, lookup_tables);
+
+    let transaction_pubkey = get_transaction_address(multisig_pubkey, transaction_index);
+
+    let mut accounts_metas = vec![
+        AccountMeta::new(*funder_pubkey, true),
+        AccountMeta::new(*proposer_pubkey, true),
+        AccountMeta::new(*multisig_pubkey, false),
+        AccountMeta::new(transaction_pubkey, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+    accounts_metas.extend(
+        tables
+            .iter()
+            .map(|table| AccountMeta::new_readonly(*table, false)),
+    );
+
+    let data = MultisigInstruction::CreateTransactionWithLookups {
+        pid: ix.program_id,
+        accs: accounts,
+        lookup_tables: tables,
+        lookup_accounts,
+        data: ix.data,
+    }
+    .try_to_vec()
+    .expect("pack");
+
+    Instruction {
+        program_id: id(),
+        accounts: accounts_metas,
+        data,
+    }
+}
+
+/// Same as `update_transaction`, with the trailing accounts of the instruction
+/// found in the lookup tables stored as lookup table indices
+pub fn update_transaction_with_lookups(
+    funder_pubkey: &Pubkey,
+    proposer_pubkey: &Pubkey,
+    multisig_pubkey: &Pubkey,
+    transaction_pubkey: &Pubkey,
+    ix: Instruction,
+    lookup_tables: &[AddressLookupTableAccount],
+) -> Instruction {
+    let (accounts, tables, lookup_accounts) = get_transaction_lookup_accounts(&ix, lookup_tables);
+
+    let mut accounts_metas = vec![
+        AccountMeta::new(*funder_pubkey, true),
+        AccountMeta::new_readonly(*proposer_pubkey, true),
+        AccountMeta::new_readonly(*multisig_pubkey, false),
+        AccountMeta::new(*transaction_pubkey, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+    accounts_metas.extend(
+        tables
+            .iter()
+            .map(|table| AccountMeta::new_readonly(*table, false)),
+    );
+
+    let data = MultisigInstruction::UpdateTransactionWithLookups {
+        pid: ix.program_id,
+        accs: accounts,
+        lookup_tables: tables,
+        lookup_accounts,
+        data: ix.data,
+    }
+    .try_to_vec()
+    .expect("pack");
+
+    Instruction {
+        program_id: id(),
+        accounts: accounts_metas,
+        data,
+    }
+}
+
+/// Static accounts of the instruction, the lookup tables used and the trailing
+/// accounts found in them
+fn get_transaction_lookup_accounts(
+    ix: &Instruction,
+    lookup_tables: &[AddressLookupTableAccount],
+) -> (
+    Vec<TransactionAccount>,
+    Vec<Pubkey>,
+    Vec<TransactionLookupAccount>,
+) {
+    let find = |pubkey: &Pubkey| {
+        lookup_tables
+            .iter()
+            .enumerate()
+            .find_map(|(table_index, table)| {
+                let address_index = table.addresses.iter().position(|a| a == pubkey)?;
+                (address_index <= u8::MAX as usize).then_some((table_index, address_index))
+            })
+    };
+
+    // Lookup accounts follow the static ones, so only a suffix can be looked up
+    let static_len = ix
         .accounts
-        .into_iter()
+        .iter()
+        .rposition(|acc| acc.is_signer || find(&acc.pubkey).is_none())
+        .map(|position| position + 1)
+        .unwrap_or_default();
+
+    let mut tables = Vec::new();
+    let mut lookup_accounts = Vec::new();
+    for acc in &ix.accounts[static_len..] {
+        let (table_index, address_index) = find(&acc.pubkey).expect("lookup account");
+        let table = lookup_tables[table_index].key;
+
+        let table_index = match tables.iter().position(|t| *t == table) {
+            Some(position) => position,
+            None => {
+                tables.push(table);
+                tables.len() - 1
+            }
+        };
+
+        lookup_accounts.push(TransactionLookupAccount {
+            table_index: table_index as u8,
+            address_index: address_index as u8,
+            is_writable: acc.is_writable,
+        });
+    }
+
+    let accounts = ix.accounts[..static_len]
+        .iter()
         .map(|acc| TransactionAccount {
             pubkey: acc.pubkey,
             is_signer: acc.is_signer,
             is_writable: acc.is_writable,
         })
-        .collect::<Vec<_>>();
-    accounts.push(TransactionAccount {
-        pubkey: ix.program_id,
-        is_signer: false,
-        is_writable: false,
-    });
+        .collect();
 
-    let transaction_pubkey = get_transaction_address(seed);
+    (accounts, tables, lookup_accounts)
+}
 
-    let data = MultisigInstruction::CreateTransaction {
-        seed,
+/// Start uploading the instruction through a transaction buffer, with its first
+/// `chunk_size` bytes clamped to `1..=TRANSACTION_BUFFER_CHUNK_SIZE`
+pub fn create_transaction_buffer(
+    funder_pubkey: &Pubkey,
+    proposer_pubkey: &Pubkey,
+    multisig_pubkey: &Pubkey,
+    ix: &Instruction,
+    chunk_size: usize,
+) -> Instruction {
+    let chunk_size = chunk_size.clamp(1, TRANSACTION_BUFFER_CHUNK_SIZE);
+
+    let buffer_pubkey = get_transaction_buffer_address(multisig_pubkey, proposer_pubkey);
+
+    let data = MultisigInstruction::CreateTransactionBuffer {
         pid: ix.program_id,
-        accs: accounts,
-        data: ix.data,
+        accs: get_transaction_accounts(ix),
+        final_hash: hash::hash(&ix.data).to_bytes(),
+        final_size: ix.data.len() as u32,
+        data: ix.data[..chunk_size.min(ix.data.len())].to_vec(),
     }
     .try_to_vec()
     .expect("pack");
@@ -70,16 +287,137 @@ pub fn create_transaction(
         program_id: id(),
         accounts: vec![
             AccountMeta::new(*funder_pubkey, true),
-            AccountMeta::new(*proposer_pubkey, true),
+            AccountMeta::new_readonly(*proposer_pubkey, true),
+            AccountMeta::new_readonly(*multisig_pubkey, false),
+            AccountMeta::new(buffer_pubkey, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data,
+    }
+}
+
+pub fn extend_transaction_buffer(
+    funder_pubkey: &Pubkey,
+    proposer_pubkey: &Pubkey,
+    multisig_pubkey: &Pubkey,
+    chunk: Vec<u8>,
+) -> Instruction {
+    let buffer_pubkey = get_transaction_buffer_address(multisig_pubkey, proposer_pubkey);
+
+    let data = MultisigInstruction::ExtendTransactionBuffer { data: chunk }
+        .try_to_vec()
+        .expect("pack");
+
+    Instruction {
+        program_id: id(),
+        accounts: vec![
+            AccountMeta::new(*funder_pubkey, true),
+            AccountMeta::new_readonly(*proposer_pubkey, true),
+            AccountMeta::new(buffer_pubkey, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data,
+    }
+}
+
+pub fn finalize_transaction(
+    funder_pubkey: &Pubkey,
+    proposer_pubkey: &Pubkey,
+    multisig_pubkey: &Pubkey,
+    transaction_index: u64,
+    buffer_funder_pubkey: &Pubkey,
+) -> Instruction {
+    let buffer_pubkey = get_transaction_buffer_address(multisig_pubkey, proposer_pubkey);
+    let transaction_pubkey = get_transaction_address(multisig_pubkey, transaction_index);
+
+    let data = MultisigInstruction::FinalizeTransaction
+        .try_to_vec()
+        .expect("pack");
+
+    Instruction {
+        program_id: id(),
+        accounts: vec![
+            AccountMeta::new(*funder_pubkey, true),
+            AccountMeta::new_readonly(*proposer_pubkey, true),
             AccountMeta::new(*multisig_pubkey, false),
+            AccountMeta::new(buffer_pubkey, false),
             AccountMeta::new(transaction_pubkey, false),
             AccountMeta::new_readonly(system_program::id(), false),
             AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new(*buffer_funder_pubkey, false),
         ],
         data,
     }
 }
 
+pub fn close_transaction_buffer(
+    proposer_pubkey: &Pubkey,
+    multisig_pubkey: &Pubkey,
+    buffer_funder_pubkey: &Pubkey,
+) -> Instruction {
+    let buffer_pubkey = get_transaction_buffer_address(multisig_pubkey, proposer_pubkey);
+
+    let data = MultisigInstruction::CloseTransactionBuffer
+        .try_to_vec()
+        .expect("pack");
+
+    Instruction {
+        program_id: id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*proposer_pubkey, true),
+            AccountMeta::new(buffer_pubkey, false),
+            AccountMeta::new(*buffer_funder_pubkey, false),
+        ],
+        data,
+    }
+}
+
+/// Instructions uploading the instruction through a transaction buffer in chunks
+/// and creating a transaction at `transaction_index` out of it. Each one is to be
+/// sent in its own transaction, in order. `chunk_size` is clamped to
+/// `1..=TRANSACTION_BUFFER_CHUNK_SIZE`
+pub fn create_transaction_with_buffer(
+    funder_pubkey: &Pubkey,
+    proposer_pubkey: &Pubkey,
+    multisig_pubkey: &Pubkey,
+    transaction_index: u64,
+    ix: Instruction,
+    chunk_size: usize,
+) -> Vec<Instruction> {
+    let chunk_size = chunk_size.clamp(1, TRANSACTION_BUFFER_CHUNK_SIZE);
+
+    let mut instructions = vec![create_transaction_buffer(
+        funder_pubkey,
+        proposer_pubkey,
+        multisig_pubkey,
+        &ix,
+        chunk_size,
+    )];
+
+    if ix.data.len() > chunk_size {
+        for chunk in ix.data[chunk_size..].chunks(chunk_size) {
+            instructions.push(extend_transaction_buffer(
+                funder_pubkey,
+                proposer_pubkey,
+                multisig_pubkey,
+                chunk.to_vec(),
+            ));
+        }
+    }
+
+    instructions.push(finalize_transaction(
+        funder_pubkey,
+        proposer_pubkey,
+        multisig_pubkey,
+        transaction_index,
+        funder_pubkey,
+    ));
+
+    instructions
+}
+
 pub fn add_owner(multisig_pubkey: &Pubkey, owner: Pubkey) -> Instruction {
     let data = MultisigInstruction::AddOwner { owner }
         .try_to_vec()
@@ -92,6 +430,30 @@ pub fn add_owner(multisig_pubkey: &Pubkey, owner: Pubkey) -> Instruction {
     }
 }
 
+pub fn delete_owner(multisig_pubkey: &Pubkey, owner: Pubkey) -> Instruction {
+    let data = MultisigInstruction::DeleteOwner { owner }
+        .try_to_vec()
+        .expect("pack");
+
+    Instruction {
+        program_id: id(),
+        accounts: vec![AccountMeta::new(*multisig_pubkey, true)],
+        data,
+    }
+}
+
+pub fn update_threshold(multisig_pubkey: &Pubkey, threshold: u64) -> Instruction {
+    let data = MultisigInstruction::UpdateThreshold { threshold }
+        .try_to_vec()
+        .expect("pack");
+
+    Instruction {
+        program_id: id(),
+        accounts: vec![AccountMeta::new(*multisig_pubkey, true)],
+        data,
+    }
+}
+
 pub fn approve(
     proposer_pubkey: &Pubkey,
     multisig_pubkey: &Pubkey,
@@ -110,7 +472,90 @@ pub fn approve(
     }
 }
 
+pub fn approve_with_signatures(
+    multisig_pubkey: &Pubkey,
+    transaction_pubkey: &Pubkey,
+) -> Instruction {
+    let data = MultisigInstruction::ApproveWithSignatures
+        .try_to_vec()
+        .expect("pack");
+
+    Instruction {
+        program_id: id(),
+        accounts: vec![
+            AccountMeta::new(*transaction_pubkey, false),
+            AccountMeta::new_readonly(*multisig_pubkey, false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        ],
+        data,
+    }
+}
+
+/// Ed25519 program instruction checking owner signatures over the approval message,
+/// to be placed in the same transaction before `approve_with_signatures`
+pub fn ed25519_approval(
+    signatures: &[(Pubkey, [u8; ED25519_SIGNATURE_SIZE])],
+    multisig_pubkey: &Pubkey,
+    transaction_pubkey: &Pubkey,
+    transaction: &Transaction,
+) -> Instruction {
+    let message = get_approval_message(
+        multisig_pubkey,
+        transaction_pubkey,
+        transaction.revision,
+        &transaction.get_instruction_hash(),
+    );
+
+    Instruction {
+        program_id: ed25519_program::id(),
+        accounts: vec![],
+        data: pack_ed25519_instruction_data(signatures, &message),
+    }
+}
+
+/// Secp256k1 program instruction checking Ethereum owner signatures over the
+/// EIP-191 approval message, to be placed at `instruction_index` of the same
+/// transaction as `approve_with_signatures`
+pub fn secp256k1_approval(
+    signatures: &[EthSignature],
+    multisig_pubkey: &Pubkey,
+    transaction_pubkey: &Pubkey,
+    transaction: &Transaction,
+    instruction_index: u8,
+) -> Instruction {
+    let message = get_eth_approval_message(
+        multisig_pubkey,
+        transaction_pubkey,
+        transaction.revision,
+        &transaction.get_instruction_hash(),
+    );
+
+    let signatures = signatures
+        .iter()
+        .map(|signature| {
+            let mut rs = [0; SECP256K1_SIGNATURE_SIZE];
+            rs[..32].copy_from_slice(&signature.r);
+            rs[32..].copy_from_slice(&signature.s);
+
+            // Accept both raw and Ethereum style (27/28) recovery ids
+            let recovery_id = match signature.v {
+                v @ 27..=28 => v - 27,
+                v => v,
+            };
+
+            (signature.address, rs, recovery_id)
+        })
+        .collect::<Vec<_>>();
+
+    Instruction {
+        program_id: secp256k1_program::id(),
+        accounts: vec![],
+        data: pack_secp256k1_instruction_data(&signatures, &message, instruction_index),
+    }
+}
+
 pub fn execute_transaction(
+    executor_pubkey: &Pubkey,
     multisig_pubkey: &Pubkey,
     transaction_pubkey: &Pubkey,
     accs: Vec<TransactionAccount>,
@@ -118,12 +563,19 @@ pub fn execute_transaction(
     let mut accounts = vec![
         AccountMeta::new(*multisig_pubkey, false),
         AccountMeta::new(*transaction_pubkey, false),
+        AccountMeta::new_readonly(*executor_pubkey, true),
     ];
 
+    let vault_pubkey = get_vault_address(multisig_pubkey);
+
     for account in accs {
+        // Multisig and vault signatures are provided by the program itself
+        let is_signer = account.is_signer
+            && account.pubkey != *multisig_pubkey
+            && account.pubkey != vault_pubkey;
         let account_meta = match account.is_writable {
-            true => AccountMeta::new(account.pubkey, false),
-            false => AccountMeta::new_readonly(account.pubkey, false),
+            true => AccountMeta::new(account.pubkey, is_signer),
+            false => AccountMeta::new_readonly(account.pubkey, is_signer),
         };
         accounts.push(account_meta);
     }
@@ -139,6 +591,82 @@ pub fn execute_transaction(
     }
 }
 
+/// Accounts of the transaction to pass to `execute_transaction`, including the
+/// lookup tables it references and the accounts resolved through them
+pub fn get_execute_accounts(transaction: &Transaction) -> Vec<TransactionAccount> {
+    let mut accounts = transaction.get_accounts();
+
+    accounts.extend(
+        transaction
+            .lookup_tables
+            .iter()
+            .map(|table| TransactionAccount {
+                pubkey: *table,
+                is_signer: false,
+                is_writable: false,
+            }),
+    );
+
+    accounts
+}
+
+/// Same accounts as `execute_transaction`, so the check covers them as well
+pub fn check_transaction(
+    executor_pubkey: &Pubkey,
+    multisig_pubkey: &Pubkey,
+    transaction_pubkey: &Pubkey,
+    accs: Vec<TransactionAccount>,
+) -> Instruction {
+    let mut ix = execute_transaction(executor_pubkey, multisig_pubkey, transaction_pubkey, accs);
+
+    ix.data = MultisigInstruction::CheckTransaction
+        .try_to_vec()
+        .expect("pack");
+
+    ix
+}
+
+/// Decode `CheckTransaction` return data
+pub fn decode_transaction_status(data: &[u8]) -> std::io::Result<TransactionStatus> {
+    TransactionStatus::try_from_slice(data)
+}
+
+/// V0 message executing a transaction, with its lookup accounts and any other
+/// account found in the lookup tables passed through the tables.
+///
+/// `lookup_tables` must contain every table the transaction references.
+pub fn execute_transaction_message(
+    executor_pubkey: &Pubkey,
+    multisig_pubkey: &Pubkey,
+    transaction_pubkey: &Pubkey,
+    transaction: &Transaction,
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+) -> Result<VersionedMessage, ProgramError> {
+    if !transaction
+        .lookup_tables
+        .iter()
+        .all(|table| lookup_tables.iter().any(|t| t.key == *table))
+    {
+        return Err(MultisigError::InvalidLookupAccount.into());
+    }
+
+    let mut ix = execute_transaction(
+        executor_pubkey,
+        multisig_pubkey,
+        transaction_pubkey,
+        get_execute_accounts(transaction),
+    );
+
+    ix.accounts
+        .push(AccountMeta::new_readonly(transaction.program_id, false));
+
+    let message = v0::Message::try_compile(executor_pubkey, &[ix], lookup_tables, recent_blockhash)
+        .map_err(|_| ProgramError::InvalidArgument)?;
+
+    Ok(VersionedMessage::V0(message))
+}
+
 pub fn delete_pending_transaction(
     multisig_pubkey: &Pubkey,
     pending_transaction: Pubkey,
@@ -149,6 +677,62 @@ pub fn delete_pending_transaction(
     .try_to_vec()
     .expect("pack");
 
+    Instruction {
+        program_id: id(),
+        accounts: vec![
+            AccountMeta::new(*multisig_pubkey, true),
+            AccountMeta::new(pending_transaction, false),
+        ],
+        data,
+    }
+}
+
+pub fn resize(multisig_pubkey: &Pubkey, funder_pubkey: &Pubkey, max_signers: u32) -> Instruction {
+    let data = MultisigInstruction::Resize { max_signers }
+        .try_to_vec()
+        .expect("pack");
+
+    let recovery_pubkey = get_recovery_address(multisig_pubkey);
+
+    Instruction {
+        program_id: id(),
+        accounts: vec![
+            AccountMeta::new(*multisig_pubkey, true),
+            AccountMeta::new(*funder_pubkey, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new(recovery_pubkey, false),
+        ],
+        data,
+    }
+}
+
+pub fn add_allowed_program(
+    multisig_pubkey: &Pubkey,
+    funder_pubkey: &Pubkey,
+    program_id: Pubkey,
+) -> Instruction {
+    let data = MultisigInstruction::AddAllowedProgram { program_id }
+        .try_to_vec()
+        .expect("pack");
+
+    Instruction {
+        program_id: id(),
+        accounts: vec![
+            AccountMeta::new(*multisig_pubkey, true),
+            AccountMeta::new(*funder_pubkey, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data,
+    }
+}
+
+pub fn remove_allowed_program(multisig_pubkey: &Pubkey, program_id: Pubkey) -> Instruction {
+    let data = MultisigInstruction::RemoveAllowedProgram { program_id }
+        .try_to_vec()
+        .expect("pack");
+
     Instruction {
         program_id: id(),
         accounts: vec![AccountMeta::new(*multisig_pubkey, true)],
@@ -156,10 +740,387 @@ pub fn delete_pending_transaction(
     }
 }
 
+pub fn update_action_thresholds(
+    multisig_pubkey: &Pubkey,
+    admin_threshold: Option<u64>,
+    upgrade_threshold: Option<u64>,
+) -> Instruction {
+    let data = MultisigInstruction::UpdateActionThresholds {
+        admin_threshold,
+        upgrade_threshold,
+    }
+    .try_to_vec()
+    .expect("pack");
+
+    Instruction {
+        program_id: id(),
+        accounts: vec![AccountMeta::new(*multisig_pubkey, true)],
+        data,
+    }
+}
+
+pub fn configure_recovery(
+    multisig_pubkey: &Pubkey,
+    funder_pubkey: &Pubkey,
+    guardians: Vec<Pubkey>,
+    guardian_threshold: u64,
+    delay: i64,
+) -> Instruction {
+    let recovery_pubkey = get_recovery_address(multisig_pubkey);
+
+    let data = MultisigInstruction::ConfigureRecovery {
+        guardians,
+        guardian_threshold,
+        delay,
+    }
+    .try_to_vec()
+    .expect("pack");
+
+    Instruction {
+        program_id: id(),
+        accounts: vec![
+            AccountMeta::new(*multisig_pubkey, true),
+            AccountMeta::new(*funder_pubkey, true),
+            AccountMeta::new(recovery_pubkey, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data,
+    }
+}
+
+pub fn start_recovery(
+    multisig_pubkey: &Pubkey,
+    guardians: &[Pubkey],
+    owners: Vec<Pubkey>,
+    threshold: u64,
+) -> Instruction {
+    let recovery_pubkey = get_recovery_address(multisig_pubkey);
+
+    let data = MultisigInstruction::StartRecovery { owners, threshold }
+        .try_to_vec()
+        .expect("pack");
+
+    let mut accounts = vec![
+        AccountMeta::new(recovery_pubkey, false),
+        AccountMeta::new_readonly(*multisig_pubkey, false),
+    ];
+    accounts.extend(
+        guardians
+            .iter()
+            .map(|guardian| AccountMeta::new_readonly(*guardian, true)),
+    );
+
+    Instruction {
+        program_id: id(),
+        accounts,
+        data,
+    }
+}
+
+pub fn veto_recovery(multisig_pubkey: &Pubkey, owner_pubkey: &Pubkey) -> Instruction {
+    let recovery_pubkey = get_recovery_address(multisig_pubkey);
+
+    let data = MultisigInstruction::VetoRecovery
+        .try_to_vec()
+        .expect("pack");
+
+    Instruction {
+        program_id: id(),
+        accounts: vec![
+            AccountMeta::new(recovery_pubkey, false),
+            AccountMeta::new_readonly(*multisig_pubkey, false),
+            AccountMeta::new_readonly(*owner_pubkey, true),
+        ],
+        data,
+    }
+}
+
+pub fn finalize_recovery(multisig_pubkey: &Pubkey) -> Instruction {
+    let recovery_pubkey = get_recovery_address(multisig_pubkey);
+
+    let data = MultisigInstruction::FinalizeRecovery
+        .try_to_vec()
+        .expect("pack");
+
+    Instruction {
+        program_id: id(),
+        accounts: vec![
+            AccountMeta::new(recovery_pubkey, false),
+            AccountMeta::new(*multisig_pubkey, false),
+        ],
+        data,
+    }
+}
+
+pub fn close_multisig(
+    multisig_pubkey: &Pubkey,
+    recipient_pubkey: &Pubkey,
+    vaults: Vec<Pubkey>,
+) -> Instruction {
+    let recovery_pubkey = get_recovery_address(multisig_pubkey);
+
+    let mut accounts = vec![
+        AccountMeta::new(*multisig_pubkey, true),
+        AccountMeta::new(*recipient_pubkey, false),
+        AccountMeta::new(recovery_pubkey, false),
+        AccountMeta::new_readonly(get_vault_address(multisig_pubkey), false),
+    ];
+
+    for vault in vaults {
+        accounts.push(AccountMeta::new_readonly(vault, false));
+    }
+
+    let data = MultisigInstruction::CloseMultisig
+        .try_to_vec()
+        .expect("pack");
+
+    Instruction {
+        program_id: id(),
+        accounts,
+        data,
+    }
+}
+
+pub fn migrate_multisig(multisig_pubkey: &Pubkey) -> Instruction {
+    let data = MultisigInstruction::MigrateMultisig
+        .try_to_vec()
+        .expect("pack");
+
+    Instruction {
+        program_id: id(),
+        accounts: vec![AccountMeta::new(*multisig_pubkey, false)],
+        data,
+    }
+}
+
+pub fn set_program_authority(
+    current_authority_pubkey: &Pubkey,
+    new_authority_pubkey: &Pubkey,
+    program_pubkey: &Pubkey,
+) -> Instruction {
+    bpf_loader_upgradeable::set_upgrade_authority(
+        program_pubkey,
+        current_authority_pubkey,
+        Some(new_authority_pubkey),
+    )
+}
+
+pub fn upgrade_program(
+    program_pubkey: &Pubkey,
+    buffer_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    spill_pubkey: &Pubkey,
+) -> Instruction {
+    bpf_loader_upgradeable::upgrade(
+        program_pubkey,
+        buffer_pubkey,
+        authority_pubkey,
+        spill_pubkey,
+    )
+}
+
+pub fn close_program(
+    program_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    recipient_pubkey: &Pubkey,
+) -> Instruction {
+    let program_data_pubkey =
+        Pubkey::find_program_address(&[program_pubkey.as_ref()], &bpf_loader_upgradeable::id()).0;
+
+    bpf_loader_upgradeable::close_any(
+        &program_data_pubkey,
+        recipient_pubkey,
+        Some(authority_pubkey),
+        Some(program_pubkey),
+    )
+}
+
+pub fn close_buffer(
+    buffer_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    recipient_pubkey: &Pubkey,
+) -> Instruction {
+    bpf_loader_upgradeable::close_any(
+        buffer_pubkey,
+        recipient_pubkey,
+        Some(authority_pubkey),
+        None,
+    )
+}
+
 pub fn get_multisig_address(seed: u128) -> Pubkey {
     Pubkey::find_program_address(&[br"multisig", &seed.to_le_bytes()], &id()).0
 }
 
-pub fn get_transaction_address(seed: u128) -> Pubkey {
-    Pubkey::find_program_address(&[br"transaction", &seed.to_le_bytes()], &id()).0
+/// Multisig seed derived from a human readable label, e.g. `bridge-ops-mainnet`.
+/// The namespace keeps labels of different deployments or teams apart.
+///
+/// Derived seeds are not bound to whoever creates the multisig, so anyone can
+/// front-run the creation with their own owners. Check the owners and the
+/// threshold, e.g. through `find_multisig_label`, before relying on it.
+pub fn get_multisig_seed_from_label(namespace: &str, label: &str) -> u128 {
+    get_multisig_seed(&[
+        br"label",
+        &(namespace.len() as u32).to_le_bytes(),
+        namespace.as_bytes(),
+        label.as_bytes(),
+    ])
+}
+
+/// Multisig seed derived from the creator key and a nonce chosen by the creator.
+///
+/// The creator does not have to sign the creation, so the same front-running
+/// caveat as for `get_multisig_seed_from_label` applies.
+pub fn get_multisig_seed_from_creator(creator_pubkey: &Pubkey, nonce: u64) -> u128 {
+    get_multisig_seed(&[br"creator", creator_pubkey.as_ref(), &nonce.to_le_bytes()])
+}
+
+/// First of the candidate labels the multisig seed was derived from, if the
+/// multisig has the expected owners and threshold
+pub fn find_multisig_label<'a, I>(
+    multisig: &Multisig,
+    owners: &[Pubkey],
+    threshold: u64,
+    namespace: &str,
+    labels: I,
+) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    if !has_members(multisig, owners, threshold) {
+        return None;
+    }
+
+    labels
+        .into_iter()
+        .find(|label| get_multisig_seed_from_label(namespace, label) == multisig.seed)
+}
+
+/// Nonce below `max_nonce`, capped at `MAX_MULTISIG_CREATOR_NONCE`, the
+/// multisig seed was derived from along with the creator key, if the multisig
+/// has the expected owners and threshold
+pub fn find_multisig_creator_nonce(
+    multisig: &Multisig,
+    owners: &[Pubkey],
+    threshold: u64,
+    creator_pubkey: &Pubkey,
+    max_nonce: u64,
+) -> Option<u64> {
+    if !has_members(multisig, owners, threshold) {
+        return None;
+    }
+
+    (0..max_nonce.min(MAX_MULTISIG_CREATOR_NONCE))
+        .find(|nonce| get_multisig_seed_from_creator(creator_pubkey, *nonce) == multisig.seed)
+}
+
+/// Whether the multisig has exactly the given owners, in any order, and threshold
+fn has_members(multisig: &Multisig, owners: &[Pubkey], threshold: u64) -> bool {
+    multisig.threshold == threshold
+        && multisig.owners.len() == owners.len()
+        && owners.iter().all(|owner| multisig.owners.contains(owner))
+}
+
+pub fn get_transaction_address(multisig_pubkey: &Pubkey, transaction_index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            br"transaction",
+            multisig_pubkey.as_ref(),
+            &transaction_index.to_le_bytes(),
+        ],
+        &id(),
+    )
+    .0
+}
+
+pub fn get_recovery_address(multisig_pubkey: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[br"recovery", multisig_pubkey.as_ref()], &id()).0
+}
+
+/// System owned account the multisig signs for on execution, meant to hold
+/// lamports since the multisig account itself can not send them
+pub fn get_vault_address(multisig_pubkey: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[br"vault", multisig_pubkey.as_ref()], &id()).0
+}
+
+pub fn get_transaction_buffer_address(
+    multisig_pubkey: &Pubkey,
+    proposer_pubkey: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            br"transaction_buffer",
+            multisig_pubkey.as_ref(),
+            proposer_pubkey.as_ref(),
+        ],
+        &id(),
+    )
+    .0
+}
+
+/// Addresses of all transactions created so far, where `transaction_index`
+/// is the value stored in the multisig account
+pub fn get_transaction_addresses(multisig_pubkey: &Pubkey, transaction_index: u64) -> Vec<Pubkey> {
+    (1..=transaction_index)
+        .map(|index| get_transaction_address(multisig_pubkey, index))
+        .collect()
+}
+
+/// Lifecycle state of a transaction along with who approved it
+pub fn get_proposal_info(multisig: &Multisig, transaction: &Transaction) -> ProposalInfo {
+    ProposalInfo {
+        status: transaction.get_status(multisig),
+        approvers: transaction.get_approvers(multisig),
+        missing_owners: transaction.get_missing_owners(multisig),
+        threshold: multisig.get_threshold(&transaction.program_id, &transaction.data),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ProposalInfo {
+    pub status: ProposalStatus,
+    pub approvers: Vec<Pubkey>,
+    pub missing_owners: Vec<Pubkey>,
+    pub threshold: u64,
+}
+
+/// Ethereum signature in r/s/v form
+#[derive(Debug, Clone, Copy)]
+pub struct EthSignature {
+    pub address: [u8; ETH_ADDRESS_SIZE],
+    pub r: [u8; 32],
+    pub s: [u8; 32],
+    pub v: u8,
+}
+
+/// Domain separated hash of the seed parts truncated to 128 bits
+fn get_multisig_seed(parts: &[&[u8]]) -> u128 {
+    let hash = hash::hashv(&[&[br"multisig-seed".as_slice()], parts].concat());
+
+    let mut seed = [0; 16];
+    seed.copy_from_slice(&hash.as_ref()[..16]);
+
+    u128::from_le_bytes(seed)
+}
+
+/// Accounts of the instruction as stored in a transaction, followed by the
+/// program account so that the transaction can be executed through a CPI
+fn get_transaction_accounts(ix: &Instruction) -> Vec<TransactionAccount> {
+    let mut accounts = ix
+        .accounts
+        .iter()
+        .map(|acc| TransactionAccount {
+            pubkey: acc.pubkey,
+            is_signer: acc.is_signer,
+            is_writable: acc.is_writable,
+        })
+        .collect::<Vec<_>>();
+    accounts.push(TransactionAccount {
+        pubkey: ix.program_id,
+        is_signer: false,
+        is_writable: false,
+    });
+
+    accounts
 }
@@ -0,0 +1,329 @@
+use std::str::FromStr;
+
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use solana_program::pubkey::Pubkey;
+
+use crate::*;
+
+/// Value of the `format` field of every proposal bundle
+pub const PROPOSAL_BUNDLE_FORMAT: &str = "octusbridge-multisig-proposal";
+pub const PROPOSAL_BUNDLE_VERSION: u32 = 1;
+/// Prefix of the compact form, followed by the version and base64 data
+pub const PROPOSAL_BUNDLE_COMPACT_PREFIX: &str = "msigprop";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProposalBundleError {
+    #[error("Invalid proposal bundle JSON: {0}")]
+    Json(String),
+    #[error("Invalid proposal bundle encoding")]
+    Encoding,
+    #[error("Not a proposal bundle")]
+    UnknownFormat,
+    #[error("Unsupported proposal bundle version {0}")]
+    UnsupportedVersion(u32),
+    #[error("Proposal bundle {0} does not match its content")]
+    Mismatch(&'static str),
+}
+
+/// Proposal as handed to an offline custodian for review and signing.
+///
+/// Keys are base58 and binary data is base64. Everything but the raw
+/// proposal is derived from it and checked by `verify_proposal_bundle`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposalBundle {
+    pub format: String,
+    pub version: u32,
+    pub multisig: String,
+    pub transaction: String,
+    pub index: u64,
+    pub revision: u64,
+    pub program_id: String,
+    pub data: String,
+    pub lookup_tables: Vec<String>,
+    pub lookup_accounts: Vec<TransactionLookupAccount>,
+    /// Addresses the lookup accounts resolved to when the instruction was
+    /// proposed, also listed last in `instruction.accounts`
+    pub lookup_addresses: Vec<String>,
+    pub instruction: DecodedInstruction,
+    /// Hash of the raw instruction the message commits to, see
+    /// `get_instruction_hash`
+    pub instruction_hash: String,
+    pub approvals: Vec<ProposalApproval>,
+    pub threshold: u64,
+    pub did_execute: bool,
+    pub is_cancelled: bool,
+    /// Message owners sign to approve the proposal, see `get_approval_message`
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposalApproval {
+    pub owner: String,
+    pub approved: bool,
+}
+
+/// Raw proposal a bundle is derived from, serialized as is in the compact form
+#[derive(BorshSerialize, BorshDeserialize)]
+struct ProposalBundleData {
+    multisig: Pubkey,
+    index: u64,
+    revision: u64,
+    program_id: Pubkey,
+    accounts: Vec<TransactionAccount>,
+    data: Vec<u8>,
+    lookup_tables: Vec<Pubkey>,
+    lookup_accounts: Vec<TransactionLookupAccount>,
+    lookup_addresses: Vec<Pubkey>,
+    owners: Vec<Pubkey>,
+    signers: Vec<bool>,
+    threshold: u64,
+    did_execute: bool,
+    is_cancelled: bool,
+}
+
+impl From<ProposalBundleData> for ProposalBundle {
+    fn from(raw: ProposalBundleData) -> Self {
+        let transaction_pubkey = get_transaction_address(&raw.multisig, raw.index);
+
+        let accounts = raw
+            .accounts
+            .iter()
+            .cloned()
+            .chain(raw.lookup_addresses.iter().zip(&raw.lookup_accounts).map(
+                |(pubkey, account)| TransactionAccount {
+                    pubkey: *pubkey,
+                    is_signer: false,
+                    is_writable: account.is_writable,
+                },
+            ))
+            .collect::<Vec<_>>();
+
+        let instruction = decode_instruction(&raw.program_id, &accounts, &raw.data);
+        let instruction_hash = get_instruction_hash(
+            &raw.program_id,
+            &raw.accounts,
+            &raw.data,
+            &raw.lookup_tables,
+            &raw.lookup_accounts,
+            &raw.lookup_addresses,
+        );
+        let message = get_approval_message(
+            &raw.multisig,
+            &transaction_pubkey,
+            raw.revision,
+            &instruction_hash,
+        );
+
+        let approvals = raw
+            .owners
+            .iter()
+            .enumerate()
+            .map(|(i, owner)| ProposalApproval {
+                owner: owner.to_string(),
+                approved: raw.signers.get(i).copied().unwrap_or_default(),
+            })
+            .collect();
+
+        ProposalBundle {
+            format: PROPOSAL_BUNDLE_FORMAT.to_string(),
+            version: PROPOSAL_BUNDLE_VERSION,
+            multisig: raw.multisig.to_string(),
+            transaction: transaction_pubkey.to_string(),
+            index: raw.index,
+            revision: raw.revision,
+            program_id: raw.program_id.to_string(),
+            data: STANDARD.encode(&raw.data),
+            lookup_tables: raw.lookup_tables.iter().map(Pubkey::to_string).collect(),
+            lookup_accounts: raw.lookup_accounts,
+            lookup_addresses: raw.lookup_addresses.iter().map(Pubkey::to_string).collect(),
+            instruction,
+            instruction_hash: STANDARD.encode(instruction_hash),
+            approvals,
+            threshold: raw.threshold,
+            did_execute: raw.did_execute,
+            is_cancelled: raw.is_cancelled,
+            message: STANDARD.encode(message),
+        }
+    }
+}
+
+impl TryFrom<&ProposalBundle> for ProposalBundleData {
+    type Error = ProposalBundleError;
+
+    fn try_from(bundle: &ProposalBundle) -> Result<Self, Self::Error> {
+        let parse_pubkey =
+            |value: &str| Pubkey::from_str(value).map_err(|_| ProposalBundleError::Encoding);
+
+        // Lookup accounts are listed after the static ones
+        let static_len = bundle
+            .instruction
+            .accounts
+            .len()
+            .checked_sub(bundle.lookup_addresses.len())
+            .ok_or(ProposalBundleError::Encoding)?;
+
+        let accounts = bundle.instruction.accounts[..static_len]
+            .iter()
+            .map(|account| {
+                Ok(TransactionAccount {
+                    pubkey: parse_pubkey(&account.pubkey)?,
+                    is_signer: account.is_signer,
+                    is_writable: account.is_writable,
+                })
+            })
+            .collect::<Result<_, ProposalBundleError>>()?;
+
+        Ok(ProposalBundleData {
+            multisig: parse_pubkey(&bundle.multisig)?,
+            index: bundle.index,
+            revision: bundle.revision,
+            program_id: parse_pubkey(&bundle.program_id)?,
+            accounts,
+            data: STANDARD
+                .decode(&bundle.data)
+                .map_err(|_| ProposalBundleError::Encoding)?,
+            lookup_tables: bundle
+                .lookup_tables
+                .iter()
+                .map(|table| parse_pubkey(table))
+                .collect::<Result<_, _>>()?,
+            lookup_accounts: bundle.lookup_accounts.clone(),
+            lookup_addresses: bundle
+                .lookup_addresses
+                .iter()
+                .map(|address| parse_pubkey(address))
+                .collect::<Result<_, _>>()?,
+            owners: bundle
+                .approvals
+                .iter()
+                .map(|approval| parse_pubkey(&approval.owner))
+                .collect::<Result<_, _>>()?,
+            signers: bundle
+                .approvals
+                .iter()
+                .map(|approval| approval.approved)
+                .collect(),
+            threshold: bundle.threshold,
+            did_execute: bundle.did_execute,
+            is_cancelled: bundle.is_cancelled,
+        })
+    }
+}
+
+/// Bundle a proposal with the current state of its multisig
+pub fn create_proposal_bundle(multisig: &Multisig, transaction: &Transaction) -> ProposalBundle {
+    ProposalBundleData {
+        multisig: transaction.multisig,
+        index: transaction.index,
+        revision: transaction.revision,
+        program_id: transaction.program_id,
+        accounts: transaction.accounts.clone(),
+        data: transaction.data.clone(),
+        lookup_tables: transaction.lookup_tables.clone(),
+        lookup_accounts: transaction.lookup_accounts.clone(),
+        lookup_addresses: transaction.lookup_addresses.clone(),
+        owners: multisig.owners.clone(),
+        signers: transaction.signers.clone(),
+        threshold: multisig.get_threshold(&transaction.program_id, &transaction.data),
+        did_execute: transaction.did_execute,
+        is_cancelled: transaction.is_cancelled,
+    }
+    .into()
+}
+
+pub fn serialize_proposal_bundle(bundle: &ProposalBundle) -> String {
+    serde_json::to_string_pretty(bundle).expect("serialize")
+}
+
+/// Compact single-line form, suitable for QR codes
+pub fn serialize_proposal_bundle_compact(
+    bundle: &ProposalBundle,
+) -> Result<String, ProposalBundleError> {
+    let data = ProposalBundleData::try_from(bundle)?
+        .try_to_vec()
+        .map_err(|_| ProposalBundleError::Encoding)?;
+
+    Ok(format!(
+        "{}{}:{}",
+        PROPOSAL_BUNDLE_COMPACT_PREFIX,
+        bundle.version,
+        URL_SAFE_NO_PAD.encode(data)
+    ))
+}
+
+/// Parse a bundle in either form and verify it
+pub fn parse_proposal_bundle(input: &str) -> Result<ProposalBundle, ProposalBundleError> {
+    let input = input.trim();
+
+    let bundle = match input.strip_prefix(PROPOSAL_BUNDLE_COMPACT_PREFIX) {
+        Some(compact) => {
+            let (version, data) = compact
+                .split_once(':')
+                .ok_or(ProposalBundleError::UnknownFormat)?;
+
+            let version = u32::from_str(version).map_err(|_| ProposalBundleError::UnknownFormat)?;
+            if version != PROPOSAL_BUNDLE_VERSION {
+                return Err(ProposalBundleError::UnsupportedVersion(version));
+            }
+
+            let data = URL_SAFE_NO_PAD
+                .decode(data)
+                .map_err(|_| ProposalBundleError::Encoding)?;
+            let raw = ProposalBundleData::try_from_slice(&data)
+                .map_err(|_| ProposalBundleError::Encoding)?;
+
+            ProposalBundle::from(raw)
+        }
+        None => {
+            serde_json::from_str(input).map_err(|e| ProposalBundleError::Json(e.to_string()))?
+        }
+    };
+
+    verify_proposal_bundle(&bundle)?;
+
+    Ok(bundle)
+}
+
+/// Check that everything derived from the raw proposal matches it, so that
+/// the decoded instruction and the message to sign can be trusted.
+///
+/// The message commits to the raw instruction through its hash, so a
+/// signature over it only approves the instruction shown. Approvals and the
+/// threshold are informational and not covered by it.
+pub fn verify_proposal_bundle(bundle: &ProposalBundle) -> Result<(), ProposalBundleError> {
+    if bundle.format != PROPOSAL_BUNDLE_FORMAT {
+        return Err(ProposalBundleError::UnknownFormat);
+    }
+
+    if bundle.version != PROPOSAL_BUNDLE_VERSION {
+        return Err(ProposalBundleError::UnsupportedVersion(bundle.version));
+    }
+
+    let expected = ProposalBundle::from(ProposalBundleData::try_from(bundle)?);
+
+    if bundle.transaction != expected.transaction {
+        return Err(ProposalBundleError::Mismatch("transaction"));
+    }
+
+    if bundle.instruction != expected.instruction {
+        return Err(ProposalBundleError::Mismatch("instruction"));
+    }
+
+    if bundle.instruction_hash != expected.instruction_hash {
+        return Err(ProposalBundleError::Mismatch("instruction hash"));
+    }
+
+    if bundle.message != expected.message {
+        return Err(ProposalBundleError::Mismatch("message"));
+    }
+
+    // Catches non-canonical encodings of the same content
+    if *bundle != expected {
+        return Err(ProposalBundleError::Mismatch("encoding"));
+    }
+
+    Ok(())
+}
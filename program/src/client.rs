@@ -0,0 +1,242 @@
+use async_trait::async_trait;
+use solana_banks_client::{BanksClient, BanksClientError};
+use solana_program::hash::Hash;
+use solana_program::instruction::Instruction;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_rpc_client_api::client_error::Error as RpcClientError;
+use solana_sdk::account::Account;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction as SignedTransaction;
+
+use crate::*;
+
+#[derive(Debug, thiserror::Error)]
+pub enum MultisigClientError {
+    #[error("RPC error: {0}")]
+    Rpc(Box<RpcClientError>),
+    #[error("Banks error: {0}")]
+    Banks(Box<BanksClientError>),
+    #[error("Program error: {0}")]
+    Program(#[from] ProgramError),
+    #[error("Account {0} not found")]
+    AccountNotFound(Pubkey),
+}
+
+impl From<RpcClientError> for MultisigClientError {
+    fn from(e: RpcClientError) -> Self {
+        Self::Rpc(Box::new(e))
+    }
+}
+
+impl From<BanksClientError> for MultisigClientError {
+    fn from(e: BanksClientError) -> Self {
+        Self::Banks(Box::new(e))
+    }
+}
+
+/// Minimal set of node requests the client needs
+#[async_trait]
+pub trait MultisigBackend {
+    async fn get_latest_blockhash(&mut self) -> Result<Hash, MultisigClientError>;
+
+    /// Send the transaction and wait until it is confirmed or rejected
+    async fn send_transaction(
+        &mut self,
+        transaction: SignedTransaction,
+    ) -> Result<(), MultisigClientError>;
+
+    async fn get_account(
+        &mut self,
+        pubkey: &Pubkey,
+    ) -> Result<Option<Account>, MultisigClientError>;
+}
+
+#[async_trait]
+impl MultisigBackend for RpcClient {
+    async fn get_latest_blockhash(&mut self) -> Result<Hash, MultisigClientError> {
+        Ok(RpcClient::get_latest_blockhash(self).await?)
+    }
+
+    async fn send_transaction(
+        &mut self,
+        transaction: SignedTransaction,
+    ) -> Result<(), MultisigClientError> {
+        self.send_and_confirm_transaction(&transaction).await?;
+        Ok(())
+    }
+
+    async fn get_account(
+        &mut self,
+        pubkey: &Pubkey,
+    ) -> Result<Option<Account>, MultisigClientError> {
+        let response = self
+            .get_account_with_commitment(pubkey, self.commitment())
+            .await?;
+        Ok(response.value)
+    }
+}
+
+#[async_trait]
+impl MultisigBackend for BanksClient {
+    async fn get_latest_blockhash(&mut self) -> Result<Hash, MultisigClientError> {
+        Ok(BanksClient::get_latest_blockhash(self).await?)
+    }
+
+    async fn send_transaction(
+        &mut self,
+        transaction: SignedTransaction,
+    ) -> Result<(), MultisigClientError> {
+        self.process_transaction(transaction).await?;
+        Ok(())
+    }
+
+    async fn get_account(
+        &mut self,
+        pubkey: &Pubkey,
+    ) -> Result<Option<Account>, MultisigClientError> {
+        Ok(BanksClient::get_account(self, *pubkey).await?)
+    }
+}
+
+/// Builds, signs and sends multisig instructions, with `payer` paying fees
+/// and rent of the created accounts
+pub struct MultisigClient<B> {
+    backend: B,
+    payer: Keypair,
+}
+
+impl<B: MultisigBackend + Send> MultisigClient<B> {
+    pub fn new(backend: B, payer: Keypair) -> Self {
+        Self { backend, payer }
+    }
+
+    pub fn backend(&mut self) -> &mut B {
+        &mut self.backend
+    }
+
+    pub fn payer(&self) -> &Keypair {
+        &self.payer
+    }
+
+    /// Send instructions in a single transaction signed by the payer and `signers`
+    pub async fn send(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), MultisigClientError> {
+        let recent_blockhash = self.backend.get_latest_blockhash().await?;
+
+        let mut transaction =
+            SignedTransaction::new_with_payer(instructions, Some(&self.payer.pubkey()));
+        transaction.sign(&[&[&self.payer], signers].concat(), recent_blockhash);
+
+        self.backend.send_transaction(transaction).await
+    }
+
+    /// Create a multisig and return its address
+    pub async fn create_multisig(
+        &mut self,
+        seed: u128,
+        owners: Vec<Pubkey>,
+        threshold: u64,
+        max_signers: u32,
+    ) -> Result<Pubkey, MultisigClientError> {
+        let ix = create_multisig(&self.payer.pubkey(), seed, owners, threshold, max_signers);
+        self.send(&[ix], &[]).await?;
+
+        Ok(get_multisig_address(seed))
+    }
+
+    /// Propose the instruction to be executed by the multisig and return the
+    /// address of the created transaction
+    pub async fn propose(
+        &mut self,
+        proposer: &Keypair,
+        multisig_pubkey: &Pubkey,
+        ix: Instruction,
+    ) -> Result<Pubkey, MultisigClientError> {
+        let multisig = self.get_multisig(multisig_pubkey).await?;
+        let transaction_index = multisig.transaction_index + 1;
+
+        let ix = create_transaction(
+            &self.payer.pubkey(),
+            &proposer.pubkey(),
+            multisig_pubkey,
+            transaction_index,
+            ix,
+        );
+        self.send(&[ix], &[proposer]).await?;
+
+        Ok(get_transaction_address(multisig_pubkey, transaction_index))
+    }
+
+    pub async fn approve(
+        &mut self,
+        owner: &Keypair,
+        multisig_pubkey: &Pubkey,
+        transaction_pubkey: &Pubkey,
+    ) -> Result<(), MultisigClientError> {
+        let ix = approve(&owner.pubkey(), multisig_pubkey, transaction_pubkey);
+        self.send(&[ix], &[owner]).await
+    }
+
+    /// Execute an approved transaction with the payer as the executor
+    pub async fn execute(
+        &mut self,
+        multisig_pubkey: &Pubkey,
+        transaction_pubkey: &Pubkey,
+    ) -> Result<(), MultisigClientError> {
+        let transaction = self.get_transaction(transaction_pubkey).await?;
+
+        let ix = execute_transaction(
+            &self.payer.pubkey(),
+            multisig_pubkey,
+            transaction_pubkey,
+            get_execute_accounts(&transaction),
+        );
+        self.send(&[ix], &[]).await
+    }
+
+    pub async fn get_multisig(
+        &mut self,
+        multisig_pubkey: &Pubkey,
+    ) -> Result<Multisig, MultisigClientError> {
+        let account = self.get_account(multisig_pubkey).await?;
+        Ok(Multisig::unpack(&account.data)?)
+    }
+
+    pub async fn get_transaction(
+        &mut self,
+        transaction_pubkey: &Pubkey,
+    ) -> Result<Transaction, MultisigClientError> {
+        let account = self.get_account(transaction_pubkey).await?;
+        Ok(Transaction::unpack_from_slice(&account.data)?)
+    }
+
+    /// All transactions ever proposed to the multisig, oldest first
+    pub async fn get_proposals(
+        &mut self,
+        multisig_pubkey: &Pubkey,
+    ) -> Result<Vec<(Pubkey, Transaction)>, MultisigClientError> {
+        let multisig = self.get_multisig(multisig_pubkey).await?;
+
+        let mut proposals = Vec::new();
+        for transaction_pubkey in
+            get_transaction_addresses(multisig_pubkey, multisig.transaction_index)
+        {
+            let transaction = self.get_transaction(&transaction_pubkey).await?;
+            proposals.push((transaction_pubkey, transaction));
+        }
+
+        Ok(proposals)
+    }
+
+    async fn get_account(&mut self, pubkey: &Pubkey) -> Result<Account, MultisigClientError> {
+        self.backend
+            .get_account(pubkey)
+            .await?
+            .ok_or(MultisigClientError::AccountNotFound(*pubkey))
+    }
+}
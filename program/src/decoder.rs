@@ -0,0 +1,674 @@
+use std::fmt::Write;
+
+use borsh::BorshDeserialize;
+use serde::{Deserialize, Serialize};
+use solana_program::loader_upgradeable_instruction::UpgradeableLoaderInstruction;
+use solana_program::program_utils::limited_deserialize;
+use solana_program::pubkey::{Pubkey, PUBKEY_BYTES};
+use solana_program::system_instruction::SystemInstruction;
+use solana_program::{bpf_loader_upgradeable, pubkey, system_program};
+
+use crate::*;
+
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+pub const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+pub const MEMO_V1_PROGRAM_ID: Pubkey = pubkey!("Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo");
+
+/// Instruction rendered for review. Instructions of unknown programs, or
+/// that fail to decode, have no name and keep their data as hex
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodedInstruction {
+    pub program_id: String,
+    pub program: Option<String>,
+    pub name: Option<String>,
+    pub fields: Vec<DecodedField>,
+    pub accounts: Vec<DecodedAccount>,
+    pub data: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodedField {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodedAccount {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    pub role: Option<String>,
+}
+
+impl DecodedInstruction {
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| field.value.as_str())
+    }
+}
+
+/// Decode the instruction a transaction executes
+pub fn decode_transaction(transaction: &Transaction) -> DecodedInstruction {
+    decode_instruction(
+        &transaction.program_id,
+        &transaction.accounts,
+        &transaction.data,
+    )
+}
+
+pub fn decode_instruction(
+    program_id: &Pubkey,
+    accounts: &[TransactionAccount],
+    data: &[u8],
+) -> DecodedInstruction {
+    let (program, decoded) = if *program_id == crate::id() {
+        (Some("Multisig"), decode_multisig(data))
+    } else if *program_id == system_program::id() {
+        (Some("System"), decode_system(data))
+    } else if *program_id == bpf_loader_upgradeable::id() {
+        (Some("BPF Upgradeable Loader"), decode_loader(data))
+    } else if *program_id == TOKEN_PROGRAM_ID {
+        (Some("SPL Token"), decode_token(data))
+    } else if *program_id == TOKEN_2022_PROGRAM_ID {
+        (Some("SPL Token-2022"), decode_token(data))
+    } else if *program_id == MEMO_PROGRAM_ID || *program_id == MEMO_V1_PROGRAM_ID {
+        (Some("Memo"), decode_memo(data))
+    } else {
+        (None, None)
+    };
+
+    let (roles, remaining) = decoded
+        .as_ref()
+        .map(|decoded| (decoded.roles, decoded.remaining))
+        .unwrap_or_default();
+
+    let accounts = accounts
+        .iter()
+        .enumerate()
+        .map(|(i, account)| {
+            // Transactions keep the program account last
+            let role = match roles.get(i) {
+                _ if account.pubkey == *program_id => Some("program"),
+                Some(role) => Some(*role),
+                None => remaining,
+            };
+
+            DecodedAccount {
+                pubkey: account.pubkey.to_string(),
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+                role: role.map(str::to_string),
+            }
+        })
+        .collect();
+
+    let (name, fields, data) = match decoded {
+        Some(decoded) => (Some(decoded.name.to_string()), decoded.fields, None),
+        None => (None, Vec::new(), Some(to_hex(data))),
+    };
+
+    DecodedInstruction {
+        program_id: program_id.to_string(),
+        program: program.map(str::to_string),
+        name,
+        fields,
+        accounts,
+        data,
+    }
+}
+
+struct Decoded {
+    name: &'static str,
+    fields: Vec<DecodedField>,
+    roles: &'static [&'static str],
+    // Role of the accounts following `roles`
+    remaining: Option<&'static str>,
+}
+
+impl Decoded {
+    fn new(name: &'static str, roles: &'static [&'static str]) -> Self {
+        Self {
+            name,
+            fields: Vec::new(),
+            roles,
+            remaining: None,
+        }
+    }
+
+    fn field(mut self, name: &str, value: impl ToString) -> Self {
+        self.fields.push(DecodedField {
+            name: name.to_string(),
+            value: value.to_string(),
+        });
+        self
+    }
+
+    fn remaining(mut self, role: &'static str) -> Self {
+        self.remaining = Some(role);
+        self
+    }
+}
+
+const SYSTEM: &str = "system program";
+const RENT: &str = "rent sysvar";
+
+fn decode_multisig(data: &[u8]) -> Option<Decoded> {
+    let instruction = MultisigInstruction::try_from_slice(data).ok()?;
+
+    let decoded = match instruction {
+        MultisigInstruction::CreateMultisig {
+            seed,
+            owners,
+            threshold,
+            max_signers,
+        } => Decoded::new("CreateMultisig", &["funder", "multisig", SYSTEM, RENT])
+            .field("seed", seed)
+            .field("owners", join(&owners))
+            .field("threshold", threshold)
+            .field("max_signers", max_signers),
+        MultisigInstruction::AddOwner { owner } => {
+            Decoded::new("AddOwner", &["multisig"]).field("owner", owner)
+        }
+        MultisigInstruction::DeleteOwner { owner } => {
+            Decoded::new("DeleteOwner", &["multisig"]).field("owner", owner)
+        }
+        MultisigInstruction::UpdateThreshold { threshold } => {
+            Decoded::new("UpdateThreshold", &["multisig"]).field("threshold", threshold)
+        }
+        MultisigInstruction::CreateTransaction { pid, accs, data } => Decoded::new(
+            "CreateTransaction",
+            &[
+                "funder",
+                "proposer",
+                "multisig",
+                "transaction",
+                SYSTEM,
+                RENT,
+            ],
+        )
+        .field("program_id", pid)
+        .field("accounts", accs.len())
+        .field("data", to_hex(&data)),
+        MultisigInstruction::Approve => {
+            Decoded::new("Approve", &["owner", "transaction", "multisig"])
+        }
+        MultisigInstruction::ExecuteTransaction => Decoded::new(
+            "ExecuteTransaction",
+            &["multisig", "transaction", "executor"],
+        )
+        .remaining("transaction account"),
+        MultisigInstruction::DeletePendingTransaction {
+            pending_transaction,
+        } => Decoded::new(
+            "DeletePendingTransaction",
+            &["multisig", "pending transaction"],
+        )
+        .field("pending_transaction", pending_transaction),
+        MultisigInstruction::Resize { max_signers } => {
+            Decoded::new("Resize", &["multisig", "funder", SYSTEM, RENT, "recovery"])
+                .field("max_signers", max_signers)
+        }
+        MultisigInstruction::CloseMultisig => Decoded::new(
+            "CloseMultisig",
+            &["multisig", "recipient", "recovery", "vault"],
+        )
+        .remaining("token vault"),
+        MultisigInstruction::ApproveWithSignatures => Decoded::new(
+            "ApproveWithSignatures",
+            &["transaction", "multisig", "instructions sysvar"],
+        ),
+        MultisigInstruction::AddAllowedProgram { program_id } => {
+            Decoded::new("AddAllowedProgram", &["multisig", "funder", SYSTEM, RENT])
+                .field("program_id", program_id)
+        }
+        MultisigInstruction::RemoveAllowedProgram { program_id } => {
+            Decoded::new("RemoveAllowedProgram", &["multisig"]).field("program_id", program_id)
+        }
+        MultisigInstruction::UpdateActionThresholds {
+            admin_threshold,
+            upgrade_threshold,
+        } => Decoded::new("UpdateActionThresholds", &["multisig"])
+            .field("admin_threshold", optional(admin_threshold))
+            .field("upgrade_threshold", optional(upgrade_threshold)),
+        MultisigInstruction::ConfigureRecovery {
+            guardians,
+            guardian_threshold,
+            delay,
+        } => Decoded::new(
+            "ConfigureRecovery",
+            &["multisig", "funder", "recovery", SYSTEM, RENT],
+        )
+        .field("guardians", join(&guardians))
+        .field("guardian_threshold", guardian_threshold)
+        .field("delay", delay),
+        MultisigInstruction::StartRecovery { owners, threshold } => {
+            Decoded::new("StartRecovery", &["recovery", "multisig"])
+                .remaining("guardian")
+                .field("owners", join(&owners))
+                .field("threshold", threshold)
+        }
+        MultisigInstruction::VetoRecovery => {
+            Decoded::new("VetoRecovery", &["recovery", "multisig", "owner"])
+        }
+        MultisigInstruction::FinalizeRecovery => {
+            Decoded::new("FinalizeRecovery", &["recovery", "multisig"])
+        }
+        MultisigInstruction::CheckTransaction => {
+            Decoded::new("CheckTransaction", &["multisig", "transaction", "executor"])
+                .remaining("transaction account")
+        }
+        MultisigInstruction::UpdateTransaction { pid, accs, data } => Decoded::new(
+            "UpdateTransaction",
+            &[
+                "funder",
+                "proposer",
+                "multisig",
+                "transaction",
+                SYSTEM,
+                RENT,
+            ],
+        )
+        .field("program_id", pid)
+        .field("accounts", accs.len())
+        .field("data", to_hex(&data)),
+        MultisigInstruction::CreateTransactionBuffer {
+            pid,
+            accs,
+            final_hash,
+            final_size,
+            data,
+        } => Decoded::new(
+            "CreateTransactionBuffer",
+            &["funder", "proposer", "multisig", "buffer", SYSTEM, RENT],
+        )
+        .field("program_id", pid)
+        .field("accounts", accs.len())
+        .field("final_hash", to_hex(&final_hash))
+        .field("final_size", final_size)
+        .field("data", to_hex(&data)),
+        MultisigInstruction::ExtendTransactionBuffer { data } => Decoded::new(
+            "ExtendTransactionBuffer",
+            &["funder", "proposer", "buffer", SYSTEM, RENT],
+        )
+        .field("data", to_hex(&data)),
+        MultisigInstruction::FinalizeTransaction => Decoded::new(
+            "FinalizeTransaction",
+            &[
+                "funder",
+                "proposer",
+                "multisig",
+                "buffer",
+                "transaction",
+                SYSTEM,
+                RENT,
+                "buffer funder",
+            ],
+        ),
+        MultisigInstruction::CreateTransactionWithLookups {
+            pid,
+            accs,
+            lookup_tables,
+            lookup_accounts,
+            data,
+        } => Decoded::new(
+            "CreateTransactionWithLookups",
+            &[
+                "funder",
+                "proposer",
+                "multisig",
+                "transaction",
+                SYSTEM,
+                RENT,
+            ],
+        )
+        .remaining("lookup table")
+        .field("program_id", pid)
+        .field("accounts", accs.len())
+        .field("lookup_tables", join(&lookup_tables))
+        .field("lookup_accounts", lookup_accounts.len())
+        .field("data", to_hex(&data)),
+        MultisigInstruction::MigrateMultisig => Decoded::new("MigrateMultisig", &["multisig"]),
+        MultisigInstruction::CloseTransactionBuffer => Decoded::new(
+            "CloseTransactionBuffer",
+            &["proposer", "buffer", "buffer funder"],
+        ),
+        MultisigInstruction::UpdateTransactionWithLookups {
+            pid,
+            accs,
+            lookup_tables,
+            lookup_accounts,
+            data,
+        } => Decoded::new(
+            "UpdateTransactionWithLookups",
+            &[
+                "funder",
+                "proposer",
+                "multisig",
+                "transaction",
+                SYSTEM,
+                RENT,
+            ],
+        )
+        .remaining("lookup table")
+        .field("program_id", pid)
+        .field("accounts", accs.len())
+        .field("lookup_tables", join(&lookup_tables))
+        .field("lookup_accounts", lookup_accounts.len())
+        .field("data", to_hex(&data)),
+    };
+
+    Some(decoded)
+}
+
+fn decode_system(data: &[u8]) -> Option<Decoded> {
+    let instruction: SystemInstruction = limited_deserialize(data, data.len() as u64).ok()?;
+
+    let decoded = match instruction {
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => Decoded::new("CreateAccount", &["funding account", "new account"])
+            .field("lamports", lamports)
+            .field("space", space)
+            .field("owner", owner),
+        SystemInstruction::Assign { owner } => {
+            Decoded::new("Assign", &["assigned account"]).field("owner", owner)
+        }
+        SystemInstruction::Transfer { lamports } => {
+            Decoded::new("Transfer", &["from", "to"]).field("lamports", lamports)
+        }
+        SystemInstruction::CreateAccountWithSeed {
+            base,
+            seed,
+            lamports,
+            space,
+            owner,
+        } => Decoded::new(
+            "CreateAccountWithSeed",
+            &["funding account", "new account", "base account"],
+        )
+        .field("base", base)
+        .field("seed", seed)
+        .field("lamports", lamports)
+        .field("space", space)
+        .field("owner", owner),
+        SystemInstruction::AdvanceNonceAccount => Decoded::new(
+            "AdvanceNonceAccount",
+            &[
+                "nonce account",
+                "recent blockhashes sysvar",
+                "nonce authority",
+            ],
+        ),
+        SystemInstruction::WithdrawNonceAccount(lamports) => Decoded::new(
+            "WithdrawNonceAccount",
+            &[
+                "nonce account",
+                "recipient",
+                "recent blockhashes sysvar",
+                RENT,
+                "nonce authority",
+            ],
+        )
+        .field("lamports", lamports),
+        SystemInstruction::InitializeNonceAccount(authority) => Decoded::new(
+            "InitializeNonceAccount",
+            &["nonce account", "recent blockhashes sysvar", RENT],
+        )
+        .field("authority", authority),
+        SystemInstruction::AuthorizeNonceAccount(authority) => Decoded::new(
+            "AuthorizeNonceAccount",
+            &["nonce account", "nonce authority"],
+        )
+        .field("new_authority", authority),
+        SystemInstruction::Allocate { space } => {
+            Decoded::new("Allocate", &["allocated account"]).field("space", space)
+        }
+        SystemInstruction::AllocateWithSeed {
+            base,
+            seed,
+            space,
+            owner,
+        } => Decoded::new("AllocateWithSeed", &["allocated account", "base account"])
+            .field("base", base)
+            .field("seed", seed)
+            .field("space", space)
+            .field("owner", owner),
+        SystemInstruction::AssignWithSeed { base, seed, owner } => {
+            Decoded::new("AssignWithSeed", &["assigned account", "base account"])
+                .field("base", base)
+                .field("seed", seed)
+                .field("owner", owner)
+        }
+        SystemInstruction::TransferWithSeed {
+            lamports,
+            from_seed,
+            from_owner,
+        } => Decoded::new("TransferWithSeed", &["from", "base account", "to"])
+            .field("lamports", lamports)
+            .field("from_seed", from_seed)
+            .field("from_owner", from_owner),
+        SystemInstruction::UpgradeNonceAccount => {
+            Decoded::new("UpgradeNonceAccount", &["nonce account"])
+        }
+    };
+
+    Some(decoded)
+}
+
+fn decode_loader(data: &[u8]) -> Option<Decoded> {
+    let instruction: UpgradeableLoaderInstruction =
+        limited_deserialize(data, data.len() as u64).ok()?;
+
+    let decoded = match instruction {
+        UpgradeableLoaderInstruction::InitializeBuffer => {
+            Decoded::new("InitializeBuffer", &["buffer", "authority"])
+        }
+        UpgradeableLoaderInstruction::Write { offset, bytes } => {
+            Decoded::new("Write", &["buffer", "authority"])
+                .field("offset", offset)
+                .field("size", bytes.len())
+        }
+        UpgradeableLoaderInstruction::DeployWithMaxDataLen { max_data_len } => Decoded::new(
+            "DeployWithMaxDataLen",
+            &[
+                "payer",
+                "program data",
+                "program",
+                "buffer",
+                RENT,
+                "clock sysvar",
+                SYSTEM,
+                "authority",
+            ],
+        )
+        .field("max_data_len", max_data_len),
+        UpgradeableLoaderInstruction::Upgrade => Decoded::new(
+            "Upgrade",
+            &[
+                "program data",
+                "program",
+                "buffer",
+                "spill",
+                RENT,
+                "clock sysvar",
+                "authority",
+            ],
+        ),
+        UpgradeableLoaderInstruction::SetAuthority => Decoded::new(
+            "SetAuthority",
+            &["account", "current authority", "new authority"],
+        ),
+        UpgradeableLoaderInstruction::Close => {
+            Decoded::new("Close", &["account", "recipient", "authority", "program"])
+        }
+        UpgradeableLoaderInstruction::ExtendProgram { additional_bytes } => Decoded::new(
+            "ExtendProgram",
+            &["program data", "program", SYSTEM, "payer"],
+        )
+        .field("additional_bytes", additional_bytes),
+        UpgradeableLoaderInstruction::SetAuthorityChecked => Decoded::new(
+            "SetAuthorityChecked",
+            &["account", "current authority", "new authority"],
+        ),
+    };
+
+    Some(decoded)
+}
+
+/// SPL Token instructions, which Token-2022 shares up to extensions
+fn decode_token(data: &[u8]) -> Option<Decoded> {
+    let (&tag, rest) = data.split_first()?;
+    let mut reader = TokenReader { data: rest };
+
+    let decoded = match tag {
+        0 | 20 => {
+            let (name, roles): (_, &'static [_]) = match tag {
+                0 => ("InitializeMint", &["mint", RENT]),
+                _ => ("InitializeMint2", &["mint"]),
+            };
+            Decoded::new(name, roles)
+                .field("decimals", reader.u8()?)
+                .field("mint_authority", reader.pubkey()?)
+                .field("freeze_authority", optional(reader.optional_pubkey()?))
+        }
+        1 => Decoded::new("InitializeAccount", &["account", "mint", "owner", RENT]),
+        2 | 19 => {
+            let (name, roles): (_, &'static [_]) = match tag {
+                2 => ("InitializeMultisig", &["multisig", RENT]),
+                _ => ("InitializeMultisig2", &["multisig"]),
+            };
+            Decoded::new(name, roles)
+                .remaining("signer")
+                .field("m", reader.u8()?)
+        }
+        3 => Decoded::new("Transfer", &["source", "destination", "authority"])
+            .remaining("signer")
+            .field("amount", reader.u64()?),
+        4 => Decoded::new("Approve", &["source", "delegate", "owner"])
+            .remaining("signer")
+            .field("amount", reader.u64()?),
+        5 => Decoded::new("Revoke", &["source", "owner"]).remaining("signer"),
+        6 => Decoded::new("SetAuthority", &["account", "current authority"])
+            .remaining("signer")
+            .field("authority_type", authority_type(reader.u8()?))
+            .field("new_authority", optional(reader.optional_pubkey()?)),
+        7 => Decoded::new("MintTo", &["mint", "destination", "authority"])
+            .remaining("signer")
+            .field("amount", reader.u64()?),
+        8 => Decoded::new("Burn", &["account", "mint", "authority"])
+            .remaining("signer")
+            .field("amount", reader.u64()?),
+        9 => Decoded::new("CloseAccount", &["account", "destination", "authority"])
+            .remaining("signer"),
+        10 => Decoded::new("FreezeAccount", &["account", "mint", "authority"]).remaining("signer"),
+        11 => Decoded::new("ThawAccount", &["account", "mint", "authority"]).remaining("signer"),
+        12 => Decoded::new(
+            "TransferChecked",
+            &["source", "mint", "destination", "authority"],
+        )
+        .remaining("signer")
+        .field("amount", reader.u64()?)
+        .field("decimals", reader.u8()?),
+        13 => Decoded::new("ApproveChecked", &["source", "mint", "delegate", "owner"])
+            .remaining("signer")
+            .field("amount", reader.u64()?)
+            .field("decimals", reader.u8()?),
+        14 => Decoded::new("MintToChecked", &["mint", "destination", "authority"])
+            .remaining("signer")
+            .field("amount", reader.u64()?)
+            .field("decimals", reader.u8()?),
+        15 => Decoded::new("BurnChecked", &["account", "mint", "authority"])
+            .remaining("signer")
+            .field("amount", reader.u64()?)
+            .field("decimals", reader.u8()?),
+        16 => Decoded::new("InitializeAccount2", &["account", "mint", RENT])
+            .field("owner", reader.pubkey()?),
+        17 => Decoded::new("SyncNative", &["account"]),
+        18 => Decoded::new("InitializeAccount3", &["account", "mint"])
+            .field("owner", reader.pubkey()?),
+        _ => return None,
+    };
+
+    Some(decoded)
+}
+
+fn decode_memo(data: &[u8]) -> Option<Decoded> {
+    let memo = std::str::from_utf8(data).ok()?;
+
+    Some(
+        Decoded::new("Memo", &[])
+            .remaining("signer")
+            .field("memo", memo),
+    )
+}
+
+struct TokenReader<'a> {
+    data: &'a [u8],
+}
+
+impl TokenReader<'_> {
+    fn take(&mut self, len: usize) -> Option<&[u8]> {
+        if self.data.len() < len {
+            return None;
+        }
+        let (value, rest) = self.data.split_at(len);
+        self.data = rest;
+        Some(value)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|value| value[0])
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take(8)?.try_into().ok().map(u64::from_le_bytes)
+    }
+
+    fn pubkey(&mut self) -> Option<Pubkey> {
+        Pubkey::try_from(self.take(PUBKEY_BYTES)?).ok()
+    }
+
+    fn optional_pubkey(&mut self) -> Option<Option<Pubkey>> {
+        match self.u8()? {
+            0 => Some(None),
+            1 => self.pubkey().map(Some),
+            _ => None,
+        }
+    }
+}
+
+fn authority_type(value: u8) -> String {
+    match value {
+        0 => "MintTokens".to_string(),
+        1 => "FreezeAccount".to_string(),
+        2 => "AccountOwner".to_string(),
+        3 => "CloseAccount".to_string(),
+        value => value.to_string(),
+    }
+}
+
+fn optional(value: Option<impl ToString>) -> String {
+    value
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| "none".to_string())
+}
+
+fn join(pubkeys: &[Pubkey]) -> String {
+    pubkeys
+        .iter()
+        .map(Pubkey::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn to_hex(data: &[u8]) -> String {
+    data.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{:02x}", byte);
+        hex
+    })
+}
@@ -1,7 +1,9 @@
 use solana_program::{
-    account_info::AccountInfo, entrypoint, entrypoint::ProgramResult, pubkey::Pubkey,
+    account_info::AccountInfo, entrypoint, entrypoint::ProgramResult,
+    program_error::PrintProgramError, pubkey::Pubkey,
 };
 
+use crate::error::MultisigError;
 use crate::processor::Processor;
 
 entrypoint!(process_instruction);
@@ -11,5 +13,10 @@ pub fn process_instruction(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    Processor::process(program_id, accounts, instruction_data)
+    if let Err(error) = Processor::process(program_id, accounts, instruction_data) {
+        error.print::<MultisigError>();
+        return Err(error);
+    }
+
+    Ok(())
 }
@@ -1,8 +1,12 @@
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
 use thiserror::Error;
 
-use solana_program::program_error::ProgramError;
+use solana_program::decode_error::DecodeError;
+use solana_program::msg;
+use solana_program::program_error::{PrintProgramError, ProgramError};
 
-#[derive(Error, Debug, Copy, Clone)]
+#[derive(Error, Debug, Copy, Clone, PartialEq, Eq, FromPrimitive)]
 pub enum MultisigError {
     #[error("The given owner is not part of this multisig.")]
     InvalidOwner,
@@ -36,6 +40,28 @@ pub enum MultisigError {
     InvalidLastTransaction,
     #[error("Owner already exist")]
     OwnerAlreadyExist,
+    #[error("Vault should be drained")]
+    VaultNotDrained,
+    #[error("Program is not in the allowlist")]
+    ProgramNotAllowed,
+    #[error("Recovery is not configured")]
+    RecoveryNotConfigured,
+    #[error("Recovery delay is too short")]
+    InvalidRecoveryDelay,
+    #[error("Recovery is already in progress")]
+    RecoveryInProgress,
+    #[error("No recovery in progress")]
+    NoPendingRecovery,
+    #[error("Recovery delay has not passed yet")]
+    RecoveryDelayNotPassed,
+    #[error("Transaction buffer exceeds its final size")]
+    TransactionBufferOverflow,
+    #[error("Transaction buffer does not match its final size or hash")]
+    TransactionBufferMismatch,
+    #[error("Lookup table account is missing or does not contain the address")]
+    InvalidLookupAccount,
+    #[error("Owners changed since the transaction was created")]
+    OwnersChanged,
 }
 
 impl From<MultisigError> for ProgramError {
@@ -43,3 +69,26 @@ impl From<MultisigError> for ProgramError {
         ProgramError::Custom(e as u32)
     }
 }
+
+impl TryFrom<u32> for MultisigError {
+    type Error = ProgramError;
+
+    fn try_from(code: u32) -> Result<Self, Self::Error> {
+        Self::from_u32(code).ok_or(ProgramError::Custom(code))
+    }
+}
+
+impl<T> DecodeError<T> for MultisigError {
+    fn type_of() -> &'static str {
+        "MultisigError"
+    }
+}
+
+impl PrintProgramError for MultisigError {
+    fn print<E>(&self)
+    where
+        E: 'static + std::error::Error + DecodeError<E> + PrintProgramError + FromPrimitive,
+    {
+        msg!("Error: {}", self);
+    }
+}
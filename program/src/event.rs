@@ -0,0 +1,39 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use solana_program::entrypoint::ProgramResult;
+use solana_program::log::sol_log_data;
+use solana_program::pubkey::Pubkey;
+
+/// Events logged with `sol_log_data` as borsh serialized data
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub enum MultisigEvent {
+    RecoveryConfigured {
+        multisig: Pubkey,
+        guardians: Vec<Pubkey>,
+        guardian_threshold: u64,
+        delay: i64,
+    },
+    RecoveryStarted {
+        multisig: Pubkey,
+        guardians: Vec<Pubkey>,
+        owners: Vec<Pubkey>,
+        threshold: u64,
+        executable_at: i64,
+    },
+    RecoveryVetoed {
+        multisig: Pubkey,
+        owner: Pubkey,
+    },
+    RecoveryFinalized {
+        multisig: Pubkey,
+        owners: Vec<Pubkey>,
+        threshold: u64,
+    },
+}
+
+impl MultisigEvent {
+    pub fn emit(&self) -> ProgramResult {
+        sol_log_data(&[&self.try_to_vec()?]);
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Deserialize;
+use solana_program::pubkey::Pubkey;
+
+use crate::*;
+
+#[derive(Debug, thiserror::Error)]
+pub enum MultisigIndexError {
+    #[error("Invalid account dump JSON: {0}")]
+    Json(String),
+    #[error("Invalid pubkey {0}")]
+    InvalidPubkey(String),
+    #[error("Unsupported account data encoding {0}")]
+    UnsupportedEncoding(String),
+    #[error("Invalid account data of {0}")]
+    InvalidData(String),
+}
+
+/// Kind of a program account, recognized by its address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultisigAccountKind {
+    Multisig,
+    Transaction,
+}
+
+/// In-memory index of multisig program accounts, e.g. as returned by
+/// `getProgramAccounts` or dumped from a test validator
+#[derive(Debug, Default)]
+pub struct MultisigIndex {
+    multisigs: BTreeMap<Pubkey, Multisig>,
+    transactions: BTreeMap<Pubkey, Transaction>,
+}
+
+impl MultisigIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_accounts<I, D>(accounts: I) -> Self
+    where
+        I: IntoIterator<Item = (Pubkey, D)>,
+        D: AsRef<[u8]>,
+    {
+        let mut index = Self::new();
+        for (pubkey, data) in accounts {
+            index.insert(pubkey, data.as_ref());
+        }
+        index
+    }
+
+    /// Build an index from a JSON account dump.
+    ///
+    /// Accepts a single account as written by `solana account --output json`,
+    /// a list of such accounts, or a `getProgramAccounts` response. Account
+    /// data must be base64 encoded, accounts of other programs are skipped.
+    pub fn from_json(json: &str) -> Result<Self, MultisigIndexError> {
+        let dump: AccountDump =
+            serde_json::from_str(json).map_err(|e| MultisigIndexError::Json(e.to_string()))?;
+
+        let accounts = match dump {
+            AccountDump::Many(accounts) | AccountDump::Response { result: accounts } => accounts,
+            AccountDump::One(account) => vec![account],
+        };
+
+        let program_id = id().to_string();
+
+        let mut index = Self::new();
+        for KeyedAccount { pubkey, account } in accounts {
+            if account.owner != program_id {
+                continue;
+            }
+
+            let (data, encoding) = account.data;
+            if encoding != "base64" {
+                return Err(MultisigIndexError::UnsupportedEncoding(encoding));
+            }
+
+            let data = STANDARD
+                .decode(data)
+                .map_err(|_| MultisigIndexError::InvalidData(pubkey.clone()))?;
+            let pubkey = Pubkey::from_str(&pubkey)
+                .map_err(|_| MultisigIndexError::InvalidPubkey(pubkey.clone()))?;
+
+            index.insert(pubkey, &data);
+        }
+
+        Ok(index)
+    }
+
+    /// Decode and store a program account. Accounts which are neither
+    /// a multisig nor a transaction at their expected address are skipped.
+    pub fn insert(&mut self, pubkey: Pubkey, data: &[u8]) -> Option<MultisigAccountKind> {
+        if let Ok(transaction) = Transaction::unpack_from_slice(data) {
+            if transaction.is_initialized
+                && get_transaction_address(&transaction.multisig, transaction.index) == pubkey
+            {
+                self.transactions.insert(pubkey, transaction);
+                return Some(MultisigAccountKind::Transaction);
+            }
+        }
+
+        if let Ok(multisig) = Multisig::unpack(data) {
+            if get_multisig_address(multisig.seed) == pubkey {
+                self.multisigs.insert(pubkey, multisig);
+                return Some(MultisigAccountKind::Multisig);
+            }
+        }
+
+        None
+    }
+
+    pub fn get_multisig(&self, pubkey: &Pubkey) -> Option<&Multisig> {
+        self.multisigs.get(pubkey)
+    }
+
+    pub fn get_transaction(&self, pubkey: &Pubkey) -> Option<&Transaction> {
+        self.transactions.get(pubkey)
+    }
+
+    pub fn multisigs(&self) -> impl Iterator<Item = (&Pubkey, &Multisig)> {
+        self.multisigs.iter()
+    }
+
+    pub fn transactions(&self) -> impl Iterator<Item = (&Pubkey, &Transaction)> {
+        self.transactions.iter()
+    }
+
+    /// Multisigs the key is an owner of
+    pub fn get_multisigs_by_owner(&self, owner: &Pubkey) -> Vec<(&Pubkey, &Multisig)> {
+        self.multisigs
+            .iter()
+            .filter(|(_, multisig)| multisig.owners.contains(owner))
+            .collect()
+    }
+
+    /// Transactions of the multisig ordered by index
+    pub fn get_proposals_by_multisig(&self, multisig: &Pubkey) -> Vec<(&Pubkey, &Transaction)> {
+        let mut proposals = self
+            .transactions
+            .iter()
+            .filter(|(_, transaction)| transaction.multisig == *multisig)
+            .collect::<Vec<_>>();
+        proposals.sort_by_key(|(_, transaction)| transaction.index);
+        proposals
+    }
+
+    /// Pending transactions of the owner's multisigs which lack their approval.
+    /// Transactions of multisigs missing from the index are not considered.
+    pub fn get_proposals_awaiting(&self, owner: &Pubkey) -> Vec<(&Pubkey, &Transaction)> {
+        self.get_multisigs_by_owner(owner)
+            .into_iter()
+            .flat_map(|(multisig_pubkey, multisig)| {
+                self.get_proposals_by_multisig(multisig_pubkey)
+                    .into_iter()
+                    .filter(move |(_, transaction)| {
+                        matches!(
+                            transaction.get_status(multisig),
+                            ProposalStatus::Draft | ProposalStatus::Active
+                        ) && transaction.get_missing_owners(multisig).contains(owner)
+                    })
+            })
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AccountDump {
+    Many(Vec<KeyedAccount>),
+    Response { result: Vec<KeyedAccount> },
+    One(KeyedAccount),
+}
+
+#[derive(Deserialize)]
+struct KeyedAccount {
+    pubkey: String,
+    account: DumpedAccount,
+}
+
+#[derive(Deserialize)]
+struct DumpedAccount {
+    data: (String, String),
+    owner: String,
+}
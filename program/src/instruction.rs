@@ -1,7 +1,7 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::pubkey::Pubkey;
 
-use crate::TransactionAccount;
+use crate::{TransactionAccount, TransactionLookupAccount};
 
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub enum MultisigInstruction {
@@ -13,6 +13,7 @@ pub enum MultisigInstruction {
         seed: u128,
         owners: Vec<Pubkey>,
         threshold: u64,
+        max_signers: u32,
     },
 
     /// Add a new account to custodian list
@@ -36,10 +37,11 @@ pub enum MultisigInstruction {
     /// Creates a new transaction account, automatically signed by the creator,
     /// which must be one of the owners of the multisig
     ///
+    /// An owner can be another multisig, proposing through its own `ExecuteTransaction`
+    ///
     /// # Account references
     /// ...
     CreateTransaction {
-        seed: u128,
         pid: Pubkey,
         accs: Vec<TransactionAccount>,
         data: Vec<u8>,
@@ -47,11 +49,18 @@ pub enum MultisigInstruction {
 
     /// Approves a transaction on behalf of an owner of the multisig
     ///
+    /// An owner can be another multisig, approving through its own `ExecuteTransaction`
+    ///
     /// # Account references
     /// ...
     Approve,
 
-    /// Execute transaction
+    /// Execute transaction, signed by the multisig account and its vault.
+    /// Records the executor and return data of the invoked program
+    ///
+    /// The executor signs after the transaction account, the accounts of the
+    /// invoked instruction come after it. Instructions built with the
+    /// previous layout, without the executor, must be rebuilt
     ///
     /// # Account references
     /// ...
@@ -59,7 +68,181 @@ pub enum MultisigInstruction {
 
     /// Delete pending transaction
     ///
+    /// The pending transaction account must follow the multisig account.
+    /// Instructions built with the previous layout, passing only the
+    /// multisig account, must be rebuilt
+    ///
     /// # Account references
     /// ...
     DeletePendingTransaction { pending_transaction: Pubkey },
+
+    /// Reallocate multisig account and its recovery account to fit a new
+    /// capacity, funder pays the rent difference
+    ///
+    /// # Account references
+    /// ...
+    Resize { max_signers: u32 },
+
+    /// Close multisig account and its recovery account and send their
+    /// lamports to the recipient. The vault of the multisig and the token
+    /// accounts of the multisig passed along must be empty
+    ///
+    /// The multisig account is emptied but keeps the rent exempt balance of
+    /// an empty account, so no multisig can be created with the same seed
+    ///
+    /// The vault follows the recovery account, token accounts come after it
+    ///
+    /// # Account references
+    /// ...
+    CloseMultisig,
+
+    /// Approves a transaction on behalf of owners who signed the approval message
+    /// off-chain, with signatures checked by ed25519 or secp256k1 program instructions
+    /// of the same transaction
+    ///
+    /// # Account references
+    /// ...
+    ApproveWithSignatures,
+
+    /// Add a program to the list of programs transactions may target,
+    /// funder pays for the extra space
+    ///
+    /// # Account references
+    /// ...
+    AddAllowedProgram { program_id: Pubkey },
+
+    /// Remove a program from the list of programs transactions may target
+    ///
+    /// # Account references
+    /// ...
+    RemoveAllowedProgram { program_id: Pubkey },
+
+    /// Update thresholds of configuration changes and program upgrades,
+    /// unset ones fall back to the default threshold
+    ///
+    /// # Account references
+    /// ...
+    UpdateActionThresholds {
+        admin_threshold: Option<u64>,
+        upgrade_threshold: Option<u64>,
+    },
+
+    /// Set guardians allowed to recover the multisig, cancels a recovery
+    /// in progress. Funder pays for the recovery account
+    ///
+    /// # Account references
+    /// ...
+    ConfigureRecovery {
+        guardians: Vec<Pubkey>,
+        guardian_threshold: u64,
+        delay: i64,
+    },
+
+    /// Start replacing owners and threshold of the multisig, signed by
+    /// at least `guardian_threshold` guardians
+    ///
+    /// # Account references
+    /// ...
+    StartRecovery { owners: Vec<Pubkey>, threshold: u64 },
+
+    /// Cancel a recovery in progress, signed by any of the current owners
+    ///
+    /// # Account references
+    /// ...
+    VetoRecovery,
+
+    /// Apply a recovery once its delay has passed, can be called by anyone
+    ///
+    /// # Account references
+    /// ...
+    FinalizeRecovery,
+
+    /// Report whether `ExecuteTransaction` with the same accounts would succeed,
+    /// a borsh serialized `TransactionStatus` is set as return data
+    ///
+    /// # Account references
+    /// ...
+    CheckTransaction,
+
+    /// Replace the instruction of a transaction, allowed to its proposer while
+    /// no other owner has approved it. Approvals are reset to the proposer only.
+    /// Lookups of the transaction are dropped, see `UpdateTransactionWithLookups`
+    ///
+    /// # Account references
+    /// ...
+    UpdateTransaction {
+        pid: Pubkey,
+        accs: Vec<TransactionAccount>,
+        data: Vec<u8>,
+    },
+
+    /// Start uploading a transaction whose instruction data does not fit into
+    /// a single `CreateTransaction`, `data` is the first chunk
+    ///
+    /// # Account references
+    /// ...
+    CreateTransactionBuffer {
+        pid: Pubkey,
+        accs: Vec<TransactionAccount>,
+        final_hash: [u8; 32],
+        final_size: u32,
+        data: Vec<u8>,
+    },
+
+    /// Append a chunk of instruction data to the transaction buffer
+    ///
+    /// # Account references
+    /// ...
+    ExtendTransactionBuffer { data: Vec<u8> },
+
+    /// Create a transaction from the complete transaction buffer and close
+    /// the buffer, its lamports go back to the funder recorded in the buffer
+    ///
+    /// # Account references
+    /// ...
+    FinalizeTransaction,
+
+    /// Same as `CreateTransaction`, with part of the accounts referenced
+    /// through address lookup tables. The lookup tables follow the accounts of
+    /// `CreateTransaction` and the addresses they resolve to are stored in the
+    /// transaction, execution fails if the tables no longer resolve to them
+    ///
+    /// # Account references
+    /// ...
+    CreateTransactionWithLookups {
+        pid: Pubkey,
+        accs: Vec<TransactionAccount>,
+        lookup_tables: Vec<Pubkey>,
+        lookup_accounts: Vec<TransactionLookupAccount>,
+        data: Vec<u8>,
+    },
+
+    /// Convert a multisig created before the account was resizable to the
+    /// current layout with the default capacity, can be called by anyone.
+    /// Pending transactions of the old layout are dropped
+    ///
+    /// # Account references
+    /// ...
+    MigrateMultisig,
+
+    /// Close the transaction buffer of the proposer without creating a
+    /// transaction, its lamports go back to the funder recorded in the buffer
+    ///
+    /// # Account references
+    /// ...
+    CloseTransactionBuffer,
+
+    /// Same as `UpdateTransaction`, with part of the accounts referenced
+    /// through address lookup tables passed after the accounts of
+    /// `UpdateTransaction`
+    ///
+    /// # Account references
+    /// ...
+    UpdateTransactionWithLookups {
+        pid: Pubkey,
+        accs: Vec<TransactionAccount>,
+        lookup_tables: Vec<Pubkey>,
+        lookup_accounts: Vec<TransactionLookupAccount>,
+        data: Vec<u8>,
+    },
 }
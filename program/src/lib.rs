@@ -1,12 +1,16 @@
 mod error;
+mod event;
 mod instruction;
 mod processor;
+mod signature;
 mod state;
 mod utils;
 
 pub use self::error::*;
+pub use self::event::*;
 pub use self::instruction::*;
 pub use self::processor::*;
+pub use self::signature::*;
 pub use self::state::*;
 
 #[cfg(feature = "wasm")]
@@ -23,6 +27,33 @@ mod bindings;
 #[cfg(feature = "bindings")]
 pub use self::bindings::*;
 
+#[cfg(feature = "bindings")]
+mod bundle;
+
+// Separate from `bindings`, which only builds instructions: the decoder goes
+// the other way, covers other programs as well, and backs both the proposal
+// bundles and the wasm `decodeInstruction`
+#[cfg(feature = "bindings")]
+mod decoder;
+
+#[cfg(feature = "bindings")]
+mod indexer;
+
+#[cfg(feature = "bindings")]
+pub use self::bundle::*;
+
+#[cfg(feature = "bindings")]
+pub use self::decoder::*;
+
+#[cfg(feature = "bindings")]
+pub use self::indexer::*;
+
+#[cfg(feature = "client")]
+mod client;
+
+#[cfg(feature = "client")]
+pub use self::client::*;
+
 #[cfg(not(feature = "no-entrypoint"))]
 mod entrypoint;
 
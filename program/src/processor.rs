@@ -1,21 +1,28 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 
 use solana_program::account_info::{next_account_info, AccountInfo};
+use solana_program::address_lookup_table::{self, state::LOOKUP_TABLE_META_SIZE};
+use solana_program::clock::Clock;
 use solana_program::entrypoint::ProgramResult;
-use solana_program::instruction::Instruction;
-use solana_program::program::invoke_signed;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::program::{get_return_data, invoke, invoke_signed, set_return_data};
 use solana_program::program_error::ProgramError;
-use solana_program::program_pack::Pack;
 use solana_program::pubkey::Pubkey;
 use solana_program::rent::Rent;
-use solana_program::sysvar::Sysvar;
-use solana_program::{msg, system_instruction};
+use solana_program::sysvar::{instructions, Sysvar};
+use solana_program::{ed25519_program, hash, msg, pubkey, secp256k1_program, system_instruction};
 
 use crate::{
-    require, Multisig, MultisigError, MultisigInstruction, Transaction, TransactionAccount,
-    MAX_SIGNERS, MAX_TRANSACTIONS, MIN_SIGNERS,
+    get_approval_message, get_ed25519_signers, get_eth_approval_message, get_eth_owner,
+    get_secp256k1_signers, require, BlockingReason, LegacyMultisig, Multisig, MultisigError,
+    MultisigEvent, MultisigInstruction, PendingRecovery, Recovery, Transaction, TransactionAccount,
+    TransactionBuffer, TransactionLookupAccount, TransactionStatus, MAX_SIGNERS,
+    MAX_TRANSACTION_BUFFER_SIZE, MAX_TRANSACTION_RETURN_DATA, MIN_RECOVERY_DELAY, MIN_SIGNERS,
 };
 
+const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
 pub struct Processor;
 impl Processor {
     pub fn process(
@@ -30,18 +37,21 @@ impl Processor {
                 seed,
                 owners,
                 threshold,
+                max_signers,
             } => {
                 msg!("Instruction: Create Multisig");
-                Self::process_create_multisig(program_id, accounts, seed, owners, threshold)?;
+                Self::process_create_multisig(
+                    program_id,
+                    accounts,
+                    seed,
+                    owners,
+                    threshold,
+                    max_signers,
+                )?;
             }
-            MultisigInstruction::CreateTransaction {
-                seed,
-                pid,
-                accs,
-                data,
-            } => {
+            MultisigInstruction::CreateTransaction { pid, accs, data } => {
                 msg!("Instruction: Create Transaction");
-                Self::process_create_transaction(program_id, accounts, seed, pid, accs, data)?;
+                Self::process_create_transaction(program_id, accounts, pid, accs, data)?;
             }
             MultisigInstruction::Approve => {
                 msg!("Instruction: Approve");
@@ -73,6 +83,148 @@ impl Processor {
                     pending_transaction,
                 )?;
             }
+            MultisigInstruction::Resize { max_signers } => {
+                msg!("Instruction: Resize");
+                Self::process_resize(program_id, accounts, max_signers)?;
+            }
+            MultisigInstruction::CloseMultisig => {
+                msg!("Instruction: Close Multisig");
+                Self::process_close_multisig(program_id, accounts)?;
+            }
+            MultisigInstruction::ApproveWithSignatures => {
+                msg!("Instruction: Approve With Signatures");
+                Self::process_approve_with_signatures(program_id, accounts)?;
+            }
+            MultisigInstruction::AddAllowedProgram {
+                program_id: allowed_program,
+            } => {
+                msg!("Instruction: Add Allowed Program");
+                Self::process_add_allowed_program(program_id, accounts, allowed_program)?;
+            }
+            MultisigInstruction::RemoveAllowedProgram {
+                program_id: allowed_program,
+            } => {
+                msg!("Instruction: Remove Allowed Program");
+                Self::process_remove_allowed_program(program_id, accounts, allowed_program)?;
+            }
+            MultisigInstruction::UpdateActionThresholds {
+                admin_threshold,
+                upgrade_threshold,
+            } => {
+                msg!("Instruction: Update Action Thresholds");
+                Self::process_update_action_thresholds(
+                    program_id,
+                    accounts,
+                    admin_threshold,
+                    upgrade_threshold,
+                )?;
+            }
+            MultisigInstruction::ConfigureRecovery {
+                guardians,
+                guardian_threshold,
+                delay,
+            } => {
+                msg!("Instruction: Configure Recovery");
+                Self::process_configure_recovery(
+                    program_id,
+                    accounts,
+                    guardians,
+                    guardian_threshold,
+                    delay,
+                )?;
+            }
+            MultisigInstruction::StartRecovery { owners, threshold } => {
+                msg!("Instruction: Start Recovery");
+                Self::process_start_recovery(program_id, accounts, owners, threshold)?;
+            }
+            MultisigInstruction::VetoRecovery => {
+                msg!("Instruction: Veto Recovery");
+                Self::process_veto_recovery(program_id, accounts)?;
+            }
+            MultisigInstruction::FinalizeRecovery => {
+                msg!("Instruction: Finalize Recovery");
+                Self::process_finalize_recovery(program_id, accounts)?;
+            }
+            MultisigInstruction::CheckTransaction => {
+                msg!("Instruction: Check Transaction");
+                Self::process_check_transaction(program_id, accounts)?;
+            }
+            MultisigInstruction::UpdateTransaction { pid, accs, data } => {
+                msg!("Instruction: Update Transaction");
+                Self::process_update_transaction(
+                    program_id,
+                    accounts,
+                    pid,
+                    accs,
+                    vec![],
+                    vec![],
+                    data,
+                )?;
+            }
+            MultisigInstruction::CreateTransactionBuffer {
+                pid,
+                accs,
+                final_hash,
+                final_size,
+                data,
+            } => {
+                msg!("Instruction: Create Transaction Buffer");
+                Self::process_create_transaction_buffer(
+                    program_id, accounts, pid, accs, final_hash, final_size, data,
+                )?;
+            }
+            MultisigInstruction::ExtendTransactionBuffer { data } => {
+                msg!("Instruction: Extend Transaction Buffer");
+                Self::process_extend_transaction_buffer(program_id, accounts, data)?;
+            }
+            MultisigInstruction::FinalizeTransaction => {
+                msg!("Instruction: Finalize Transaction");
+                Self::process_finalize_transaction(program_id, accounts)?;
+            }
+            MultisigInstruction::CreateTransactionWithLookups {
+                pid,
+                accs,
+                lookup_tables,
+                lookup_accounts,
+                data,
+            } => {
+                msg!("Instruction: Create Transaction With Lookups");
+                Self::process_create_transaction_with_lookups(
+                    program_id,
+                    accounts,
+                    pid,
+                    accs,
+                    lookup_tables,
+                    lookup_accounts,
+                    data,
+                )?;
+            }
+            MultisigInstruction::MigrateMultisig => {
+                msg!("Instruction: Migrate Multisig");
+                Self::process_migrate_multisig(program_id, accounts)?;
+            }
+            MultisigInstruction::CloseTransactionBuffer => {
+                msg!("Instruction: Close Transaction Buffer");
+                Self::process_close_transaction_buffer(program_id, accounts)?;
+            }
+            MultisigInstruction::UpdateTransactionWithLookups {
+                pid,
+                accs,
+                lookup_tables,
+                lookup_accounts,
+                data,
+            } => {
+                msg!("Instruction: Update Transaction With Lookups");
+                Self::process_update_transaction(
+                    program_id,
+                    accounts,
+                    pid,
+                    accs,
+                    lookup_tables,
+                    lookup_accounts,
+                    data,
+                )?;
+            }
         };
 
         Ok(())
@@ -84,6 +236,7 @@ impl Processor {
         seed: u128,
         owners: Vec<Pubkey>,
         threshold: u64,
+        max_signers: u32,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
@@ -107,19 +260,26 @@ impl Processor {
 
         require!(
             threshold <= owners.len() as u64
-                && threshold <= MAX_SIGNERS as u64
+                && threshold <= max_signers as u64
                 && threshold >= MIN_SIGNERS as u64,
             MultisigError::InvalidThreshold
         );
 
         require!(!owners.is_empty(), MultisigError::InvalidOwnersLen);
 
+        require!(
+            owners.len() <= max_signers as usize,
+            MultisigError::OwnersOverflow
+        );
+
+        let data_len = Multisig::get_len(max_signers, 0);
+
         invoke_signed(
             &system_instruction::create_account(
                 funder_account_info.key,
                 multisig_account_info.key,
-                1.max(rent.minimum_balance(Multisig::LEN)),
-                Multisig::LEN as u64,
+                1.max(rent.minimum_balance(data_len)),
+                data_len as u64,
                 program_id,
             ),
             &[
@@ -134,8 +294,14 @@ impl Processor {
             is_initialized: true,
             owners,
             threshold,
-            pending_transactions: vec![],
+            pending_transaction_count: 0,
+            transaction_index: 0,
             seed,
+            max_signers,
+            allowed_programs: vec![],
+            admin_threshold: None,
+            upgrade_threshold: None,
+            owners_epoch: 0,
         };
 
         Multisig::pack(multisig, &mut multisig_account_info.data.borrow_mut())?;
@@ -168,11 +334,13 @@ impl Processor {
         }
 
         require!(
-            multisig_account_data.owners.len() < MAX_SIGNERS,
+            multisig_account_data.owners.len() < multisig_account_data.max_signers as usize,
             MultisigError::OwnersOverflow
         );
 
-        if !multisig_account_data.owners.iter().any(|a| *a == owner) {
+        // Owners are appended, so approvals of pending transactions keep
+        // lining up with the owner indices and stay valid
+        if !multisig_account_data.owners.contains(&owner) {
             multisig_account_data.owners.push(owner);
         }
 
@@ -210,12 +378,19 @@ impl Processor {
 
         require!(
             multisig_account_data.owners.len() > MIN_SIGNERS
-                && multisig_account_data.owners.len() > multisig_account_data.threshold as usize,
+                && multisig_account_data.owners.len()
+                    > multisig_account_data.get_max_threshold() as usize,
             MultisigError::OwnersLackOff
         );
 
+        let owners_len = multisig_account_data.owners.len();
+
         multisig_account_data.owners.retain(|x| *x != owner);
 
+        if multisig_account_data.owners.len() != owners_len {
+            multisig_account_data.bump_owners_epoch()?;
+        }
+
         Multisig::pack(
             multisig_account_data,
             &mut multisig_account_info.data.borrow_mut(),
@@ -263,10 +438,53 @@ impl Processor {
         Ok(())
     }
 
+    fn process_update_action_thresholds(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        admin_threshold: Option<u64>,
+        upgrade_threshold: Option<u64>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let multisig_account_info = next_account_info(account_info_iter)?;
+
+        if !multisig_account_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
+
+        let (multisig_account, _nonce) = Pubkey::find_program_address(
+            &[br"multisig", &multisig_account_data.seed.to_le_bytes()],
+            program_id,
+        );
+
+        if multisig_account != *multisig_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        for threshold in [admin_threshold, upgrade_threshold].into_iter().flatten() {
+            require!(
+                threshold <= multisig_account_data.owners.len() as u64
+                    && threshold >= MIN_SIGNERS as u64,
+                MultisigError::InvalidThreshold
+            );
+        }
+
+        multisig_account_data.admin_threshold = admin_threshold;
+        multisig_account_data.upgrade_threshold = upgrade_threshold;
+
+        Multisig::pack(
+            multisig_account_data,
+            &mut multisig_account_info.data.borrow_mut(),
+        )?;
+
+        Ok(())
+    }
+
     fn process_create_transaction(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
-        seed: u128,
         pid: Pubkey,
         accs: Vec<TransactionAccount>,
         data: Vec<u8>,
@@ -285,244 +503,1761 @@ impl Processor {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
-        let mut multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
+        create_transaction_account(
+            program_id,
+            funder_account_info,
+            proposer_account_info,
+            multisig_account_info,
+            transaction_account_info,
+            system_program_info,
+            rent,
+            pid,
+            accs,
+            vec![],
+            vec![],
+            vec![],
+            data,
+        )
+    }
 
-        require!(
-            multisig_account_data.pending_transactions.len() <= MAX_TRANSACTIONS,
-            MultisigError::InvalidThreshold
-        );
+    fn process_create_transaction_with_lookups(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        pid: Pubkey,
+        accs: Vec<TransactionAccount>,
+        lookup_tables: Vec<Pubkey>,
+        lookup_accounts: Vec<TransactionLookupAccount>,
+        data: Vec<u8>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
 
-        if multisig_account_data.pending_transactions.len() + 1 == MAX_TRANSACTIONS {
-            if *program_id != pid {
-                return Err(MultisigError::InvalidLastTransaction.into());
-            }
+        let funder_account_info = next_account_info(account_info_iter)?;
+        let proposer_account_info = next_account_info(account_info_iter)?;
+        let multisig_account_info = next_account_info(account_info_iter)?;
+        let transaction_account_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent_sysvar_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(rent_sysvar_info)?;
 
-            let instruction = MultisigInstruction::try_from_slice(&data)?;
-            if !matches!(
-                instruction,
-                MultisigInstruction::DeletePendingTransaction {
-                    pending_transaction: _
-                }
-            ) {
-                return Err(MultisigError::InvalidLastTransaction.into());
-            }
+        if !proposer_account_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
         }
 
-        let owner_index = multisig_account_data
-            .owners
-            .iter()
-            .position(|a| a == proposer_account_info.key)
-            .ok_or(MultisigError::InvalidOwner)?;
+        let lookup_addresses = resolve_lookup_accounts(
+            &lookup_tables,
+            &lookup_accounts,
+            account_info_iter.as_slice(),
+        )?;
 
-        let (transaction_account, transaction_nonce) =
-            Pubkey::find_program_address(&[br"transaction", &seed.to_le_bytes()], program_id);
+        create_transaction_account(
+            program_id,
+            funder_account_info,
+            proposer_account_info,
+            multisig_account_info,
+            transaction_account_info,
+            system_program_info,
+            rent,
+            pid,
+            accs,
+            lookup_tables,
+            lookup_accounts,
+            lookup_addresses,
+            data,
+        )
+    }
 
-        if transaction_account != *transaction_account_info.key {
-            return Err(ProgramError::InvalidAccountData);
+    #[allow(clippy::too_many_arguments)]
+    fn process_create_transaction_buffer(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        pid: Pubkey,
+        accs: Vec<TransactionAccount>,
+        final_hash: [u8; 32],
+        final_size: u32,
+        data: Vec<u8>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let funder_account_info = next_account_info(account_info_iter)?;
+        let proposer_account_info = next_account_info(account_info_iter)?;
+        let multisig_account_info = next_account_info(account_info_iter)?;
+        let buffer_account_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent_sysvar_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(rent_sysvar_info)?;
+
+        if !proposer_account_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
         }
 
-        let transaction_account_signer_seeds: &[&[_]] =
-            &[br"transaction", &seed.to_le_bytes(), &[transaction_nonce]];
+        let multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
+
+        require!(
+            multisig_account_data
+                .owners
+                .contains(proposer_account_info.key),
+            MultisigError::InvalidOwner
+        );
 
-        let mut signers = Vec::new();
-        signers.resize(multisig_account_data.owners.len(), false);
-        signers[owner_index] = true;
+        require!(
+            multisig_account_data.is_program_allowed(&pid),
+            MultisigError::ProgramNotAllowed
+        );
+
+        require!(
+            final_size <= MAX_TRANSACTION_BUFFER_SIZE && data.len() <= final_size as usize,
+            MultisigError::TransactionBufferOverflow
+        );
+
+        let (buffer_account, buffer_nonce) = Pubkey::find_program_address(
+            &[
+                br"transaction_buffer",
+                multisig_account_info.key.as_ref(),
+                proposer_account_info.key.as_ref(),
+            ],
+            program_id,
+        );
 
-        let tx = Transaction {
+        if buffer_account != *buffer_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let buffer_account_signer_seeds: &[&[_]] = &[
+            br"transaction_buffer",
+            multisig_account_info.key.as_ref(),
+            proposer_account_info.key.as_ref(),
+            &[buffer_nonce],
+        ];
+
+        let buffer = TransactionBuffer {
             is_initialized: true,
             multisig: *multisig_account_info.key,
+            proposer: *proposer_account_info.key,
+            funder: *funder_account_info.key,
             program_id: pid,
             accounts: accs,
-            did_execute: false,
+            final_hash,
+            final_size,
             data,
-            signers,
         };
 
-        let data_len = tx.try_to_vec()?.len();
+        let data_len = buffer.try_to_vec()?.len();
 
         invoke_signed(
             &system_instruction::create_account(
                 funder_account_info.key,
-                transaction_account_info.key,
+                buffer_account_info.key,
                 1.max(rent.minimum_balance(data_len)),
                 data_len as u64,
                 program_id,
             ),
             &[
                 funder_account_info.clone(),
-                transaction_account_info.clone(),
+                buffer_account_info.clone(),
                 system_program_info.clone(),
             ],
-            &[transaction_account_signer_seeds],
+            &[buffer_account_signer_seeds],
         )?;
 
-        tx.pack_into_slice(&mut transaction_account_info.data.borrow_mut());
-
-        // Add transaction to pending list
-        multisig_account_data
-            .pending_transactions
-            .push(*transaction_account_info.key);
-
-        Multisig::pack(
-            multisig_account_data,
-            &mut multisig_account_info.data.borrow_mut(),
-        )?;
+        TransactionBuffer::pack(buffer, &mut buffer_account_info.data.borrow_mut())?;
 
         Ok(())
     }
 
-    fn process_approve(_program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    fn process_extend_transaction_buffer(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        data: Vec<u8>,
+    ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
+        let funder_account_info = next_account_info(account_info_iter)?;
         let proposer_account_info = next_account_info(account_info_iter)?;
-        let transaction_account_info = next_account_info(account_info_iter)?;
-        let multisig_account_info = next_account_info(account_info_iter)?;
+        let buffer_account_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent_sysvar_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(rent_sysvar_info)?;
 
         if !proposer_account_info.is_signer {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
-        let multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
-        let mut transaction_account_data =
-            Transaction::unpack_from_slice(&transaction_account_info.data.borrow())?;
-
-        if transaction_account_data.multisig != *multisig_account_info.key {
+        if buffer_account_info.owner != program_id {
             return Err(ProgramError::InvalidAccountData);
         }
 
-        let owner_index = multisig_account_data
-            .owners
-            .iter()
-            .position(|value| value == proposer_account_info.key)
-            .ok_or(MultisigError::InvalidOwner)?;
+        let mut buffer_account_data =
+            TransactionBuffer::unpack(&buffer_account_info.data.borrow())?;
 
-        transaction_account_data.signers[owner_index] = true;
+        if buffer_account_data.proposer != *proposer_account_info.key {
+            return Err(MultisigError::InvalidOwner.into());
+        }
 
-        transaction_account_data.pack_into_slice(&mut transaction_account_info.data.borrow_mut());
+        require!(
+            buffer_account_data.data.len() + data.len() <= buffer_account_data.final_size as usize,
+            MultisigError::TransactionBufferOverflow
+        );
+
+        buffer_account_data.data.extend_from_slice(&data);
+
+        let data_len = buffer_account_data.try_to_vec()?.len();
+
+        realloc_account(
+            buffer_account_info,
+            funder_account_info,
+            system_program_info,
+            rent,
+            data_len,
+        )?;
+
+        TransactionBuffer::pack(
+            buffer_account_data,
+            &mut buffer_account_info.data.borrow_mut(),
+        )?;
 
         Ok(())
     }
 
-    fn process_execute_transaction(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    fn process_finalize_transaction(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
+        let funder_account_info = next_account_info(account_info_iter)?;
+        let proposer_account_info = next_account_info(account_info_iter)?;
         let multisig_account_info = next_account_info(account_info_iter)?;
+        let buffer_account_info = next_account_info(account_info_iter)?;
         let transaction_account_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent_sysvar_info = next_account_info(account_info_iter)?;
+        let buffer_funder_account_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(rent_sysvar_info)?;
 
-        let multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
-
-        let (_account, multisig_nonce) = Pubkey::find_program_address(
-            &[br"multisig", &multisig_account_data.seed.to_le_bytes()],
-            program_id,
-        );
-
-        let multisig_account_seeds: &[&[_]] = &[
-            br"multisig",
-            &multisig_account_data.seed.to_le_bytes(),
-            &[multisig_nonce],
-        ];
-
-        let mut transaction_account_data =
-            Transaction::unpack_from_slice(&transaction_account_info.data.borrow())?;
+        if !proposer_account_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
 
-        if transaction_account_data.multisig != *multisig_account_info.key {
+        if buffer_account_info.owner != program_id {
             return Err(ProgramError::InvalidAccountData);
         }
 
-        // Has this been executed already?
-        if transaction_account_data.did_execute {
-            return Err(MultisigError::AlreadyExecuted.into());
-        }
+        let buffer_account_data = TransactionBuffer::unpack(&buffer_account_info.data.borrow())?;
 
-        // Do we have enough signers.
-        let sig_count = transaction_account_data
-            .signers
-            .iter()
-            .filter(|&did_sign| *did_sign)
-            .count() as u64;
-        if sig_count < multisig_account_data.threshold {
-            return Err(MultisigError::NotEnoughSigners.into());
+        if buffer_account_data.proposer != *proposer_account_info.key
+            || buffer_account_data.multisig != *multisig_account_info.key
+            || buffer_account_data.funder != *buffer_funder_account_info.key
+        {
+            return Err(ProgramError::InvalidAccountData);
         }
 
-        // Execute the transaction signed by the multisig.
-        let mut ix: Instruction = (&transaction_account_data).into();
-        ix.accounts = ix
-            .accounts
+        require!(
+            buffer_account_data.data.len() == buffer_account_data.final_size as usize
+                && hash::hash(&buffer_account_data.data).to_bytes()
+                    == buffer_account_data.final_hash,
+            MultisigError::TransactionBufferMismatch
+        );
+
+        create_transaction_account(
+            program_id,
+            funder_account_info,
+            proposer_account_info,
+            multisig_account_info,
+            transaction_account_info,
+            system_program_info,
+            rent,
+            buffer_account_data.program_id,
+            buffer_account_data.accounts,
+            vec![],
+            vec![],
+            vec![],
+            buffer_account_data.data,
+        )?;
+
+        close_account(buffer_account_info, buffer_funder_account_info)?;
+
+        Ok(())
+    }
+
+    fn process_close_transaction_buffer(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let proposer_account_info = next_account_info(account_info_iter)?;
+        let buffer_account_info = next_account_info(account_info_iter)?;
+        let buffer_funder_account_info = next_account_info(account_info_iter)?;
+
+        if !proposer_account_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if buffer_account_info.owner != program_id {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let buffer_account_data = TransactionBuffer::unpack(&buffer_account_info.data.borrow())?;
+
+        if buffer_account_data.proposer != *proposer_account_info.key {
+            return Err(MultisigError::InvalidOwner.into());
+        }
+
+        if buffer_account_data.funder != *buffer_funder_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        close_account(buffer_account_info, buffer_funder_account_info)?;
+
+        Ok(())
+    }
+
+    fn process_update_transaction(
+        _program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        pid: Pubkey,
+        accs: Vec<TransactionAccount>,
+        lookup_tables: Vec<Pubkey>,
+        lookup_accounts: Vec<TransactionLookupAccount>,
+        data: Vec<u8>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let funder_account_info = next_account_info(account_info_iter)?;
+        let proposer_account_info = next_account_info(account_info_iter)?;
+        let multisig_account_info = next_account_info(account_info_iter)?;
+        let transaction_account_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent_sysvar_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(rent_sysvar_info)?;
+
+        if !proposer_account_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
+        let mut transaction_account_data =
+            Transaction::unpack_from_slice(&transaction_account_info.data.borrow())?;
+
+        if transaction_account_data.multisig != *multisig_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if transaction_account_data.proposer != *proposer_account_info.key {
+            return Err(MultisigError::InvalidOwner.into());
+        }
+
+        if transaction_account_data.did_execute {
+            return Err(MultisigError::AlreadyExecuted.into());
+        }
+
+        if transaction_account_data.is_cancelled {
+            return Err(MultisigError::InvalidTransaction.into());
+        }
+
+        require!(
+            multisig_account_data.is_program_allowed(&pid),
+            MultisigError::ProgramNotAllowed
+        );
+
+        let owner_index = multisig_account_data
+            .owners
             .iter()
-            .map(|acc| {
-                let mut acc = acc.clone();
-                if &acc.pubkey == multisig_account_info.key {
-                    acc.is_signer = true;
-                }
-                acc
-            })
-            .collect();
+            .position(|a| a == proposer_account_info.key)
+            .ok_or(MultisigError::InvalidOwner)?;
 
-        let accounts = account_info_iter.cloned().collect::<Vec<_>>();
+        // Has anyone but the proposer approved it?
+        let is_approved_by_others = transaction_account_data
+            .signers
+            .iter()
+            .enumerate()
+            .any(|(index, did_sign)| *did_sign && index != owner_index);
+        if is_approved_by_others {
+            return Err(MultisigError::TransactionAlreadySigned.into());
+        }
 
-        invoke_signed(&ix, &accounts, &[multisig_account_seeds])?;
+        let mut signers = Vec::new();
+        signers.resize(multisig_account_data.owners.len(), false);
+        signers[owner_index] = true;
 
-        // Burn the transaction to ensure one time use.
-        transaction_account_data.did_execute = true;
+        transaction_account_data.program_id = pid;
+        transaction_account_data.accounts = accs;
+        transaction_account_data.data = data;
+        transaction_account_data.lookup_addresses = resolve_lookup_accounts(
+            &lookup_tables,
+            &lookup_accounts,
+            account_info_iter.as_slice(),
+        )?;
+        transaction_account_data.lookup_tables = lookup_tables;
+        transaction_account_data.lookup_accounts = lookup_accounts;
+        transaction_account_data.signers = signers;
+        transaction_account_data.owners_epoch = multisig_account_data.owners_epoch;
+        transaction_account_data.revision = transaction_account_data
+            .revision
+            .checked_add(1)
+            .ok_or(MultisigError::Overflow)?;
+
+        // Room for the return data stored on execution
+        let data_len = transaction_account_data.try_to_vec()?.len() + MAX_TRANSACTION_RETURN_DATA;
+
+        if data_len != transaction_account_info.data_len() {
+            realloc_account(
+                transaction_account_info,
+                funder_account_info,
+                system_program_info,
+                rent,
+                data_len,
+            )?;
+        }
 
         transaction_account_data.pack_into_slice(&mut transaction_account_info.data.borrow_mut());
 
-        // Remove transaction from pending list
-        let mut multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
+        Ok(())
+    }
+
+    fn process_approve(_program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let proposer_account_info = next_account_info(account_info_iter)?;
+        let transaction_account_info = next_account_info(account_info_iter)?;
+        let multisig_account_info = next_account_info(account_info_iter)?;
+
+        if !proposer_account_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
+        let mut transaction_account_data =
+            Transaction::unpack_from_slice(&transaction_account_info.data.borrow())?;
+
+        if transaction_account_data.multisig != *multisig_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if transaction_account_data.did_execute {
+            return Err(MultisigError::AlreadyExecuted.into());
+        }
+
+        if transaction_account_data.is_cancelled {
+            return Err(MultisigError::InvalidTransaction.into());
+        }
 
-        let transaction_index = multisig_account_data
-            .pending_transactions
+        if transaction_account_data.owners_epoch != multisig_account_data.owners_epoch {
+            return Err(MultisigError::OwnersChanged.into());
+        }
+
+        let owner_index = multisig_account_data
+            .owners
             .iter()
-            .position(|value| value == transaction_account_info.key)
-            .ok_or(MultisigError::InvalidTransaction)?;
+            .position(|value| value == proposer_account_info.key)
+            .ok_or(MultisigError::InvalidOwner)?;
 
-        multisig_account_data
-            .pending_transactions
-            .remove(transaction_index);
+        // Owners added after the transaction was created have no approval slot
+        *transaction_account_data
+            .signers
+            .get_mut(owner_index)
+            .ok_or(MultisigError::InvalidTransaction)? = true;
 
-        Multisig::pack(
-            multisig_account_data,
-            &mut multisig_account_info.data.borrow_mut(),
-        )?;
+        transaction_account_data.pack_into_slice(&mut transaction_account_info.data.borrow_mut());
 
         Ok(())
     }
 
-    fn process_delete_pending_transaction(
-        program_id: &Pubkey,
+    fn process_approve_with_signatures(
+        _program_id: &Pubkey,
         accounts: &[AccountInfo],
-        pending_transaction: Pubkey,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
+        let transaction_account_info = next_account_info(account_info_iter)?;
         let multisig_account_info = next_account_info(account_info_iter)?;
+        let instructions_sysvar_info = next_account_info(account_info_iter)?;
 
-        if !multisig_account_info.is_signer {
+        if *instructions_sysvar_info.key != instructions::id() {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
+        let mut transaction_account_data =
+            Transaction::unpack_from_slice(&transaction_account_info.data.borrow())?;
+
+        if transaction_account_data.multisig != *multisig_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if transaction_account_data.did_execute {
+            return Err(MultisigError::AlreadyExecuted.into());
+        }
+
+        if transaction_account_data.is_cancelled {
+            return Err(MultisigError::InvalidTransaction.into());
+        }
+
+        if transaction_account_data.owners_epoch != multisig_account_data.owners_epoch {
+            return Err(MultisigError::OwnersChanged.into());
+        }
+
+        // Signatures commit to the instruction stored in the transaction
+        let instruction_hash = transaction_account_data.get_instruction_hash();
+
+        let message = get_approval_message(
+            multisig_account_info.key,
+            transaction_account_info.key,
+            transaction_account_data.revision,
+            &instruction_hash,
+        );
+        let eth_message = get_eth_approval_message(
+            multisig_account_info.key,
+            transaction_account_info.key,
+            transaction_account_data.revision,
+            &instruction_hash,
+        );
+
+        let mut approved = false;
+
+        let mut instruction_index = 0;
+        while let Ok(ix) =
+            instructions::load_instruction_at_checked(instruction_index, instructions_sysvar_info)
+        {
+            let signers = if ix.program_id == ed25519_program::id() {
+                get_ed25519_signers(&ix.data, instruction_index as u16, &message)?
+            } else if ix.program_id == secp256k1_program::id() {
+                get_secp256k1_signers(&ix.data, instruction_index as u8, &eth_message)?
+                    .iter()
+                    .map(get_eth_owner)
+                    .collect()
+            } else {
+                vec![]
+            };
+
+            for signer in signers {
+                // Owners added after the transaction was created have no approval slot
+                if let Some(did_sign) = multisig_account_data
+                    .owners
+                    .iter()
+                    .position(|value| *value == signer)
+                    .and_then(|owner_index| transaction_account_data.signers.get_mut(owner_index))
+                {
+                    *did_sign = true;
+                    approved = true;
+                }
+            }
+
+            instruction_index += 1;
+        }
+
+        require!(approved, MultisigError::InvalidOwner);
+
+        transaction_account_data.pack_into_slice(&mut transaction_account_info.data.borrow_mut());
+
+        Ok(())
+    }
+
+    fn process_execute_transaction(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let multisig_account_info = next_account_info(account_info_iter)?;
+        let transaction_account_info = next_account_info(account_info_iter)?;
+        let executor_account_info = next_account_info(account_info_iter)?;
+
+        if !executor_account_info.is_signer {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
         let mut multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
 
-        let (multisig_account, _nonce) = Pubkey::find_program_address(
+        let (multisig_account, multisig_nonce) = Pubkey::find_program_address(
             &[br"multisig", &multisig_account_data.seed.to_le_bytes()],
             program_id,
         );
 
+        // Signer seeds are only granted to the genuine multisig account
         if multisig_account != *multisig_account_info.key {
             return Err(ProgramError::InvalidAccountData);
         }
 
-        multisig_account_data
-            .pending_transactions
-            .retain(|x| *x != pending_transaction);
+        let multisig_account_seeds: &[&[_]] = &[
+            br"multisig",
+            &multisig_account_data.seed.to_le_bytes(),
+            &[multisig_nonce],
+        ];
+
+        // System owned account holding the lamports of the multisig
+        let (vault_account, vault_nonce) = Pubkey::find_program_address(
+            &[br"vault", multisig_account_info.key.as_ref()],
+            program_id,
+        );
+
+        let vault_account_seeds: &[&[_]] = &[
+            br"vault",
+            multisig_account_info.key.as_ref(),
+            &[vault_nonce],
+        ];
+
+        let mut transaction_account_data =
+            Transaction::unpack_from_slice(&transaction_account_info.data.borrow())?;
+
+        if transaction_account_data.multisig != *multisig_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // Has this been executed already?
+        if transaction_account_data.did_execute {
+            return Err(MultisigError::AlreadyExecuted.into());
+        }
+
+        // Has this been deleted from pending?
+        if transaction_account_data.is_cancelled {
+            return Err(MultisigError::InvalidTransaction.into());
+        }
+
+        // Were the approvals collected under a different set of owners?
+        if transaction_account_data.owners_epoch != multisig_account_data.owners_epoch {
+            return Err(MultisigError::OwnersChanged.into());
+        }
+
+        // Was the program removed from the allowlist since?
+        if !multisig_account_data.is_program_allowed(&transaction_account_data.program_id) {
+            return Err(MultisigError::ProgramNotAllowed.into());
+        }
+
+        // Do we have enough signers.
+        let sig_count = transaction_account_data.get_approval_count();
+        let threshold = multisig_account_data.get_threshold(
+            &transaction_account_data.program_id,
+            &transaction_account_data.data,
+        );
+        if sig_count < threshold {
+            return Err(MultisigError::NotEnoughSigners.into());
+        }
+
+        let accounts = account_info_iter.cloned().collect::<Vec<_>>();
+
+        // Do the lookup tables still resolve to the proposed accounts?
+        let lookup_addresses = resolve_lookup_accounts(
+            &transaction_account_data.lookup_tables,
+            &transaction_account_data.lookup_accounts,
+            &accounts,
+        )?;
+        if lookup_addresses != transaction_account_data.lookup_addresses {
+            return Err(MultisigError::InvalidLookupAccount.into());
+        }
+
+        // Execute the transaction signed by the multisig.
+        let mut ix: Instruction = (&transaction_account_data).into();
+        ix.accounts = ix
+            .accounts
+            .iter()
+            .map(|acc| {
+                let mut acc = acc.clone();
+                if &acc.pubkey == multisig_account_info.key || acc.pubkey == vault_account {
+                    acc.is_signer = true;
+                }
+                acc
+            })
+            .collect();
+
+        // Burn the transaction to ensure one time use. State is stored before
+        // the call, so the invoked instruction observes it already executed.
+        transaction_account_data.did_execute = true;
+
+        transaction_account_data.pack_into_slice(&mut transaction_account_info.data.borrow_mut());
+
+        multisig_account_data.pending_transaction_count = multisig_account_data
+            .pending_transaction_count
+            .saturating_sub(1);
 
         Multisig::pack(
             multisig_account_data,
             &mut multisig_account_info.data.borrow_mut(),
         )?;
 
+        invoke_signed(
+            &ix,
+            &accounts,
+            &[multisig_account_seeds, vault_account_seeds],
+        )?;
+
+        // Reload the state, the invoked instruction may have updated it
+        let mut transaction_account_data =
+            Transaction::unpack_from_slice(&transaction_account_info.data.borrow())?;
+
+        transaction_account_data.executed_slot = Clock::get()?.slot;
+        transaction_account_data.executor = *executor_account_info.key;
+
+        // Return data may also have been set by a program the invoked one called
+        if let Some((return_program_id, mut return_data)) = get_return_data() {
+            if return_program_id == transaction_account_data.program_id {
+                return_data.truncate(MAX_TRANSACTION_RETURN_DATA);
+                transaction_account_data.return_data = return_data;
+            }
+        }
+
+        transaction_account_data.pack_into_slice(&mut transaction_account_info.data.borrow_mut());
+
         Ok(())
     }
+
+    fn process_check_transaction(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let multisig_account_info = next_account_info(account_info_iter)?;
+        let transaction_account_info = next_account_info(account_info_iter)?;
+        let _executor_account_info = next_account_info(account_info_iter)?;
+
+        let multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
+
+        let (multisig_account, _nonce) = Pubkey::find_program_address(
+            &[br"multisig", &multisig_account_data.seed.to_le_bytes()],
+            program_id,
+        );
+
+        if multisig_account != *multisig_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (vault_account, _nonce) = Pubkey::find_program_address(
+            &[br"vault", multisig_account_info.key.as_ref()],
+            program_id,
+        );
+
+        let transaction_account_data =
+            Transaction::unpack_from_slice(&transaction_account_info.data.borrow())?;
+
+        if transaction_account_data.multisig != *multisig_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mut blocking_reasons = Vec::new();
+
+        if transaction_account_data.did_execute {
+            blocking_reasons.push(BlockingReason::AlreadyExecuted);
+        }
+
+        if transaction_account_data.is_cancelled {
+            blocking_reasons.push(BlockingReason::Cancelled);
+        }
+
+        // Executed transactions may have changed the owners themselves
+        if blocking_reasons.is_empty()
+            && transaction_account_data.owners_epoch != multisig_account_data.owners_epoch
+        {
+            blocking_reasons.push(BlockingReason::OwnersChanged);
+        }
+
+        if !multisig_account_data.is_program_allowed(&transaction_account_data.program_id) {
+            blocking_reasons.push(BlockingReason::ProgramNotAllowed);
+        }
+
+        let missing_owners = transaction_account_data.get_missing_owners(&multisig_account_data);
+
+        let approvals = transaction_account_data.get_approval_count();
+        let threshold = multisig_account_data.get_threshold(
+            &transaction_account_data.program_id,
+            &transaction_account_data.data,
+        );
+
+        if approvals < threshold {
+            blocking_reasons.push(BlockingReason::NotEnoughSigners);
+        }
+
+        // Accounts the executor passed along to be used by the invoked instruction
+        let account_infos = account_info_iter.as_slice();
+
+        let account_metas = transaction_account_data
+            .get_accounts()
+            .iter()
+            .map(AccountMeta::from)
+            .collect::<Vec<_>>();
+
+        match resolve_lookup_accounts(
+            &transaction_account_data.lookup_tables,
+            &transaction_account_data.lookup_accounts,
+            account_infos,
+        ) {
+            Ok(lookup_addresses)
+                if lookup_addresses == transaction_account_data.lookup_addresses => {}
+            Ok(_) => blocking_reasons.push(BlockingReason::LookupTableChanged),
+            Err(_) => blocking_reasons.push(BlockingReason::MissingAccount),
+        }
+
+        for account in &account_metas {
+            let account_info = match account_infos.iter().find(|a| a.key == &account.pubkey) {
+                Some(account_info) => account_info,
+                None => {
+                    blocking_reasons.push(BlockingReason::MissingAccount);
+                    continue;
+                }
+            };
+
+            if account.is_writable && !account_info.is_writable {
+                blocking_reasons.push(BlockingReason::AccountNotWritable);
+            }
+
+            // Multisig and vault signatures are provided by the program itself
+            if account.is_signer
+                && !account_info.is_signer
+                && account.pubkey != *multisig_account_info.key
+                && account.pubkey != vault_account
+            {
+                blocking_reasons.push(BlockingReason::AccountNotSigner);
+            }
+        }
+
+        // Report every reason once, in the order they were found
+        let mut reported = Vec::with_capacity(blocking_reasons.len());
+        blocking_reasons.retain(|reason| {
+            let is_new = !reported.contains(reason);
+            reported.push(*reason);
+            is_new
+        });
+
+        let status = TransactionStatus {
+            approvals,
+            threshold,
+            missing_owners,
+            blocking_reasons,
+            timelock_remaining: None,
+        };
+
+        set_return_data(&status.try_to_vec()?);
+
+        Ok(())
+    }
+
+    fn process_delete_pending_transaction(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        pending_transaction: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let multisig_account_info = next_account_info(account_info_iter)?;
+        let transaction_account_info = next_account_info(account_info_iter)?;
+
+        if !multisig_account_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
+
+        let (multisig_account, _nonce) = Pubkey::find_program_address(
+            &[br"multisig", &multisig_account_data.seed.to_le_bytes()],
+            program_id,
+        );
+
+        if multisig_account != *multisig_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if pending_transaction != *transaction_account_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let mut transaction_account_data =
+            Transaction::unpack_from_slice(&transaction_account_info.data.borrow())?;
+
+        if transaction_account_data.multisig != *multisig_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        require!(
+            !transaction_account_data.did_execute,
+            MultisigError::AlreadyExecuted
+        );
+
+        require!(
+            !transaction_account_data.is_cancelled,
+            MultisigError::InvalidTransaction
+        );
+
+        transaction_account_data.is_cancelled = true;
+
+        transaction_account_data.pack_into_slice(&mut transaction_account_info.data.borrow_mut());
+
+        multisig_account_data.pending_transaction_count = multisig_account_data
+            .pending_transaction_count
+            .saturating_sub(1);
+
+        Multisig::pack(
+            multisig_account_data,
+            &mut multisig_account_info.data.borrow_mut(),
+        )?;
+
+        Ok(())
+    }
+
+    fn process_resize(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        max_signers: u32,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let multisig_account_info = next_account_info(account_info_iter)?;
+        let funder_account_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent_sysvar_info = next_account_info(account_info_iter)?;
+        let recovery_account_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(rent_sysvar_info)?;
+
+        if !multisig_account_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
+
+        let (multisig_account, _nonce) = Pubkey::find_program_address(
+            &[br"multisig", &multisig_account_data.seed.to_le_bytes()],
+            program_id,
+        );
+
+        if multisig_account != *multisig_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (recovery_account, _nonce) = Pubkey::find_program_address(
+            &[br"recovery", multisig_account_info.key.as_ref()],
+            program_id,
+        );
+
+        if recovery_account != *recovery_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        require!(
+            multisig_account_data.owners.len() <= max_signers as usize
+                && multisig_account_data.get_max_threshold() <= max_signers as u64,
+            MultisigError::OwnersOverflow
+        );
+
+        multisig_account_data.max_signers = max_signers;
+
+        let data_len = Multisig::get_len(
+            max_signers,
+            multisig_account_data.allowed_programs.len() as u32,
+        );
+
+        realloc_account(
+            multisig_account_info,
+            funder_account_info,
+            system_program_info,
+            rent,
+            data_len,
+        )?;
+
+        Multisig::pack(
+            multisig_account_data,
+            &mut multisig_account_info.data.borrow_mut(),
+        )?;
+
+        // Recovery account has room for a new owner set of the multisig
+        // capacity, it exists only if recovery was ever configured
+        if recovery_account_info.owner == program_id {
+            let recovery_account_data = Recovery::unpack(&recovery_account_info.data.borrow())?;
+
+            // A pending recovery keeps its owners until it is vetoed or finalized
+            let recovery_max_signers = recovery_account_data
+                .pending
+                .as_ref()
+                .map(|pending| pending.owners.len() as u32)
+                .unwrap_or_default()
+                .max(max_signers);
+
+            let data_len = Recovery::get_len(
+                recovery_account_data.guardians.len() as u32,
+                recovery_max_signers,
+            );
+
+            realloc_account(
+                recovery_account_info,
+                funder_account_info,
+                system_program_info,
+                rent,
+                data_len,
+            )?;
+
+            Recovery::pack(
+                recovery_account_data,
+                &mut recovery_account_info.data.borrow_mut(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn process_add_allowed_program(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        allowed_program: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let multisig_account_info = next_account_info(account_info_iter)?;
+        let funder_account_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent_sysvar_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(rent_sysvar_info)?;
+
+        if !multisig_account_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
+
+        let (multisig_account, _nonce) = Pubkey::find_program_address(
+            &[br"multisig", &multisig_account_data.seed.to_le_bytes()],
+            program_id,
+        );
+
+        if multisig_account != *multisig_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if !multisig_account_data
+            .allowed_programs
+            .contains(&allowed_program)
+        {
+            multisig_account_data.allowed_programs.push(allowed_program);
+        }
+
+        let data_len = Multisig::get_len(
+            multisig_account_data.max_signers,
+            multisig_account_data.allowed_programs.len() as u32,
+        );
+
+        if data_len > multisig_account_info.data_len() {
+            realloc_account(
+                multisig_account_info,
+                funder_account_info,
+                system_program_info,
+                rent,
+                data_len,
+            )?;
+        }
+
+        Multisig::pack(
+            multisig_account_data,
+            &mut multisig_account_info.data.borrow_mut(),
+        )?;
+
+        Ok(())
+    }
+
+    fn process_remove_allowed_program(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        allowed_program: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let multisig_account_info = next_account_info(account_info_iter)?;
+
+        if !multisig_account_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
+
+        let (multisig_account, _nonce) = Pubkey::find_program_address(
+            &[br"multisig", &multisig_account_data.seed.to_le_bytes()],
+            program_id,
+        );
+
+        if multisig_account != *multisig_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        multisig_account_data
+            .allowed_programs
+            .retain(|x| *x != allowed_program);
+
+        Multisig::pack(
+            multisig_account_data,
+            &mut multisig_account_info.data.borrow_mut(),
+        )?;
+
+        Ok(())
+    }
+
+    fn process_configure_recovery(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        guardians: Vec<Pubkey>,
+        guardian_threshold: u64,
+        delay: i64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let multisig_account_info = next_account_info(account_info_iter)?;
+        let funder_account_info = next_account_info(account_info_iter)?;
+        let recovery_account_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let rent_sysvar_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(rent_sysvar_info)?;
+
+        if !multisig_account_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
+
+        let (multisig_account, _nonce) = Pubkey::find_program_address(
+            &[br"multisig", &multisig_account_data.seed.to_le_bytes()],
+            program_id,
+        );
+
+        if multisig_account != *multisig_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (recovery_account, recovery_nonce) = Pubkey::find_program_address(
+            &[br"recovery", multisig_account_info.key.as_ref()],
+            program_id,
+        );
+
+        if recovery_account != *recovery_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        assert_unique_owners(&guardians)?;
+
+        require!(
+            guardians.is_empty()
+                || (guardian_threshold <= guardians.len() as u64
+                    && guardian_threshold >= MIN_SIGNERS as u64),
+            MultisigError::InvalidThreshold
+        );
+
+        require!(
+            delay >= MIN_RECOVERY_DELAY,
+            MultisigError::InvalidRecoveryDelay
+        );
+
+        let data_len = Recovery::get_len(guardians.len() as u32, multisig_account_data.max_signers);
+
+        if recovery_account_info.data_is_empty() {
+            let recovery_account_signer_seeds: &[&[_]] = &[
+                br"recovery",
+                multisig_account_info.key.as_ref(),
+                &[recovery_nonce],
+            ];
+
+            invoke_signed(
+                &system_instruction::create_account(
+                    funder_account_info.key,
+                    recovery_account_info.key,
+                    1.max(rent.minimum_balance(data_len)),
+                    data_len as u64,
+                    program_id,
+                ),
+                &[
+                    funder_account_info.clone(),
+                    recovery_account_info.clone(),
+                    system_program_info.clone(),
+                ],
+                &[recovery_account_signer_seeds],
+            )?;
+        } else {
+            realloc_account(
+                recovery_account_info,
+                funder_account_info,
+                system_program_info,
+                rent,
+                data_len,
+            )?;
+        }
+
+        MultisigEvent::RecoveryConfigured {
+            multisig: *multisig_account_info.key,
+            guardians: guardians.clone(),
+            guardian_threshold,
+            delay,
+        }
+        .emit()?;
+
+        let recovery = Recovery {
+            is_initialized: true,
+            multisig: *multisig_account_info.key,
+            guardians,
+            guardian_threshold,
+            delay,
+            pending: None,
+        };
+
+        Recovery::pack(recovery, &mut recovery_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_start_recovery(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        owners: Vec<Pubkey>,
+        threshold: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let recovery_account_info = next_account_info(account_info_iter)?;
+        let multisig_account_info = next_account_info(account_info_iter)?;
+
+        let mut recovery_account_data = Recovery::unpack(&recovery_account_info.data.borrow())?;
+
+        if recovery_account_info.owner != program_id
+            || multisig_account_info.owner != program_id
+            || recovery_account_data.multisig != *multisig_account_info.key
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
+
+        require!(
+            !recovery_account_data.guardians.is_empty(),
+            MultisigError::RecoveryNotConfigured
+        );
+
+        require!(
+            recovery_account_data.pending.is_none(),
+            MultisigError::RecoveryInProgress
+        );
+
+        let mut guardians = account_info_iter
+            .filter(|account| {
+                account.is_signer && recovery_account_data.guardians.contains(account.key)
+            })
+            .map(|account| *account.key)
+            .collect::<Vec<_>>();
+        guardians.sort();
+        guardians.dedup();
+
+        require!(
+            guardians.len() as u64 >= recovery_account_data.guardian_threshold,
+            MultisigError::NotEnoughSigners
+        );
+
+        assert_unique_owners(&owners)?;
+
+        require!(!owners.is_empty(), MultisigError::InvalidOwnersLen);
+
+        require!(
+            owners.len() <= multisig_account_data.max_signers as usize,
+            MultisigError::OwnersOverflow
+        );
+
+        require!(
+            threshold <= owners.len() as u64 && threshold >= MIN_SIGNERS as u64,
+            MultisigError::InvalidThreshold
+        );
+
+        let executable_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(recovery_account_data.delay)
+            .ok_or(MultisigError::Overflow)?;
+
+        MultisigEvent::RecoveryStarted {
+            multisig: *multisig_account_info.key,
+            guardians,
+            owners: owners.clone(),
+            threshold,
+            executable_at,
+        }
+        .emit()?;
+
+        recovery_account_data.pending = Some(PendingRecovery {
+            owners,
+            threshold,
+            executable_at,
+        });
+
+        Recovery::pack(
+            recovery_account_data,
+            &mut recovery_account_info.data.borrow_mut(),
+        )?;
+
+        Ok(())
+    }
+
+    fn process_veto_recovery(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let recovery_account_info = next_account_info(account_info_iter)?;
+        let multisig_account_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+
+        if !owner_account_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut recovery_account_data = Recovery::unpack(&recovery_account_info.data.borrow())?;
+
+        if recovery_account_info.owner != program_id
+            || multisig_account_info.owner != program_id
+            || recovery_account_data.multisig != *multisig_account_info.key
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
+
+        require!(
+            multisig_account_data
+                .owners
+                .contains(owner_account_info.key),
+            MultisigError::InvalidOwner
+        );
+
+        require!(
+            recovery_account_data.pending.is_some(),
+            MultisigError::NoPendingRecovery
+        );
+
+        MultisigEvent::RecoveryVetoed {
+            multisig: *multisig_account_info.key,
+            owner: *owner_account_info.key,
+        }
+        .emit()?;
+
+        recovery_account_data.pending = None;
+
+        Recovery::pack(
+            recovery_account_data,
+            &mut recovery_account_info.data.borrow_mut(),
+        )?;
+
+        Ok(())
+    }
+
+    fn process_finalize_recovery(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let recovery_account_info = next_account_info(account_info_iter)?;
+        let multisig_account_info = next_account_info(account_info_iter)?;
+
+        let mut recovery_account_data = Recovery::unpack(&recovery_account_info.data.borrow())?;
+
+        if recovery_account_info.owner != program_id
+            || multisig_account_info.owner != program_id
+            || recovery_account_data.multisig != *multisig_account_info.key
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mut multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
+
+        let pending = recovery_account_data
+            .pending
+            .take()
+            .ok_or(MultisigError::NoPendingRecovery)?;
+
+        require!(
+            Clock::get()?.unix_timestamp >= pending.executable_at,
+            MultisigError::RecoveryDelayNotPassed
+        );
+
+        require!(
+            pending.owners.len() <= multisig_account_data.max_signers as usize,
+            MultisigError::OwnersOverflow
+        );
+
+        MultisigEvent::RecoveryFinalized {
+            multisig: *multisig_account_info.key,
+            owners: pending.owners.clone(),
+            threshold: pending.threshold,
+        }
+        .emit()?;
+
+        // Action thresholds may not fit the new owners, so they are reset
+        multisig_account_data.owners = pending.owners;
+        multisig_account_data.threshold = pending.threshold;
+        multisig_account_data.admin_threshold = None;
+        multisig_account_data.upgrade_threshold = None;
+        multisig_account_data.bump_owners_epoch()?;
+
+        Multisig::pack(
+            multisig_account_data,
+            &mut multisig_account_info.data.borrow_mut(),
+        )?;
+
+        Recovery::pack(
+            recovery_account_data,
+            &mut recovery_account_info.data.borrow_mut(),
+        )?;
+
+        Ok(())
+    }
+
+    fn process_migrate_multisig(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let multisig_account_info = next_account_info(account_info_iter)?;
+
+        if multisig_account_info.data_len() != Multisig::LEGACY_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let legacy_account_data = LegacyMultisig::unpack(&multisig_account_info.data.borrow())?;
+
+        let (multisig_account, _nonce) = Pubkey::find_program_address(
+            &[br"multisig", &legacy_account_data.seed.to_le_bytes()],
+            program_id,
+        );
+
+        if multisig_account != *multisig_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // Legacy transaction accounts can not be executed by this program,
+        // so their pending list is not carried over
+        let multisig_account_data = Multisig {
+            is_initialized: true,
+            owners: legacy_account_data.owners,
+            threshold: legacy_account_data.threshold,
+            pending_transaction_count: 0,
+            transaction_index: 0,
+            seed: legacy_account_data.seed,
+            max_signers: MAX_SIGNERS as u32,
+            allowed_programs: vec![],
+            admin_threshold: None,
+            upgrade_threshold: None,
+            owners_epoch: 0,
+        };
+
+        // The legacy account is larger than the new one, so the
+        // reallocation never requires more lamports
+        multisig_account_info.realloc(Multisig::LEN, false)?;
+
+        Multisig::pack(
+            multisig_account_data,
+            &mut multisig_account_info.data.borrow_mut(),
+        )?;
+
+        Ok(())
+    }
+
+    fn process_close_multisig(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let multisig_account_info = next_account_info(account_info_iter)?;
+        let recipient_account_info = next_account_info(account_info_iter)?;
+        let recovery_account_info = next_account_info(account_info_iter)?;
+        let vault_account_info = next_account_info(account_info_iter)?;
+
+        if !multisig_account_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
+
+        let (multisig_account, _nonce) = Pubkey::find_program_address(
+            &[br"multisig", &multisig_account_data.seed.to_le_bytes()],
+            program_id,
+        );
+
+        if multisig_account != *multisig_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (recovery_account, _nonce) = Pubkey::find_program_address(
+            &[br"recovery", multisig_account_info.key.as_ref()],
+            program_id,
+        );
+
+        if recovery_account != *recovery_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (vault_account, _nonce) = Pubkey::find_program_address(
+            &[br"vault", multisig_account_info.key.as_ref()],
+            program_id,
+        );
+
+        if vault_account != *vault_account_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if recipient_account_info.key == multisig_account_info.key
+            || recipient_account_info.key == recovery_account_info.key
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // Lamports left in the vault could no longer be moved
+        require!(
+            vault_account_info.lamports() == 0,
+            MultisigError::VaultNotDrained
+        );
+
+        require!(
+            multisig_account_data.pending_transaction_count == 0,
+            MultisigError::PendingTransactionExist
+        );
+
+        // Remaining accounts are token accounts held by the multisig, except
+        // the program account which proposals carry along for invocation
+        for vault_account_info in account_info_iter.filter(|info| info.key != program_id) {
+            assert_drained_vault(vault_account_info, multisig_account_info.key)?;
+        }
+
+        // Recovery account exists only if recovery was ever configured
+        if recovery_account_info.owner == program_id {
+            close_account(recovery_account_info, recipient_account_info)?;
+        }
+
+        // Transaction accounts of the multisig stay open, and a multisig created
+        // again at the same address would start over at their indices. The
+        // emptied account is kept rent exempt so the address can not be reused.
+        multisig_account_info.realloc(0, false)?;
+
+        let lamports = multisig_account_info
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(0));
+
+        **recipient_account_info.try_borrow_mut_lamports()? = recipient_account_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(MultisigError::Overflow)?;
+        **multisig_account_info.try_borrow_mut_lamports()? -= lamports;
+
+        Ok(())
+    }
+}
+
+/// Create a transaction account at the next index of the multisig,
+/// approved by the proposer
+#[allow(clippy::too_many_arguments)]
+fn create_transaction_account<'a>(
+    program_id: &Pubkey,
+    funder_account_info: &AccountInfo<'a>,
+    proposer_account_info: &AccountInfo<'a>,
+    multisig_account_info: &AccountInfo<'a>,
+    transaction_account_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    rent: &Rent,
+    pid: Pubkey,
+    accs: Vec<TransactionAccount>,
+    lookup_tables: Vec<Pubkey>,
+    lookup_accounts: Vec<TransactionLookupAccount>,
+    lookup_addresses: Vec<Pubkey>,
+    data: Vec<u8>,
+) -> ProgramResult {
+    if multisig_account_info.owner != program_id {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let mut multisig_account_data = Multisig::unpack(&multisig_account_info.data.borrow())?;
+
+    let (multisig_account, _nonce) = Pubkey::find_program_address(
+        &[br"multisig", &multisig_account_data.seed.to_le_bytes()],
+        program_id,
+    );
+
+    if multisig_account != *multisig_account_info.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    require!(
+        multisig_account_data.is_program_allowed(&pid),
+        MultisigError::ProgramNotAllowed
+    );
+
+    let owner_index = multisig_account_data
+        .owners
+        .iter()
+        .position(|a| a == proposer_account_info.key)
+        .ok_or(MultisigError::InvalidOwner)?;
+
+    let transaction_index = multisig_account_data
+        .transaction_index
+        .checked_add(1)
+        .ok_or(MultisigError::Overflow)?;
+
+    let (transaction_account, transaction_nonce) = Pubkey::find_program_address(
+        &[
+            br"transaction",
+            multisig_account_info.key.as_ref(),
+            &transaction_index.to_le_bytes(),
+        ],
+        program_id,
+    );
+
+    if transaction_account != *transaction_account_info.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let transaction_account_signer_seeds: &[&[_]] = &[
+        br"transaction",
+        multisig_account_info.key.as_ref(),
+        &transaction_index.to_le_bytes(),
+        &[transaction_nonce],
+    ];
+
+    let mut signers = Vec::new();
+    signers.resize(multisig_account_data.owners.len(), false);
+    signers[owner_index] = true;
+
+    let tx = Transaction {
+        is_initialized: true,
+        multisig: *multisig_account_info.key,
+        index: transaction_index,
+        program_id: pid,
+        accounts: accs,
+        did_execute: false,
+        is_cancelled: false,
+        data,
+        signers,
+        executed_slot: 0,
+        executor: Pubkey::default(),
+        return_data: vec![],
+        proposer: *proposer_account_info.key,
+        revision: 0,
+        lookup_tables,
+        lookup_accounts,
+        lookup_addresses,
+        owners_epoch: multisig_account_data.owners_epoch,
+    };
+
+    // Room for the return data stored on execution
+    let data_len = tx.try_to_vec()?.len() + MAX_TRANSACTION_RETURN_DATA;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            funder_account_info.key,
+            transaction_account_info.key,
+            1.max(rent.minimum_balance(data_len)),
+            data_len as u64,
+            program_id,
+        ),
+        &[
+            funder_account_info.clone(),
+            transaction_account_info.clone(),
+            system_program_info.clone(),
+        ],
+        &[transaction_account_signer_seeds],
+    )?;
+
+    tx.pack_into_slice(&mut transaction_account_info.data.borrow_mut());
+
+    multisig_account_data.transaction_index = transaction_index;
+    multisig_account_data.pending_transaction_count = multisig_account_data
+        .pending_transaction_count
+        .checked_add(1)
+        .ok_or(MultisigError::Overflow)?;
+
+    Multisig::pack(
+        multisig_account_data,
+        &mut multisig_account_info.data.borrow_mut(),
+    )?;
+
+    Ok(())
+}
+
+/// Addresses of the accounts referenced through address lookup tables, read
+/// from the table accounts passed along
+fn resolve_lookup_accounts(
+    lookup_tables: &[Pubkey],
+    lookup_accounts: &[TransactionLookupAccount],
+    account_infos: &[AccountInfo],
+) -> Result<Vec<Pubkey>, ProgramError> {
+    let mut tables = Vec::with_capacity(lookup_tables.len());
+    for table in lookup_tables {
+        let table_account_info = account_infos
+            .iter()
+            .find(|account_info| account_info.key == table)
+            .ok_or(MultisigError::InvalidLookupAccount)?;
+
+        if *table_account_info.owner != address_lookup_table::program::id() {
+            return Err(MultisigError::InvalidLookupAccount.into());
+        }
+
+        tables.push(table_account_info.data.borrow());
+    }
+
+    lookup_accounts
+        .iter()
+        .map(|account| {
+            let offset = LOOKUP_TABLE_META_SIZE + account.address_index as usize * 32;
+            let address = tables
+                .get(account.table_index as usize)
+                .and_then(|data| data.get(offset..offset + 32))
+                .ok_or(MultisigError::InvalidLookupAccount)?;
+            Pubkey::try_from(address).map_err(|_| MultisigError::InvalidLookupAccount.into())
+        })
+        .collect()
+}
+
+/// Reallocate a program owned account, topping it up to the rent exempt
+/// balance of the new size from the funder
+fn realloc_account<'a>(
+    account_info: &AccountInfo<'a>,
+    funder_account_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    rent: &Rent,
+    data_len: usize,
+) -> ProgramResult {
+    let required_lamports = 1.max(rent.minimum_balance(data_len));
+    let current_lamports = account_info.lamports();
+
+    if required_lamports > current_lamports {
+        invoke(
+            &system_instruction::transfer(
+                funder_account_info.key,
+                account_info.key,
+                required_lamports - current_lamports,
+            ),
+            &[
+                funder_account_info.clone(),
+                account_info.clone(),
+                system_program_info.clone(),
+            ],
+        )?;
+    }
+
+    account_info.realloc(data_len, false)?;
+
+    Ok(())
+}
+
+/// Move all lamports of a program owned account to the recipient and
+/// mark it uninitialized
+fn close_account(
+    account_info: &AccountInfo,
+    recipient_account_info: &AccountInfo,
+) -> ProgramResult {
+    let lamports = account_info.lamports();
+
+    **recipient_account_info.try_borrow_mut_lamports()? = recipient_account_info
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(MultisigError::Overflow)?;
+    **account_info.try_borrow_mut_lamports()? = 0;
+
+    account_info.data.borrow_mut().fill(0);
+
+    Ok(())
+}
+
+/// Check that the account is an empty SPL token account of the multisig
+fn assert_drained_vault(account_info: &AccountInfo, multisig: &Pubkey) -> ProgramResult {
+    if *account_info.owner != TOKEN_PROGRAM_ID && *account_info.owner != TOKEN_2022_PROGRAM_ID {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let data = account_info.data.borrow();
+
+    // Token account layout starts with mint, owner and amount
+    let (owner, amount) = match (data.get(32..64), data.get(64..72)) {
+        (Some(owner), Some(amount)) => (owner, amount),
+        _ => return Err(ProgramError::InvalidAccountData),
+    };
+
+    if owner != multisig.as_ref() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    require!(amount == [0; 8], MultisigError::VaultNotDrained);
+
+    Ok(())
 }
 
 fn assert_unique_owners(owners: &[Pubkey]) -> Result<(), ProgramError> {
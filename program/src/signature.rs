@@ -0,0 +1,305 @@
+use borsh::BorshSerialize;
+use solana_program::hash;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::{Pubkey, PUBKEY_BYTES};
+
+use crate::{TransactionAccount, TransactionLookupAccount};
+
+/// Domain separator of off-chain approval messages
+pub const APPROVAL_DOMAIN: &[u8] = b"octusbridge-multisig:approve";
+
+/// Size of an ed25519 signature
+pub const ED25519_SIGNATURE_SIZE: usize = 64;
+/// Size of a secp256k1 signature without recovery id
+pub const SECP256K1_SIGNATURE_SIZE: usize = 64;
+/// Size of an Ethereum address
+pub const ETH_ADDRESS_SIZE: usize = 20;
+
+const ED25519_SIGNATURE_OFFSETS_START: usize = 2;
+const ED25519_SIGNATURE_OFFSETS_SIZE: usize = 14;
+
+const SECP256K1_SIGNATURE_OFFSETS_START: usize = 1;
+const SECP256K1_SIGNATURE_OFFSETS_SIZE: usize = 11;
+
+const ETH_MESSAGE_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n";
+
+/// Hash of the instruction a transaction executes, lookups included, which
+/// approval messages commit to
+pub fn get_instruction_hash(
+    program_id: &Pubkey,
+    accounts: &[TransactionAccount],
+    data: &[u8],
+    lookup_tables: &[Pubkey],
+    lookup_accounts: &[TransactionLookupAccount],
+    lookup_addresses: &[Pubkey],
+) -> [u8; 32] {
+    hash::hashv(&[
+        program_id.as_ref(),
+        &accounts.try_to_vec().unwrap(),
+        &data.try_to_vec().unwrap(),
+        &lookup_tables.try_to_vec().unwrap(),
+        &lookup_accounts.try_to_vec().unwrap(),
+        &lookup_addresses.try_to_vec().unwrap(),
+    ])
+    .to_bytes()
+}
+
+/// Canonical message owners sign to approve a revision of a transaction
+/// off-chain, `instruction_hash` being the one of `get_instruction_hash`
+pub fn get_approval_message(
+    multisig_pubkey: &Pubkey,
+    transaction_pubkey: &Pubkey,
+    revision: u64,
+    instruction_hash: &[u8; 32],
+) -> Vec<u8> {
+    [
+        APPROVAL_DOMAIN,
+        crate::id().as_ref(),
+        multisig_pubkey.as_ref(),
+        transaction_pubkey.as_ref(),
+        &revision.to_le_bytes(),
+        instruction_hash,
+    ]
+    .concat()
+}
+
+/// EIP-191 personal message Ethereum owners sign to approve a revision of
+/// a transaction off-chain
+pub fn get_eth_approval_message(
+    multisig_pubkey: &Pubkey,
+    transaction_pubkey: &Pubkey,
+    revision: u64,
+    instruction_hash: &[u8; 32],
+) -> Vec<u8> {
+    let message = get_approval_message(
+        multisig_pubkey,
+        transaction_pubkey,
+        revision,
+        instruction_hash,
+    );
+
+    [
+        ETH_MESSAGE_PREFIX,
+        message.len().to_string().as_bytes(),
+        &message,
+    ]
+    .concat()
+}
+
+/// Owner entry standing for an Ethereum address.
+///
+/// The address takes the last 20 bytes of the key and the rest is zeroed,
+/// which no ed25519 key can practically match.
+pub fn get_eth_owner(address: &[u8; ETH_ADDRESS_SIZE]) -> Pubkey {
+    let mut owner = [0; PUBKEY_BYTES];
+    owner[PUBKEY_BYTES - ETH_ADDRESS_SIZE..].copy_from_slice(address);
+    Pubkey::new_from_array(owner)
+}
+
+/// Ethereum address of the owner entry, if it stands for one
+pub fn get_eth_address(owner: &Pubkey) -> Option<[u8; ETH_ADDRESS_SIZE]> {
+    let (prefix, address) = owner.as_ref().split_at(PUBKEY_BYTES - ETH_ADDRESS_SIZE);
+
+    match prefix.iter().all(|byte| *byte == 0) {
+        true => address.try_into().ok(),
+        false => None,
+    }
+}
+
+/// Public keys whose signatures over `message` were checked by an ed25519
+/// program instruction at `instruction_index`.
+///
+/// Only signatures with all of their data inside the instruction itself are
+/// taken into account.
+pub fn get_ed25519_signers(
+    data: &[u8],
+    instruction_index: u16,
+    message: &[u8],
+) -> Result<Vec<Pubkey>, ProgramError> {
+    let num_signatures = *data.first().ok_or(ProgramError::InvalidInstructionData)? as usize;
+
+    let mut signers = Vec::with_capacity(num_signatures);
+
+    for i in 0..num_signatures {
+        let start = ED25519_SIGNATURE_OFFSETS_START + i * ED25519_SIGNATURE_OFFSETS_SIZE;
+        let offsets = data
+            .get(start..start + ED25519_SIGNATURE_OFFSETS_SIZE)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        let read_u16 = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]);
+
+        let signature_instruction_index = read_u16(2);
+        let public_key_offset = read_u16(4) as usize;
+        let public_key_instruction_index = read_u16(6);
+        let message_data_offset = read_u16(8) as usize;
+        let message_data_size = read_u16(10) as usize;
+        let message_instruction_index = read_u16(12);
+
+        let is_local = |index: u16| index == u16::MAX || index == instruction_index;
+        if !is_local(signature_instruction_index)
+            || !is_local(public_key_instruction_index)
+            || !is_local(message_instruction_index)
+        {
+            continue;
+        }
+
+        let signed_message = data
+            .get(message_data_offset..message_data_offset + message_data_size)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        if signed_message != message {
+            continue;
+        }
+
+        let public_key = data
+            .get(public_key_offset..public_key_offset + PUBKEY_BYTES)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        signers
+            .push(Pubkey::try_from(public_key).map_err(|_| ProgramError::InvalidInstructionData)?);
+    }
+
+    Ok(signers)
+}
+
+/// Ethereum addresses whose signatures over `message` were checked by
+/// a secp256k1 program instruction at `instruction_index`.
+///
+/// Only signatures with all of their data inside the instruction itself are
+/// taken into account.
+pub fn get_secp256k1_signers(
+    data: &[u8],
+    instruction_index: u8,
+    message: &[u8],
+) -> Result<Vec<[u8; ETH_ADDRESS_SIZE]>, ProgramError> {
+    let num_signatures = *data.first().ok_or(ProgramError::InvalidInstructionData)? as usize;
+
+    let mut signers = Vec::with_capacity(num_signatures);
+
+    for i in 0..num_signatures {
+        let start = SECP256K1_SIGNATURE_OFFSETS_START + i * SECP256K1_SIGNATURE_OFFSETS_SIZE;
+        let offsets = data
+            .get(start..start + SECP256K1_SIGNATURE_OFFSETS_SIZE)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        let read_u16 = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]);
+
+        let signature_instruction_index = offsets[2];
+        let eth_address_offset = read_u16(3) as usize;
+        let eth_address_instruction_index = offsets[5];
+        let message_data_offset = read_u16(6) as usize;
+        let message_data_size = read_u16(8) as usize;
+        let message_instruction_index = offsets[10];
+
+        if signature_instruction_index != instruction_index
+            || eth_address_instruction_index != instruction_index
+            || message_instruction_index != instruction_index
+        {
+            continue;
+        }
+
+        let signed_message = data
+            .get(message_data_offset..message_data_offset + message_data_size)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        if signed_message != message {
+            continue;
+        }
+
+        let eth_address = data
+            .get(eth_address_offset..eth_address_offset + ETH_ADDRESS_SIZE)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        signers.push(
+            eth_address
+                .try_into()
+                .map_err(|_| ProgramError::InvalidInstructionData)?,
+        );
+    }
+
+    Ok(signers)
+}
+
+/// Serialize ed25519 program instruction data checking every signature
+/// against the same message
+pub fn pack_ed25519_instruction_data(
+    signatures: &[(Pubkey, [u8; ED25519_SIGNATURE_SIZE])],
+    message: &[u8],
+) -> Vec<u8> {
+    let entry_size = PUBKEY_BYTES + ED25519_SIGNATURE_SIZE;
+    let data_start =
+        ED25519_SIGNATURE_OFFSETS_START + signatures.len() * ED25519_SIGNATURE_OFFSETS_SIZE;
+    let message_data_offset = data_start + signatures.len() * entry_size;
+
+    let mut data = Vec::with_capacity(message_data_offset + message.len());
+    data.push(signatures.len() as u8);
+    data.push(0);
+
+    for i in 0..signatures.len() {
+        let public_key_offset = data_start + i * entry_size;
+        let signature_offset = public_key_offset + PUBKEY_BYTES;
+
+        for value in [
+            signature_offset as u16,
+            u16::MAX,
+            public_key_offset as u16,
+            u16::MAX,
+            message_data_offset as u16,
+            message.len() as u16,
+            u16::MAX,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    for (pubkey, signature) in signatures {
+        data.extend_from_slice(pubkey.as_ref());
+        data.extend_from_slice(signature);
+    }
+
+    data.extend_from_slice(message);
+
+    data
+}
+
+/// Serialize secp256k1 program instruction data checking every signature
+/// against the same message.
+///
+/// Signatures are `r || s` followed by the recovery id, and `instruction_index`
+/// is the position of the instruction within the transaction.
+pub fn pack_secp256k1_instruction_data(
+    signatures: &[([u8; ETH_ADDRESS_SIZE], [u8; SECP256K1_SIGNATURE_SIZE], u8)],
+    message: &[u8],
+    instruction_index: u8,
+) -> Vec<u8> {
+    let entry_size = ETH_ADDRESS_SIZE + SECP256K1_SIGNATURE_SIZE + 1;
+    let data_start =
+        SECP256K1_SIGNATURE_OFFSETS_START + signatures.len() * SECP256K1_SIGNATURE_OFFSETS_SIZE;
+    let message_data_offset = data_start + signatures.len() * entry_size;
+
+    let mut data = Vec::with_capacity(message_data_offset + message.len());
+    data.push(signatures.len() as u8);
+
+    for i in 0..signatures.len() {
+        let eth_address_offset = data_start + i * entry_size;
+        let signature_offset = eth_address_offset + ETH_ADDRESS_SIZE;
+
+        data.extend_from_slice(&(signature_offset as u16).to_le_bytes());
+        data.push(instruction_index);
+        data.extend_from_slice(&(eth_address_offset as u16).to_le_bytes());
+        data.push(instruction_index);
+        data.extend_from_slice(&(message_data_offset as u16).to_le_bytes());
+        data.extend_from_slice(&(message.len() as u16).to_le_bytes());
+        data.push(instruction_index);
+    }
+
+    for (eth_address, signature, recovery_id) in signatures {
+        data.extend_from_slice(eth_address);
+        data.extend_from_slice(signature);
+        data.push(*recovery_id);
+    }
+
+    data.extend_from_slice(message);
+
+    data
+}
@@ -1,31 +1,126 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 
+use solana_program::bpf_loader_upgradeable;
 use solana_program::instruction::{AccountMeta, Instruction};
 use solana_program::program_error::ProgramError;
-use solana_program::program_pack::{IsInitialized, Pack, Sealed};
+use solana_program::program_pack::{IsInitialized, Sealed};
 use solana_program::pubkey::Pubkey;
 
 use multisig_derive::MultisigPack;
 
+use crate::{get_instruction_hash, MultisigError, MultisigInstruction};
+
 /// Minimum number of multisignature signers
 pub const MIN_SIGNERS: usize = 1;
-/// Maximum number of multisignature signers
+/// Default maximum number of multisignature signers
 pub const MAX_SIGNERS: usize = 10;
-/// Maximum number of pending transactions
-pub const MAX_TRANSACTIONS: usize = 15;
+/// Maximum number of bytes of return data stored on an executed transaction
+pub const MAX_TRANSACTION_RETURN_DATA: usize = 256;
+/// Maximum size of instruction data uploaded through a transaction buffer
+pub const MAX_TRANSACTION_BUFFER_SIZE: u32 = 8 * 1024;
+/// Minimum time in seconds owners have to veto a recovery
+pub const MIN_RECOVERY_DELAY: i64 = 7 * 24 * 60 * 60;
 
+/// Accounts of `Multisig::LEGACY_LEN` bytes fail to unpack, the length of the
+/// current layout never matches it since every extra signer or allowed
+/// program takes 32 bytes
 #[derive(Debug, BorshSerialize, BorshDeserialize, MultisigPack)]
-#[multisig_pack(length = 833)]
+#[multisig_pack(legacy_length = Self::LEGACY_LEN)]
 pub struct Multisig {
     pub is_initialized: bool,
     // Set of custodians
     pub owners: Vec<Pubkey>,
     // Required number of signers
     pub threshold: u64,
-    // Set of pending transactions
-    pub pending_transactions: Vec<Pubkey>,
+    // Number of transactions neither executed nor cancelled
+    pub pending_transaction_count: u64,
+    // Index of the last created transaction
+    pub transaction_index: u64,
     // Seed to derive PDA
     pub seed: u128,
+    // Maximum number of custodians the account has room for
+    pub max_signers: u32,
+    // Programs transactions may target, any program if empty
+    pub allowed_programs: Vec<Pubkey>,
+    // Required number of signers to reconfigure the multisig, `threshold` if not set
+    pub admin_threshold: Option<u64>,
+    // Required number of signers to upgrade or close programs, `threshold` if not set
+    pub upgrade_threshold: Option<u64>,
+    // Incremented whenever custodians are removed or replaced
+    pub owners_epoch: u64,
+}
+
+impl Multisig {
+    /// Account size of a multisig with the default capacity
+    pub const LEN: usize = Self::get_len(MAX_SIGNERS as u32, 0);
+
+    /// Account size of multisigs created before the account was resizable,
+    /// these have to be converted with `MigrateMultisig`
+    pub const LEGACY_LEN: usize = 833;
+
+    /// Account size required to store a multisig with the given capacity
+    pub const fn get_len(max_signers: u32, allowed_programs: u32) -> usize {
+        1 // is_initialized
+            + 4 + 32 * max_signers as usize // owners
+            + 8 // threshold
+            + 8 // pending_transaction_count
+            + 8 // transaction_index
+            + 16 // seed
+            + 4 // max_signers
+            + 4 + 32 * allowed_programs as usize // allowed_programs
+            + 1 + 8 // admin_threshold
+            + 1 + 8 // upgrade_threshold
+            + 8 // owners_epoch
+    }
+
+    /// Required number of signers to execute the instruction
+    pub fn get_threshold(&self, program_id: &Pubkey, data: &[u8]) -> u64 {
+        if *program_id == crate::id() {
+            // Multisig program calls act on other multisigs or on pending
+            // transactions, everything else reconfigures the multisig itself
+            match MultisigInstruction::try_from_slice(data) {
+                Ok(MultisigInstruction::CreateTransaction { .. })
+                | Ok(MultisigInstruction::Approve)
+                | Ok(MultisigInstruction::ApproveWithSignatures)
+                | Ok(MultisigInstruction::ExecuteTransaction)
+                | Ok(MultisigInstruction::DeletePendingTransaction { .. }) => self.threshold,
+                _ => self.admin_threshold.unwrap_or(self.threshold),
+            }
+        } else if bpf_loader_upgradeable::check_id(program_id)
+            && (bpf_loader_upgradeable::is_upgrade_instruction(data)
+                || bpf_loader_upgradeable::is_set_authority_instruction(data)
+                || bpf_loader_upgradeable::is_set_authority_checked_instruction(data)
+                || bpf_loader_upgradeable::is_close_instruction(data))
+        {
+            self.upgrade_threshold.unwrap_or(self.threshold)
+        } else {
+            self.threshold
+        }
+    }
+
+    /// Invalidate approvals collected under the previous set of owners
+    pub fn bump_owners_epoch(&mut self) -> Result<(), ProgramError> {
+        self.owners_epoch = self
+            .owners_epoch
+            .checked_add(1)
+            .ok_or(MultisigError::Overflow)?;
+        Ok(())
+    }
+
+    /// Highest of the thresholds, the number of owners may not drop below it
+    pub fn get_max_threshold(&self) -> u64 {
+        self.threshold
+            .max(self.admin_threshold.unwrap_or_default())
+            .max(self.upgrade_threshold.unwrap_or_default())
+    }
+
+    /// Whether transactions of this multisig may target the program.
+    /// Calls into the multisig program itself are always allowed.
+    pub fn is_program_allowed(&self, program_id: &Pubkey) -> bool {
+        self.allowed_programs.is_empty()
+            || *program_id == crate::id()
+            || self.allowed_programs.contains(program_id)
+    }
 }
 
 impl Sealed for Multisig {}
@@ -36,11 +131,35 @@ impl IsInitialized for Multisig {
     }
 }
 
+/// Multisig layout of accounts of `Multisig::LEGACY_LEN` bytes
+#[derive(Debug, BorshSerialize, BorshDeserialize, MultisigPack)]
+pub struct LegacyMultisig {
+    pub is_initialized: bool,
+    // Set of custodians
+    pub owners: Vec<Pubkey>,
+    // Required number of signers
+    pub threshold: u64,
+    // Set of pending transactions
+    pub pending_transactions: Vec<Pubkey>,
+    // Seed to derive PDA
+    pub seed: u128,
+}
+
+impl Sealed for LegacyMultisig {}
+
+impl IsInitialized for LegacyMultisig {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
 #[derive(Debug, BorshSerialize, BorshDeserialize, serde::Serialize, serde::Deserialize)]
 pub struct Transaction {
     pub is_initialized: bool,
     // The multisig account this transaction belongs to.
     pub multisig: Pubkey,
+    // Sequential number of the transaction within the multisig.
+    pub index: u64,
     // Target program to execute against.
     pub program_id: Pubkey,
     // Accounts required for the transaction.
@@ -51,6 +170,28 @@ pub struct Transaction {
     pub signers: Vec<bool>,
     // Boolean ensuring one time execution.
     pub did_execute: bool,
+    // Set once the transaction is deleted from pending.
+    pub is_cancelled: bool,
+    // Slot the transaction was executed at.
+    pub executed_slot: u64,
+    // Account that executed the transaction.
+    pub executor: Pubkey,
+    // Return data of the invoked program, truncated to `MAX_TRANSACTION_RETURN_DATA`.
+    pub return_data: Vec<u8>,
+    // Owner who created the transaction.
+    pub proposer: Pubkey,
+    // Number of times the transaction was updated by the proposer.
+    pub revision: u64,
+    // Address lookup tables referenced by `lookup_accounts`.
+    pub lookup_tables: Vec<Pubkey>,
+    // Accounts required for the transaction, following `accounts`, stored as
+    // indices into the lookup tables.
+    pub lookup_accounts: Vec<TransactionLookupAccount>,
+    // Addresses of `lookup_accounts` as resolved when the instruction was
+    // proposed, the lookup tables must still resolve to them on execution.
+    pub lookup_addresses: Vec<Pubkey>,
+    // `owners_epoch` of the multisig the approvals were collected under.
+    pub owners_epoch: u64,
 }
 
 impl Sealed for Transaction {}
@@ -61,11 +202,60 @@ impl IsInitialized for Transaction {
     }
 }
 
+#[derive(Debug, BorshSerialize, BorshDeserialize, MultisigPack)]
+pub struct Recovery {
+    pub is_initialized: bool,
+    // The multisig account this recovery belongs to
+    pub multisig: Pubkey,
+    // Accounts allowed to start a recovery, recovery is disabled if empty
+    pub guardians: Vec<Pubkey>,
+    // Required number of guardians to start a recovery
+    pub guardian_threshold: u64,
+    // Time in seconds owners have to veto a started recovery
+    pub delay: i64,
+    // Recovery in progress
+    pub pending: Option<PendingRecovery>,
+}
+
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub struct PendingRecovery {
+    // New set of custodians
+    pub owners: Vec<Pubkey>,
+    // New required number of signers
+    pub threshold: u64,
+    // Unix timestamp after which anyone can finalize the recovery
+    pub executable_at: i64,
+}
+
+impl Recovery {
+    /// Account size required to store a recovery with the given number of
+    /// guardians for a multisig with the given capacity
+    pub fn get_len(guardians: u32, max_signers: u32) -> usize {
+        1 // is_initialized
+            + 32 // multisig
+            + 4 + 32 * guardians as usize // guardians
+            + 8 // guardian_threshold
+            + 8 // delay
+            + 1 // pending
+            + 4 + 32 * max_signers as usize // pending.owners
+            + 8 // pending.threshold
+            + 8 // pending.executable_at
+    }
+}
+
+impl Sealed for Recovery {}
+
+impl IsInitialized for Recovery {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
 impl From<&Transaction> for Instruction {
     fn from(tx: &Transaction) -> Instruction {
         Instruction {
             program_id: tx.program_id,
-            accounts: tx.accounts.iter().map(Into::into).collect(),
+            accounts: tx.get_accounts().iter().map(Into::into).collect(),
             data: tx.data.clone(),
         }
     }
@@ -82,6 +272,187 @@ impl Transaction {
         let unpacked = Self::deserialize(&mut src)?;
         Ok(unpacked)
     }
+
+    /// Accounts of the instruction, followed by the ones referenced through
+    /// the lookup tables
+    pub fn get_accounts(&self) -> Vec<TransactionAccount> {
+        let lookup_accounts =
+            self.lookup_addresses
+                .iter()
+                .zip(&self.lookup_accounts)
+                .map(|(pubkey, account)| TransactionAccount {
+                    pubkey: *pubkey,
+                    is_signer: false,
+                    is_writable: account.is_writable,
+                });
+
+        self.accounts
+            .iter()
+            .cloned()
+            .chain(lookup_accounts)
+            .collect()
+    }
+
+    /// Hash of the instruction approvals signed off-chain commit to
+    pub fn get_instruction_hash(&self) -> [u8; 32] {
+        get_instruction_hash(
+            &self.program_id,
+            &self.accounts,
+            &self.data,
+            &self.lookup_tables,
+            &self.lookup_accounts,
+            &self.lookup_addresses,
+        )
+    }
+
+    /// Number of approvals counted towards the threshold on execution
+    pub fn get_approval_count(&self) -> u64 {
+        self.signers.iter().filter(|did_sign| **did_sign).count() as u64
+    }
+
+    /// Owners of the multisig who approved the transaction
+    pub fn get_approvers(&self, multisig: &Multisig) -> Vec<Pubkey> {
+        multisig
+            .owners
+            .iter()
+            .zip(&self.signers)
+            .filter(|(_, did_sign)| **did_sign)
+            .map(|(owner, _)| *owner)
+            .collect()
+    }
+
+    /// Owners of the multisig who have not approved the transaction yet.
+    /// Owners added after the transaction was created can not approve it
+    pub fn get_missing_owners(&self, multisig: &Multisig) -> Vec<Pubkey> {
+        multisig
+            .owners
+            .iter()
+            .zip(&self.signers)
+            .filter(|(_, did_sign)| !**did_sign)
+            .map(|(owner, _)| *owner)
+            .collect()
+    }
+
+    /// Stage of the transaction lifecycle given the current multisig state
+    pub fn get_status(&self, multisig: &Multisig) -> ProposalStatus {
+        if self.did_execute {
+            return ProposalStatus::Executed;
+        }
+
+        if self.is_cancelled {
+            return ProposalStatus::Cancelled;
+        }
+
+        // Same rules `ExecuteTransaction` applies
+        if self.owners_epoch != multisig.owners_epoch
+            || !multisig.is_program_allowed(&self.program_id)
+        {
+            return ProposalStatus::Stale;
+        }
+
+        let approvals = self.get_approval_count();
+
+        if approvals == 0 {
+            ProposalStatus::Draft
+        } else if approvals < multisig.get_threshold(&self.program_id, &self.data) {
+            ProposalStatus::Active
+        } else {
+            ProposalStatus::Approved
+        }
+    }
+}
+
+#[derive(Debug, BorshSerialize, BorshDeserialize, MultisigPack)]
+pub struct TransactionBuffer {
+    pub is_initialized: bool,
+    // The multisig account the transaction is proposed to
+    pub multisig: Pubkey,
+    // Owner uploading the transaction
+    pub proposer: Pubkey,
+    // Account which paid for the buffer and gets its lamports back on close
+    pub funder: Pubkey,
+    // Target program to execute against
+    pub program_id: Pubkey,
+    // Accounts required for the transaction
+    pub accounts: Vec<TransactionAccount>,
+    // Sha256 hash of the complete instruction data
+    pub final_hash: [u8; 32],
+    // Size of the complete instruction data
+    pub final_size: u32,
+    // Instruction data uploaded so far
+    pub data: Vec<u8>,
+}
+
+impl Sealed for TransactionBuffer {}
+
+impl IsInitialized for TransactionBuffer {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+/// Executability report returned by `CheckTransaction`
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize, serde::Serialize, serde::Deserialize)]
+pub struct TransactionStatus {
+    // Number of owners who approved the transaction
+    pub approvals: u64,
+    // Required number of approvals for this transaction
+    pub threshold: u64,
+    // Owners who have not approved the transaction yet
+    pub missing_owners: Vec<Pubkey>,
+    // Reasons the transaction cannot be executed, executable if empty
+    pub blocking_reasons: Vec<BlockingReason>,
+    // Seconds until the transaction may be executed, always `None` as the
+    // program has no timelock yet
+    pub timelock_remaining: Option<u64>,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum BlockingReason {
+    NotEnoughSigners,
+    AlreadyExecuted,
+    Cancelled,
+    ProgramNotAllowed,
+    MissingAccount,
+    AccountNotWritable,
+    AccountNotSigner,
+    OwnersChanged,
+    LookupTableChanged,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum ProposalStatus {
+    /// Not approved by any owner
+    Draft,
+    /// Approved by fewer owners than required
+    Active,
+    /// Approved by enough owners to be executed
+    Approved,
+    Executed,
+    /// Deleted from pending
+    Cancelled,
+    /// Can no longer be executed since the owners or allowed programs changed
+    Stale,
 }
 
 #[derive(Debug, Clone, BorshSerialize, BorshDeserialize, serde::Serialize, serde::Deserialize)]
@@ -91,6 +462,22 @@ pub struct TransactionAccount {
     pub is_writable: bool,
 }
 
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub struct TransactionLookupAccount {
+    pub table_index: u8,
+    pub address_index: u8,
+    pub is_writable: bool,
+}
+
 impl From<&TransactionAccount> for AccountMeta {
     fn from(account: &TransactionAccount) -> AccountMeta {
         match account.is_writable {
@@ -1,399 +1,1124 @@
 use std::str::FromStr;
 
-use borsh::BorshSerialize;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
-use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen::JsValue;
 
-use solana_program::instruction::{AccountMeta, Instruction};
-use solana_program::program_pack::Pack;
+use solana_program::hash::Hash;
 use solana_program::pubkey::Pubkey;
-use solana_program::{bpf_loader_upgradeable, system_program, sysvar};
 
 use crate::*;
 
+mod types;
+
+pub use self::types::*;
+
 #[wasm_bindgen(js_name = "createMultisig")]
 pub fn create_multisig_ix(
-    funder_pubkey: String,
-    seed: String,
-    owners: JsValue,
+    funder_pubkey: JsPublicKeyInput,
+    seed: JsSeed,
+    owners: JsPublicKeyInputArray,
     threshold: u64,
-) -> Result<JsValue, JsValue> {
-    let funder_pubkey = Pubkey::from_str(funder_pubkey.as_str()).handle_error()?;
+    max_signers: u32,
+) -> Result<JsInstruction, JsValue> {
+    let funder_pubkey = parse_pubkey(&funder_pubkey)?;
 
-    let owners: Vec<String> = serde_wasm_bindgen::from_value(owners).handle_error()?;
-    let owners = owners
-        .into_iter()
-        .map(|x| Pubkey::from_str(x.as_str()).unwrap())
-        .collect();
+    let owners = parse_pubkeys(&owners)?;
 
-    let seed = uuid::Uuid::from_str(&seed).handle_error()?.as_u128();
-    let multisig_pubkey = get_multisig_address(seed);
+    let seed = parse_seed(&seed)?;
 
-    let data = MultisigInstruction::CreateMultisig {
-        seed,
-        owners,
-        threshold,
-    }
-    .try_to_vec()
-    .expect("pack");
-
-    let ix = Instruction {
-        program_id: id(),
-        accounts: vec![
-            AccountMeta::new(funder_pubkey, true),
-            AccountMeta::new(multisig_pubkey, false),
-            AccountMeta::new_readonly(system_program::id(), false),
-            AccountMeta::new_readonly(sysvar::rent::id(), false),
-        ],
-        data,
-    };
+    let ix = create_multisig(&funder_pubkey, seed, owners, threshold, max_signers);
 
-    return serde_wasm_bindgen::to_value(&ix).handle_error();
+    return to_js(&ix);
 }
 
 #[wasm_bindgen(js_name = "addOwner")]
-pub fn add_owner_ix(multisig_pubkey: String, owner: String) -> Result<JsValue, JsValue> {
-    let multisig_pubkey = Pubkey::from_str(multisig_pubkey.as_str()).handle_error()?;
-    let owner = Pubkey::from_str(owner.as_str()).handle_error()?;
-
-    let data = MultisigInstruction::AddOwner { owner }
-        .try_to_vec()
-        .expect("pack");
-
-    let ix = Instruction {
-        program_id: id(),
-        accounts: vec![AccountMeta::new(multisig_pubkey, true)],
-        data,
-    };
+pub fn add_owner_ix(
+    multisig_pubkey: JsPublicKeyInput,
+    owner: JsPublicKeyInput,
+) -> Result<JsInstruction, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let owner = parse_pubkey(&owner)?;
+
+    let ix = add_owner(&multisig_pubkey, owner);
 
-    return serde_wasm_bindgen::to_value(&ix).handle_error();
+    return to_js(&ix);
 }
 
 #[wasm_bindgen(js_name = "deleteOwner")]
-pub fn delete_owner_ix(multisig_pubkey: String, owner: String) -> Result<JsValue, JsValue> {
-    let multisig_pubkey = Pubkey::from_str(multisig_pubkey.as_str()).handle_error()?;
-    let owner = Pubkey::from_str(owner.as_str()).handle_error()?;
-
-    let data = MultisigInstruction::DeleteOwner { owner }
-        .try_to_vec()
-        .expect("pack");
-
-    let ix = Instruction {
-        program_id: id(),
-        accounts: vec![AccountMeta::new(multisig_pubkey, true)],
-        data,
-    };
+pub fn delete_owner_ix(
+    multisig_pubkey: JsPublicKeyInput,
+    owner: JsPublicKeyInput,
+) -> Result<JsInstruction, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let owner = parse_pubkey(&owner)?;
+
+    let ix = delete_owner(&multisig_pubkey, owner);
 
-    return serde_wasm_bindgen::to_value(&ix).handle_error();
+    return to_js(&ix);
 }
 
 #[wasm_bindgen(js_name = "updateThreshold")]
-pub fn update_threshold_ix(multisig_pubkey: String, threshold: u64) -> Result<JsValue, JsValue> {
-    let multisig_pubkey = Pubkey::from_str(multisig_pubkey.as_str()).handle_error()?;
+pub fn update_threshold_ix(
+    multisig_pubkey: JsPublicKeyInput,
+    threshold: u64,
+) -> Result<JsInstruction, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
 
-    let data = MultisigInstruction::UpdateThreshold { threshold }
-        .try_to_vec()
-        .expect("pack");
+    let ix = update_threshold(&multisig_pubkey, threshold);
 
-    let ix = Instruction {
-        program_id: id(),
-        accounts: vec![AccountMeta::new(multisig_pubkey, true)],
-        data,
-    };
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "resize")]
+pub fn resize_ix(
+    multisig_pubkey: JsPublicKeyInput,
+    funder_pubkey: JsPublicKeyInput,
+    max_signers: u32,
+) -> Result<JsInstruction, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let funder_pubkey = parse_pubkey(&funder_pubkey)?;
 
-    return serde_wasm_bindgen::to_value(&ix).handle_error();
+    let ix = resize(&multisig_pubkey, &funder_pubkey, max_signers);
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "addAllowedProgram")]
+pub fn add_allowed_program_ix(
+    multisig_pubkey: JsPublicKeyInput,
+    funder_pubkey: JsPublicKeyInput,
+    program_id: JsPublicKeyInput,
+) -> Result<JsInstruction, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let funder_pubkey = parse_pubkey(&funder_pubkey)?;
+    let program_id = parse_pubkey(&program_id)?;
+
+    let ix = add_allowed_program(&multisig_pubkey, &funder_pubkey, program_id);
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "removeAllowedProgram")]
+pub fn remove_allowed_program_ix(
+    multisig_pubkey: JsPublicKeyInput,
+    program_id: JsPublicKeyInput,
+) -> Result<JsInstruction, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let program_id = parse_pubkey(&program_id)?;
+
+    let ix = remove_allowed_program(&multisig_pubkey, program_id);
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "updateActionThresholds")]
+pub fn update_action_thresholds_ix(
+    multisig_pubkey: JsPublicKeyInput,
+    admin_threshold: Option<u64>,
+    upgrade_threshold: Option<u64>,
+) -> Result<JsInstruction, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+
+    let ix = update_action_thresholds(&multisig_pubkey, admin_threshold, upgrade_threshold);
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "configureRecovery")]
+pub fn configure_recovery_ix(
+    multisig_pubkey: JsPublicKeyInput,
+    funder_pubkey: JsPublicKeyInput,
+    guardians: JsPublicKeyInputArray,
+    guardian_threshold: u64,
+    delay: i64,
+) -> Result<JsInstruction, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let funder_pubkey = parse_pubkey(&funder_pubkey)?;
+
+    let guardians = parse_pubkeys(&guardians)?;
+
+    let ix = configure_recovery(
+        &multisig_pubkey,
+        &funder_pubkey,
+        guardians,
+        guardian_threshold,
+        delay,
+    );
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "startRecovery")]
+pub fn start_recovery_ix(
+    multisig_pubkey: JsPublicKeyInput,
+    guardians: JsPublicKeyInputArray,
+    owners: JsPublicKeyInputArray,
+    threshold: u64,
+) -> Result<JsInstruction, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+
+    let guardians = parse_pubkeys(&guardians)?;
+
+    let owners = parse_pubkeys(&owners)?;
+
+    let ix = start_recovery(&multisig_pubkey, &guardians, owners, threshold);
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "vetoRecovery")]
+pub fn veto_recovery_ix(
+    multisig_pubkey: JsPublicKeyInput,
+    owner_pubkey: JsPublicKeyInput,
+) -> Result<JsInstruction, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let owner_pubkey = parse_pubkey(&owner_pubkey)?;
+
+    let ix = veto_recovery(&multisig_pubkey, &owner_pubkey);
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "finalizeRecovery")]
+pub fn finalize_recovery_ix(multisig_pubkey: JsPublicKeyInput) -> Result<JsInstruction, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+
+    let ix = finalize_recovery(&multisig_pubkey);
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "closeMultisig")]
+pub fn close_multisig_ix(
+    multisig_pubkey: JsPublicKeyInput,
+    recipient_pubkey: JsPublicKeyInput,
+    vaults: JsPublicKeyInputArray,
+) -> Result<JsInstruction, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let recipient_pubkey = parse_pubkey(&recipient_pubkey)?;
+
+    let vaults = parse_pubkeys(&vaults)?;
+
+    let ix = close_multisig(&multisig_pubkey, &recipient_pubkey, vaults);
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "migrateMultisig")]
+pub fn migrate_multisig_ix(multisig_pubkey: JsPublicKeyInput) -> Result<JsInstruction, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+
+    let ix = migrate_multisig(&multisig_pubkey);
+
+    return to_js(&ix);
 }
 
 #[wasm_bindgen(js_name = "createTransaction")]
 pub fn create_transaction_ix(
-    funder_pubkey: String,
-    proposer_pubkey: String,
-    multisig_pubkey: String,
-    seed: String,
-    instruction: JsValue,
-) -> Result<JsValue, JsValue> {
-    let funder_pubkey = Pubkey::from_str(funder_pubkey.as_str()).handle_error()?;
-    let proposer_pubkey = Pubkey::from_str(proposer_pubkey.as_str()).handle_error()?;
-    let multisig_pubkey = Pubkey::from_str(multisig_pubkey.as_str()).handle_error()?;
-
-    let ix: Instruction = serde_wasm_bindgen::from_value(instruction).handle_error()?;
-
-    let mut accounts = ix
-        .accounts
-        .into_iter()
-        .map(|acc| TransactionAccount {
-            pubkey: acc.pubkey,
-            is_signer: acc.is_signer,
-            is_writable: acc.is_writable,
-        })
-        .collect::<Vec<_>>();
-    accounts.push(TransactionAccount {
-        pubkey: ix.program_id,
-        is_signer: false,
-        is_writable: false,
-    });
+    funder_pubkey: JsPublicKeyInput,
+    proposer_pubkey: JsPublicKeyInput,
+    multisig_pubkey: JsPublicKeyInput,
+    transaction_index: u64,
+    instruction: JsInstructionInput,
+) -> Result<JsInstruction, JsValue> {
+    let funder_pubkey = parse_pubkey(&funder_pubkey)?;
+    let proposer_pubkey = parse_pubkey(&proposer_pubkey)?;
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+
+    let ix = parse_instruction(&instruction)?;
+
+    let ix = create_transaction(
+        &funder_pubkey,
+        &proposer_pubkey,
+        &multisig_pubkey,
+        transaction_index,
+        ix,
+    );
 
-    let seed = uuid::Uuid::from_str(&seed).handle_error()?.as_u128();
-    let transaction_pubkey = get_transaction_address(seed);
+    return to_js(&ix);
+}
 
-    let data = MultisigInstruction::CreateTransaction {
-        seed,
-        pid: ix.program_id,
-        accs: accounts,
-        data: ix.data,
-    }
-    .try_to_vec()
-    .expect("pack");
-
-    let ix = Instruction {
-        program_id: id(),
-        accounts: vec![
-            AccountMeta::new(funder_pubkey, true),
-            AccountMeta::new(proposer_pubkey, true),
-            AccountMeta::new(multisig_pubkey, false),
-            AccountMeta::new(transaction_pubkey, false),
-            AccountMeta::new_readonly(system_program::id(), false),
-            AccountMeta::new_readonly(sysvar::rent::id(), false),
-        ],
-        data,
-    };
+#[wasm_bindgen(js_name = "createTransactionBuffer")]
+pub fn create_transaction_buffer_ix(
+    funder_pubkey: JsPublicKeyInput,
+    proposer_pubkey: JsPublicKeyInput,
+    multisig_pubkey: JsPublicKeyInput,
+    instruction: JsInstructionInput,
+    chunk_size: Option<usize>,
+) -> Result<JsInstruction, JsValue> {
+    let funder_pubkey = parse_pubkey(&funder_pubkey)?;
+    let proposer_pubkey = parse_pubkey(&proposer_pubkey)?;
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+
+    let ix = parse_instruction(&instruction)?;
+
+    let ix = create_transaction_buffer(
+        &funder_pubkey,
+        &proposer_pubkey,
+        &multisig_pubkey,
+        &ix,
+        parse_chunk_size(chunk_size)?,
+    );
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "extendTransactionBuffer")]
+pub fn extend_transaction_buffer_ix(
+    funder_pubkey: JsPublicKeyInput,
+    proposer_pubkey: JsPublicKeyInput,
+    multisig_pubkey: JsPublicKeyInput,
+    chunk: Vec<u8>,
+) -> Result<JsInstruction, JsValue> {
+    let funder_pubkey = parse_pubkey(&funder_pubkey)?;
+    let proposer_pubkey = parse_pubkey(&proposer_pubkey)?;
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+
+    let ix = extend_transaction_buffer(&funder_pubkey, &proposer_pubkey, &multisig_pubkey, chunk);
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "finalizeTransaction")]
+pub fn finalize_transaction_ix(
+    funder_pubkey: JsPublicKeyInput,
+    proposer_pubkey: JsPublicKeyInput,
+    multisig_pubkey: JsPublicKeyInput,
+    transaction_index: u64,
+    buffer_funder_pubkey: JsPublicKeyInput,
+) -> Result<JsInstruction, JsValue> {
+    let funder_pubkey = parse_pubkey(&funder_pubkey)?;
+    let proposer_pubkey = parse_pubkey(&proposer_pubkey)?;
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let buffer_funder_pubkey = parse_pubkey(&buffer_funder_pubkey)?;
+
+    let ix = finalize_transaction(
+        &funder_pubkey,
+        &proposer_pubkey,
+        &multisig_pubkey,
+        transaction_index,
+        &buffer_funder_pubkey,
+    );
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "closeTransactionBuffer")]
+pub fn close_transaction_buffer_ix(
+    proposer_pubkey: JsPublicKeyInput,
+    multisig_pubkey: JsPublicKeyInput,
+    buffer_funder_pubkey: JsPublicKeyInput,
+) -> Result<JsInstruction, JsValue> {
+    let proposer_pubkey = parse_pubkey(&proposer_pubkey)?;
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let buffer_funder_pubkey = parse_pubkey(&buffer_funder_pubkey)?;
+
+    let ix = close_transaction_buffer(&proposer_pubkey, &multisig_pubkey, &buffer_funder_pubkey);
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "createTransactionWithLookups")]
+pub fn create_transaction_with_lookups_ix(
+    funder_pubkey: JsPublicKeyInput,
+    proposer_pubkey: JsPublicKeyInput,
+    multisig_pubkey: JsPublicKeyInput,
+    transaction_index: u64,
+    instruction: JsInstructionInput,
+    lookup_tables: JsLookupTableInputArray,
+) -> Result<JsInstruction, JsValue> {
+    let funder_pubkey = parse_pubkey(&funder_pubkey)?;
+    let proposer_pubkey = parse_pubkey(&proposer_pubkey)?;
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+
+    let ix = parse_instruction(&instruction)?;
+    let lookup_tables = parse_lookup_tables(&lookup_tables)?;
+
+    let ix = create_transaction_with_lookups(
+        &funder_pubkey,
+        &proposer_pubkey,
+        &multisig_pubkey,
+        transaction_index,
+        ix,
+        &lookup_tables,
+    );
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "createTransactionWithBuffer")]
+pub fn create_transaction_with_buffer_ix(
+    funder_pubkey: JsPublicKeyInput,
+    proposer_pubkey: JsPublicKeyInput,
+    multisig_pubkey: JsPublicKeyInput,
+    transaction_index: u64,
+    instruction: JsInstructionInput,
+    chunk_size: Option<usize>,
+) -> Result<JsInstructionArray, JsValue> {
+    let funder_pubkey = parse_pubkey(&funder_pubkey)?;
+    let proposer_pubkey = parse_pubkey(&proposer_pubkey)?;
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+
+    let ix = parse_instruction(&instruction)?;
+
+    let ixs = create_transaction_with_buffer(
+        &funder_pubkey,
+        &proposer_pubkey,
+        &multisig_pubkey,
+        transaction_index,
+        ix,
+        parse_chunk_size(chunk_size)?,
+    );
+
+    return to_js(&ixs);
+}
+
+#[wasm_bindgen(js_name = "updateTransaction")]
+pub fn update_transaction_ix(
+    funder_pubkey: JsPublicKeyInput,
+    proposer_pubkey: JsPublicKeyInput,
+    multisig_pubkey: JsPublicKeyInput,
+    transaction_pubkey: JsPublicKeyInput,
+    instruction: JsInstructionInput,
+) -> Result<JsInstruction, JsValue> {
+    let funder_pubkey = parse_pubkey(&funder_pubkey)?;
+    let proposer_pubkey = parse_pubkey(&proposer_pubkey)?;
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let transaction_pubkey = parse_pubkey(&transaction_pubkey)?;
+
+    let ix = parse_instruction(&instruction)?;
+
+    let ix = update_transaction(
+        &funder_pubkey,
+        &proposer_pubkey,
+        &multisig_pubkey,
+        &transaction_pubkey,
+        ix,
+    );
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "updateTransactionWithLookups")]
+pub fn update_transaction_with_lookups_ix(
+    funder_pubkey: JsPublicKeyInput,
+    proposer_pubkey: JsPublicKeyInput,
+    multisig_pubkey: JsPublicKeyInput,
+    transaction_pubkey: JsPublicKeyInput,
+    instruction: JsInstructionInput,
+    lookup_tables: JsLookupTableInputArray,
+) -> Result<JsInstruction, JsValue> {
+    let funder_pubkey = parse_pubkey(&funder_pubkey)?;
+    let proposer_pubkey = parse_pubkey(&proposer_pubkey)?;
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let transaction_pubkey = parse_pubkey(&transaction_pubkey)?;
+
+    let ix = parse_instruction(&instruction)?;
+    let lookup_tables = parse_lookup_tables(&lookup_tables)?;
+
+    let ix = update_transaction_with_lookups(
+        &funder_pubkey,
+        &proposer_pubkey,
+        &multisig_pubkey,
+        &transaction_pubkey,
+        ix,
+        &lookup_tables,
+    );
 
-    return serde_wasm_bindgen::to_value(&ix).handle_error();
+    return to_js(&ix);
 }
 
 #[wasm_bindgen(js_name = "setProgramAuthority")]
 pub fn set_program_authority_ix(
-    current_authority_pubkey: String,
-    new_authority_pubkey: String,
-    program_address: String,
-) -> Result<JsValue, JsValue> {
-    let current_authority_pubkey =
-        Pubkey::from_str(current_authority_pubkey.as_str()).handle_error()?;
-    let new_authority_pubkey = Pubkey::from_str(new_authority_pubkey.as_str()).handle_error()?;
-    let program_address = Pubkey::from_str(program_address.as_str()).handle_error()?;
-
-    let ix = bpf_loader_upgradeable::set_upgrade_authority(
-        &program_address,
+    current_authority_pubkey: JsPublicKeyInput,
+    new_authority_pubkey: JsPublicKeyInput,
+    program_address: JsPublicKeyInput,
+) -> Result<JsInstruction, JsValue> {
+    let current_authority_pubkey = parse_pubkey(&current_authority_pubkey)?;
+    let new_authority_pubkey = parse_pubkey(&new_authority_pubkey)?;
+    let program_address = parse_pubkey(&program_address)?;
+
+    let ix = set_program_authority(
         &current_authority_pubkey,
-        Some(&new_authority_pubkey),
+        &new_authority_pubkey,
+        &program_address,
     );
 
-    return serde_wasm_bindgen::to_value(&ix).handle_error();
+    return to_js(&ix);
 }
 
 #[wasm_bindgen(js_name = "upgradeProgram")]
 pub fn upgrade_program_ix(
-    program_pubkey: String,
-    buffer_pubkey: String,
-    authority_pubkey: String,
-    spill_pubkey: String,
-) -> Result<JsValue, JsValue> {
-    let program_pubkey = Pubkey::from_str(program_pubkey.as_str()).handle_error()?;
-    let buffer_pubkey = Pubkey::from_str(buffer_pubkey.as_str()).handle_error()?;
-    let authority_pubkey = Pubkey::from_str(authority_pubkey.as_str()).handle_error()?;
-    let spill_pubkey = Pubkey::from_str(spill_pubkey.as_str()).handle_error()?;
-
-    let ix = bpf_loader_upgradeable::upgrade(
+    program_pubkey: JsPublicKeyInput,
+    buffer_pubkey: JsPublicKeyInput,
+    authority_pubkey: JsPublicKeyInput,
+    spill_pubkey: JsPublicKeyInput,
+) -> Result<JsInstruction, JsValue> {
+    let program_pubkey = parse_pubkey(&program_pubkey)?;
+    let buffer_pubkey = parse_pubkey(&buffer_pubkey)?;
+    let authority_pubkey = parse_pubkey(&authority_pubkey)?;
+    let spill_pubkey = parse_pubkey(&spill_pubkey)?;
+
+    let ix = upgrade_program(
         &program_pubkey,
         &buffer_pubkey,
         &authority_pubkey,
         &spill_pubkey,
     );
 
-    return serde_wasm_bindgen::to_value(&ix).handle_error();
+    return to_js(&ix);
 }
 
 #[wasm_bindgen(js_name = "closeProgram")]
 pub fn close_program_ix(
-    program_pubkey: String,
-    authority_pubkey: String,
-    recipient_pubkey: String,
-) -> Result<JsValue, JsValue> {
-    let program_pubkey = Pubkey::from_str(program_pubkey.as_str()).handle_error()?;
-    let authority_pubkey = Pubkey::from_str(authority_pubkey.as_str()).handle_error()?;
-    let recipient_pubkey = Pubkey::from_str(recipient_pubkey.as_str()).handle_error()?;
-
-    let close_pubkey =
-        Pubkey::find_program_address(&[program_pubkey.as_ref()], &bpf_loader_upgradeable::id()).0;
-
-    let ix = bpf_loader_upgradeable::close_any(
-        &close_pubkey,
-        &recipient_pubkey,
-        Some(&authority_pubkey),
-        Some(&program_pubkey),
-    );
+    program_pubkey: JsPublicKeyInput,
+    authority_pubkey: JsPublicKeyInput,
+    recipient_pubkey: JsPublicKeyInput,
+) -> Result<JsInstruction, JsValue> {
+    let program_pubkey = parse_pubkey(&program_pubkey)?;
+    let authority_pubkey = parse_pubkey(&authority_pubkey)?;
+    let recipient_pubkey = parse_pubkey(&recipient_pubkey)?;
+
+    let ix = close_program(&program_pubkey, &authority_pubkey, &recipient_pubkey);
 
-    return serde_wasm_bindgen::to_value(&ix).handle_error();
+    return to_js(&ix);
 }
 
 #[wasm_bindgen(js_name = "closeBuffer")]
 pub fn close_buffer_ix(
-    buffer_pubkey: String,
-    authority_pubkey: String,
-    recipient_pubkey: String,
-) -> Result<JsValue, JsValue> {
-    let buffer_pubkey = Pubkey::from_str(buffer_pubkey.as_str()).handle_error()?;
-    let authority_pubkey = Pubkey::from_str(authority_pubkey.as_str()).handle_error()?;
-    let recipient_pubkey = Pubkey::from_str(recipient_pubkey.as_str()).handle_error()?;
-
-    let ix = bpf_loader_upgradeable::close_any(
-        &buffer_pubkey,
-        &recipient_pubkey,
-        Some(&authority_pubkey),
-        None,
-    );
+    buffer_pubkey: JsPublicKeyInput,
+    authority_pubkey: JsPublicKeyInput,
+    recipient_pubkey: JsPublicKeyInput,
+) -> Result<JsInstruction, JsValue> {
+    let buffer_pubkey = parse_pubkey(&buffer_pubkey)?;
+    let authority_pubkey = parse_pubkey(&authority_pubkey)?;
+    let recipient_pubkey = parse_pubkey(&recipient_pubkey)?;
 
-    return serde_wasm_bindgen::to_value(&ix).handle_error();
+    let ix = close_buffer(&buffer_pubkey, &authority_pubkey, &recipient_pubkey);
+
+    return to_js(&ix);
 }
 
 #[wasm_bindgen(js_name = "approve")]
 pub fn approve_ix(
-    proposer_pubkey: String,
-    multisig_pubkey: String,
-    transaction_pubkey: String,
-) -> Result<JsValue, JsValue> {
-    let proposer_pubkey = Pubkey::from_str(proposer_pubkey.as_str()).handle_error()?;
-    let multisig_pubkey = Pubkey::from_str(multisig_pubkey.as_str()).handle_error()?;
-    let transaction_pubkey = Pubkey::from_str(transaction_pubkey.as_str()).handle_error()?;
-
-    let data = MultisigInstruction::Approve.try_to_vec().expect("pack");
-
-    let ix = Instruction {
-        program_id: id(),
-        accounts: vec![
-            AccountMeta::new(proposer_pubkey, true),
-            AccountMeta::new(transaction_pubkey, false),
-            AccountMeta::new_readonly(multisig_pubkey, false),
-        ],
-        data,
-    };
+    proposer_pubkey: JsPublicKeyInput,
+    multisig_pubkey: JsPublicKeyInput,
+    transaction_pubkey: JsPublicKeyInput,
+) -> Result<JsInstruction, JsValue> {
+    let proposer_pubkey = parse_pubkey(&proposer_pubkey)?;
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let transaction_pubkey = parse_pubkey(&transaction_pubkey)?;
+
+    let ix = approve(&proposer_pubkey, &multisig_pubkey, &transaction_pubkey);
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "approveWithSignatures")]
+pub fn approve_with_signatures_ix(
+    multisig_pubkey: JsPublicKeyInput,
+    transaction_pubkey: JsPublicKeyInput,
+) -> Result<JsInstruction, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let transaction_pubkey = parse_pubkey(&transaction_pubkey)?;
+
+    let ix = approve_with_signatures(&multisig_pubkey, &transaction_pubkey);
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "ed25519Approval")]
+pub fn ed25519_approval_ix(
+    signatures: JsEd25519SignatureInputArray,
+    multisig_pubkey: JsPublicKeyInput,
+    transaction_pubkey: JsPublicKeyInput,
+    transaction_data: Vec<u8>,
+) -> Result<JsInstruction, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let transaction_pubkey = parse_pubkey(&transaction_pubkey)?;
+    let transaction = Transaction::unpack_from_slice(&transaction_data)
+        .handle_error(ErrorCode::InvalidAccountData)?;
+
+    let code = ErrorCode::InvalidSignature;
+    let signatures = parse_array(&signatures, code)?
+        .iter()
+        .map(|x| {
+            let pubkey = parse_pubkey(&get_field(&x, &["pubkey"], code)?)?;
+            let signature = get_byte_array::<ED25519_SIGNATURE_SIZE>(&x, "signature", code)?;
+            Ok((pubkey, signature))
+        })
+        .collect::<Result<Vec<_>, JsValue>>()?;
+
+    let ix = ed25519_approval(
+        &signatures,
+        &multisig_pubkey,
+        &transaction_pubkey,
+        &transaction,
+    );
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "secp256k1Approval")]
+pub fn secp256k1_approval_ix(
+    signatures: JsEthSignatureInputArray,
+    multisig_pubkey: JsPublicKeyInput,
+    transaction_pubkey: JsPublicKeyInput,
+    transaction_data: Vec<u8>,
+    instruction_index: u8,
+) -> Result<JsInstruction, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let transaction_pubkey = parse_pubkey(&transaction_pubkey)?;
+    let transaction = Transaction::unpack_from_slice(&transaction_data)
+        .handle_error(ErrorCode::InvalidAccountData)?;
+
+    let code = ErrorCode::InvalidSignature;
+    let signatures = parse_array(&signatures, code)?
+        .iter()
+        .map(|x| {
+            let v = get_number(&x, "v", code)?;
+            if v.fract() != 0.0 || !(0.0..=255.0).contains(&v) {
+                return Err(error(code, "v must be a byte"));
+            }
+
+            Ok(EthSignature {
+                address: get_byte_array(&x, "address", code)?,
+                r: get_byte_array(&x, "r", code)?,
+                s: get_byte_array(&x, "s", code)?,
+                v: v as u8,
+            })
+        })
+        .collect::<Result<Vec<_>, JsValue>>()?;
+
+    let ix = secp256k1_approval(
+        &signatures,
+        &multisig_pubkey,
+        &transaction_pubkey,
+        &transaction,
+        instruction_index,
+    );
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "getEthApprovalMessage")]
+pub fn get_eth_approval_message_js(
+    multisig_pubkey: JsPublicKeyInput,
+    transaction_pubkey: JsPublicKeyInput,
+    transaction_data: Vec<u8>,
+) -> Result<Vec<u8>, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let transaction_pubkey = parse_pubkey(&transaction_pubkey)?;
+    let transaction = Transaction::unpack_from_slice(&transaction_data)
+        .handle_error(ErrorCode::InvalidAccountData)?;
+
+    Ok(get_eth_approval_message(
+        &multisig_pubkey,
+        &transaction_pubkey,
+        transaction.revision,
+        &transaction.get_instruction_hash(),
+    ))
+}
+
+#[wasm_bindgen(js_name = "getEthOwner")]
+pub fn get_eth_owner_js(address: Vec<u8>) -> Result<String, JsValue> {
+    let address = <[u8; ETH_ADDRESS_SIZE]>::try_from(address.as_slice())
+        .handle_error(ErrorCode::InvalidBytes)?;
 
-    return serde_wasm_bindgen::to_value(&ix).handle_error();
+    Ok(get_eth_owner(&address).to_string())
+}
+
+#[wasm_bindgen(js_name = "getApprovalMessage")]
+pub fn get_approval_message_js(
+    multisig_pubkey: JsPublicKeyInput,
+    transaction_pubkey: JsPublicKeyInput,
+    transaction_data: Vec<u8>,
+) -> Result<Vec<u8>, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let transaction_pubkey = parse_pubkey(&transaction_pubkey)?;
+    let transaction = Transaction::unpack_from_slice(&transaction_data)
+        .handle_error(ErrorCode::InvalidAccountData)?;
+
+    Ok(get_approval_message(
+        &multisig_pubkey,
+        &transaction_pubkey,
+        transaction.revision,
+        &transaction.get_instruction_hash(),
+    ))
 }
 
 #[wasm_bindgen(js_name = "deletePendingTransaction")]
 pub fn delete_pending_transaction_ix(
-    multisig_pubkey: String,
-    pending_transaction: String,
-) -> Result<JsValue, JsValue> {
-    let multisig_pubkey = Pubkey::from_str(multisig_pubkey.as_str()).handle_error()?;
-    let pending_transaction = Pubkey::from_str(pending_transaction.as_str()).handle_error()?;
-
-    let data = MultisigInstruction::DeletePendingTransaction {
-        pending_transaction,
-    }
-    .try_to_vec()
-    .expect("pack");
+    multisig_pubkey: JsPublicKeyInput,
+    pending_transaction: JsPublicKeyInput,
+) -> Result<JsInstruction, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let pending_transaction = parse_pubkey(&pending_transaction)?;
 
-    let ix = Instruction {
-        program_id: id(),
-        accounts: vec![AccountMeta::new(multisig_pubkey, true)],
-        data,
-    };
+    let ix = delete_pending_transaction(&multisig_pubkey, pending_transaction);
 
-    return serde_wasm_bindgen::to_value(&ix).handle_error();
+    return to_js(&ix);
 }
 
 #[wasm_bindgen(js_name = "execute")]
 pub fn execute_ix(
-    multisig_pubkey: String,
-    transaction_pubkey: String,
+    executor_pubkey: JsPublicKeyInput,
+    multisig_pubkey: JsPublicKeyInput,
+    transaction_pubkey: JsPublicKeyInput,
     transaction_data: Vec<u8>,
-) -> Result<JsValue, JsValue> {
-    let multisig_pubkey = Pubkey::from_str(multisig_pubkey.as_str()).handle_error()?;
-    let transaction_pubkey = Pubkey::from_str(transaction_pubkey.as_str()).handle_error()?;
-
-    let transaction_data = Transaction::unpack_from_slice(&transaction_data).handle_error()?;
-
-    let mut accounts = vec![
-        AccountMeta::new(multisig_pubkey, false),
-        AccountMeta::new(transaction_pubkey, false),
-    ];
-
-    for account in transaction_data.accounts {
-        let account_meta = match account.is_writable {
-            true => AccountMeta::new(account.pubkey, false),
-            false => AccountMeta::new_readonly(account.pubkey, false),
-        };
-        accounts.push(account_meta);
-    }
+) -> Result<JsInstruction, JsValue> {
+    let executor_pubkey = parse_pubkey(&executor_pubkey)?;
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let transaction_pubkey = parse_pubkey(&transaction_pubkey)?;
+
+    let transaction_data = Transaction::unpack_from_slice(&transaction_data)
+        .handle_error(ErrorCode::InvalidAccountData)?;
+
+    let ix = execute_transaction(
+        &executor_pubkey,
+        &multisig_pubkey,
+        &transaction_pubkey,
+        get_execute_accounts(&transaction_data),
+    );
+
+    return to_js(&ix);
+}
+
+/// Serialized v0 message executing the transaction through the lookup tables
+#[wasm_bindgen(js_name = "executeMessage")]
+pub fn execute_message(
+    executor_pubkey: JsPublicKeyInput,
+    multisig_pubkey: JsPublicKeyInput,
+    transaction_pubkey: JsPublicKeyInput,
+    transaction_data: Vec<u8>,
+    lookup_tables: JsLookupTableInputArray,
+    recent_blockhash: String,
+) -> Result<Vec<u8>, JsValue> {
+    let executor_pubkey = parse_pubkey(&executor_pubkey)?;
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let transaction_pubkey = parse_pubkey(&transaction_pubkey)?;
+    let recent_blockhash =
+        Hash::from_str(recent_blockhash.as_str()).handle_error(ErrorCode::InvalidHash)?;
+
+    let transaction_data = Transaction::unpack_from_slice(&transaction_data)
+        .handle_error(ErrorCode::InvalidAccountData)?;
+    let lookup_tables = parse_lookup_tables(&lookup_tables)?;
+
+    let message = execute_transaction_message(
+        &executor_pubkey,
+        &multisig_pubkey,
+        &transaction_pubkey,
+        &transaction_data,
+        &lookup_tables,
+        recent_blockhash,
+    )
+    .handle_error(ErrorCode::InvalidLookupTable)?;
+
+    Ok(message.serialize())
+}
+
+#[wasm_bindgen(js_name = "checkTransaction")]
+pub fn check_transaction_ix(
+    executor_pubkey: JsPublicKeyInput,
+    multisig_pubkey: JsPublicKeyInput,
+    transaction_pubkey: JsPublicKeyInput,
+    transaction_data: Vec<u8>,
+) -> Result<JsInstruction, JsValue> {
+    let executor_pubkey = parse_pubkey(&executor_pubkey)?;
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let transaction_pubkey = parse_pubkey(&transaction_pubkey)?;
+
+    let transaction_data = Transaction::unpack_from_slice(&transaction_data)
+        .handle_error(ErrorCode::InvalidAccountData)?;
+
+    let ix = check_transaction(
+        &executor_pubkey,
+        &multisig_pubkey,
+        &transaction_pubkey,
+        get_execute_accounts(&transaction_data),
+    );
+
+    return to_js(&ix);
+}
+
+#[wasm_bindgen(js_name = "decodeTransactionStatus")]
+pub fn decode_transaction_status_js(data: Vec<u8>) -> Result<JsTransactionStatus, JsValue> {
+    let status = decode_transaction_status(&data).handle_error(ErrorCode::InvalidAccountData)?;
+
+    return to_js(&status);
+}
+
+#[wasm_bindgen(js_name = "decodeTransaction")]
+pub fn decode_transaction_js(data: Vec<u8>) -> Result<JsDecodedInstruction, JsValue> {
+    let transaction =
+        Transaction::unpack_from_slice(&data).handle_error(ErrorCode::InvalidAccountData)?;
+
+    let decoded = decode_transaction(&transaction);
+
+    return to_js(&decoded);
+}
+
+#[wasm_bindgen(js_name = "decodeInstruction")]
+pub fn decode_instruction_js(
+    instruction: JsInstructionInput,
+) -> Result<JsDecodedInstruction, JsValue> {
+    let ix = parse_instruction(&instruction)?;
+
+    let accounts = ix
+        .accounts
+        .iter()
+        .map(|acc| TransactionAccount {
+            pubkey: acc.pubkey,
+            is_signer: acc.is_signer,
+            is_writable: acc.is_writable,
+        })
+        .collect::<Vec<_>>();
 
-    let data = MultisigInstruction::ExecuteTransaction
-        .try_to_vec()
-        .expect("pack");
+    let decoded = decode_instruction(&ix.program_id, &accounts, &ix.data);
 
-    let ix = Instruction {
-        program_id: id(),
-        accounts,
-        data,
+    return to_js(&decoded);
+}
+
+#[wasm_bindgen(js_name = "getProposalStatus")]
+pub fn get_proposal_status_js(
+    multisig_data: Vec<u8>,
+    transaction_data: Vec<u8>,
+) -> Result<JsProposalInfo, JsValue> {
+    let multisig = Multisig::unpack(&multisig_data).handle_error(ErrorCode::InvalidAccountData)?;
+    let transaction = Transaction::unpack_from_slice(&transaction_data)
+        .handle_error(ErrorCode::InvalidAccountData)?;
+
+    let info = get_proposal_info(&multisig, &transaction);
+
+    let info = WasmProposalInfo {
+        status: info.status,
+        approvers: info.approvers.iter().map(Pubkey::to_string).collect(),
+        missing_owners: info.missing_owners.iter().map(Pubkey::to_string).collect(),
+        threshold: info.threshold,
+    };
+
+    return to_js(&info);
+}
+
+#[wasm_bindgen(js_name = "decodeError")]
+pub fn decode_error_js(code: u32) -> Result<Option<JsProgramErrorInfo>, JsValue> {
+    let error = match MultisigError::try_from(code) {
+        Ok(error) => error,
+        Err(_) => return Ok(None),
+    };
+
+    let error = WasmMultisigError {
+        code,
+        name: format!("{:?}", error),
+        message: error.to_string(),
     };
 
-    return serde_wasm_bindgen::to_value(&ix).handle_error();
+    to_js(&error).map(Some)
+}
+
+#[wasm_bindgen(js_name = "createProposalBundle")]
+pub fn create_proposal_bundle_js(
+    multisig_data: Vec<u8>,
+    transaction_data: Vec<u8>,
+) -> Result<JsProposalBundle, JsValue> {
+    let multisig = Multisig::unpack(&multisig_data).handle_error(ErrorCode::InvalidAccountData)?;
+    let transaction = Transaction::unpack_from_slice(&transaction_data)
+        .handle_error(ErrorCode::InvalidAccountData)?;
+
+    let bundle = create_proposal_bundle(&multisig, &transaction);
+
+    return to_js(&bundle);
+}
+
+#[wasm_bindgen(js_name = "serializeProposalBundle")]
+pub fn serialize_proposal_bundle_js(
+    bundle: JsProposalBundle,
+    compact: bool,
+) -> Result<String, JsValue> {
+    let bundle: ProposalBundle = serde_wasm_bindgen::from_value(bundle.into())
+        .handle_error(ErrorCode::InvalidProposalBundle)?;
+    verify_proposal_bundle(&bundle).handle_error(ErrorCode::InvalidProposalBundle)?;
+
+    if compact {
+        serialize_proposal_bundle_compact(&bundle).handle_error(ErrorCode::InvalidProposalBundle)
+    } else {
+        Ok(serialize_proposal_bundle(&bundle))
+    }
+}
+
+#[wasm_bindgen(js_name = "parseProposalBundle")]
+pub fn parse_proposal_bundle_js(input: String) -> Result<JsProposalBundle, JsValue> {
+    let bundle = parse_proposal_bundle(&input).handle_error(ErrorCode::InvalidProposalBundle)?;
+
+    return to_js(&bundle);
+}
+
+#[wasm_bindgen(js_name = "verifyProposalBundle")]
+pub fn verify_proposal_bundle_js(bundle: JsProposalBundle) -> Result<(), JsValue> {
+    let bundle: ProposalBundle = serde_wasm_bindgen::from_value(bundle.into())
+        .handle_error(ErrorCode::InvalidProposalBundle)?;
+
+    verify_proposal_bundle(&bundle).handle_error(ErrorCode::InvalidProposalBundle)
+}
+
+#[wasm_bindgen(js_name = "getMultisigAddress")]
+pub fn get_multisig_address_js(seed: JsSeed) -> Result<String, JsValue> {
+    let seed = parse_seed(&seed)?;
+
+    let multisig_pubkey = get_multisig_address(seed);
+
+    Ok(multisig_pubkey.to_string())
+}
+
+#[wasm_bindgen(js_name = "getMultisigSeedFromLabel")]
+pub fn get_multisig_seed_from_label_js(namespace: String, label: String) -> u128 {
+    get_multisig_seed_from_label(&namespace, &label)
+}
+
+#[wasm_bindgen(js_name = "getMultisigSeedFromCreator")]
+pub fn get_multisig_seed_from_creator_js(
+    creator_pubkey: JsPublicKeyInput,
+    nonce: u64,
+) -> Result<u128, JsValue> {
+    let creator_pubkey = parse_pubkey(&creator_pubkey)?;
+
+    Ok(get_multisig_seed_from_creator(&creator_pubkey, nonce))
+}
+
+#[wasm_bindgen(js_name = "findMultisigLabel")]
+pub fn find_multisig_label_js(
+    multisig_data: Vec<u8>,
+    owners: JsPublicKeyInputArray,
+    threshold: u64,
+    namespace: String,
+    labels: JsStringArray,
+) -> Result<Option<String>, JsValue> {
+    let multisig = Multisig::unpack(&multisig_data).handle_error(ErrorCode::InvalidAccountData)?;
+    let owners = parse_pubkeys(&owners)?;
+
+    let labels: Vec<String> =
+        serde_wasm_bindgen::from_value(labels.into()).handle_error(ErrorCode::InvalidArgument)?;
+
+    let label = find_multisig_label(
+        &multisig,
+        &owners,
+        threshold,
+        &namespace,
+        labels.iter().map(String::as_str),
+    );
+
+    Ok(label.map(str::to_string))
+}
+
+#[wasm_bindgen(js_name = "findMultisigCreatorNonce")]
+pub fn find_multisig_creator_nonce_js(
+    multisig_data: Vec<u8>,
+    owners: JsPublicKeyInputArray,
+    threshold: u64,
+    creator_pubkey: JsPublicKeyInput,
+    max_nonce: u64,
+) -> Result<Option<u64>, JsValue> {
+    let multisig = Multisig::unpack(&multisig_data).handle_error(ErrorCode::InvalidAccountData)?;
+    let owners = parse_pubkeys(&owners)?;
+    let creator_pubkey = parse_pubkey(&creator_pubkey)?;
+
+    Ok(find_multisig_creator_nonce(
+        &multisig,
+        &owners,
+        threshold,
+        &creator_pubkey,
+        max_nonce,
+    ))
+}
+
+#[wasm_bindgen(js_name = "getTransactionAddress")]
+pub fn get_transaction_address_js(
+    multisig_pubkey: JsPublicKeyInput,
+    transaction_index: u64,
+) -> Result<String, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+
+    let transaction_pubkey = get_transaction_address(&multisig_pubkey, transaction_index);
+
+    Ok(transaction_pubkey.to_string())
+}
+
+#[wasm_bindgen(js_name = "getRecoveryAddress")]
+pub fn get_recovery_address_js(multisig_pubkey: JsPublicKeyInput) -> Result<String, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+
+    let recovery_pubkey = get_recovery_address(&multisig_pubkey);
+
+    Ok(recovery_pubkey.to_string())
+}
+
+#[wasm_bindgen(js_name = "getVaultAddress")]
+pub fn get_vault_address_js(multisig_pubkey: JsPublicKeyInput) -> Result<String, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+
+    let vault_pubkey = get_vault_address(&multisig_pubkey);
+
+    Ok(vault_pubkey.to_string())
+}
+
+#[wasm_bindgen(js_name = "getTransactionBufferAddress")]
+pub fn get_transaction_buffer_address_js(
+    multisig_pubkey: JsPublicKeyInput,
+    proposer_pubkey: JsPublicKeyInput,
+) -> Result<String, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+    let proposer_pubkey = parse_pubkey(&proposer_pubkey)?;
+
+    let buffer_pubkey = get_transaction_buffer_address(&multisig_pubkey, &proposer_pubkey);
+
+    Ok(buffer_pubkey.to_string())
+}
+
+#[wasm_bindgen(js_name = "getTransactionAddresses")]
+pub fn get_transaction_addresses_js(
+    multisig_pubkey: JsPublicKeyInput,
+    transaction_index: u64,
+) -> Result<JsStringArray, JsValue> {
+    let multisig_pubkey = parse_pubkey(&multisig_pubkey)?;
+
+    let addresses = get_transaction_addresses(&multisig_pubkey, transaction_index)
+        .into_iter()
+        .map(|address| address.to_string())
+        .collect::<Vec<_>>();
+
+    return to_js(&addresses);
 }
 
 #[wasm_bindgen(js_name = "unpackMultisig")]
-pub fn unpack_multisig(data: Vec<u8>) -> Result<JsValue, JsValue> {
-    let multisig = Multisig::unpack(&data).handle_error()?;
+pub fn unpack_multisig(data: Vec<u8>) -> Result<JsMultisig, JsValue> {
+    let multisig = Multisig::unpack(&data).handle_error(ErrorCode::InvalidAccountData)?;
 
     let msig = WasmMultisigMeta {
         threshold: multisig.threshold,
         owners: multisig.owners,
-        pending_transactions: multisig.pending_transactions,
+        pending_transaction_count: multisig.pending_transaction_count,
+        transaction_index: multisig.transaction_index,
+        max_signers: multisig.max_signers,
+        allowed_programs: multisig.allowed_programs,
+        admin_threshold: multisig.admin_threshold,
+        upgrade_threshold: multisig.upgrade_threshold,
+        owners_epoch: multisig.owners_epoch,
     };
 
-    return serde_wasm_bindgen::to_value(&msig).handle_error();
+    return to_js(&msig);
 }
 
 #[wasm_bindgen(js_name = "unpackTransaction")]
-pub fn unpack_transaction(data: Vec<u8>) -> Result<JsValue, JsValue> {
-    let transaction = Transaction::unpack_from_slice(&data).handle_error()?;
+pub fn unpack_transaction(data: Vec<u8>) -> Result<JsTransaction, JsValue> {
+    let transaction =
+        Transaction::unpack_from_slice(&data).handle_error(ErrorCode::InvalidAccountData)?;
 
     let tx = WasmTransactionMeta {
         multisig: transaction.multisig,
+        index: transaction.index,
         program_id: transaction.program_id,
         signers: transaction.signers,
         accounts: transaction.accounts,
         did_execute: transaction.did_execute,
+        is_cancelled: transaction.is_cancelled,
         data: transaction.data,
+        executed_slot: transaction.executed_slot,
+        executor: transaction.executor,
+        return_data: transaction.return_data,
+        proposer: transaction.proposer,
+        revision: transaction.revision,
+        lookup_tables: transaction.lookup_tables,
+        lookup_accounts: transaction.lookup_accounts,
+        lookup_addresses: transaction.lookup_addresses,
+        owners_epoch: transaction.owners_epoch,
+    };
+
+    return to_js(&tx);
+}
+
+#[wasm_bindgen(js_name = "unpackRecovery")]
+pub fn unpack_recovery(data: Vec<u8>) -> Result<JsRecovery, JsValue> {
+    let recovery = Recovery::unpack(&data).handle_error(ErrorCode::InvalidAccountData)?;
+
+    let pending = recovery.pending.map(|pending| WasmPendingRecoveryMeta {
+        owners: pending.owners,
+        threshold: pending.threshold,
+        executable_at: pending.executable_at,
+    });
+
+    let rec = WasmRecoveryMeta {
+        multisig: recovery.multisig,
+        guardians: recovery.guardians,
+        guardian_threshold: recovery.guardian_threshold,
+        delay: recovery.delay,
+        pending,
     };
 
-    return serde_wasm_bindgen::to_value(&tx).handle_error();
+    return to_js(&rec);
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct WasmMultisigMeta {
     pub threshold: u64,
     pub owners: Vec<Pubkey>,
-    pub pending_transactions: Vec<Pubkey>,
+    pub pending_transaction_count: u64,
+    pub transaction_index: u64,
+    pub max_signers: u32,
+    pub allowed_programs: Vec<Pubkey>,
+    pub admin_threshold: Option<u64>,
+    pub upgrade_threshold: Option<u64>,
+    pub owners_epoch: u64,
 }
 
 #[derive(Serialize, Deserialize)]
-pub struct WasmTransactionMeta {
+pub struct WasmRecoveryMeta {
     pub multisig: Pubkey,
-    pub program_id: Pubkey,
-    pub signers: Vec<bool>,
-    pub accounts: Vec<TransactionAccount>,
-    pub did_execute: bool,
-    pub data: Vec<u8>,
+    pub guardians: Vec<Pubkey>,
+    pub guardian_threshold: u64,
+    pub delay: i64,
+    pub pending: Option<WasmPendingRecoveryMeta>,
 }
 
-impl<T, E> HandleError for Result<T, E>
-where
-    E: ToString,
-{
-    type Output = T;
+#[derive(Serialize, Deserialize)]
+pub struct WasmPendingRecoveryMeta {
+    pub owners: Vec<Pubkey>,
+    pub threshold: u64,
+    pub executable_at: i64,
+}
 
-    fn handle_error(self) -> Result<Self::Output, JsValue> {
-        self.map_err(|e| {
-            let error = e.to_string();
-            js_sys::Error::new(&error).unchecked_into()
-        })
-    }
+#[derive(Serialize, Deserialize)]
+pub struct WasmProposalInfo {
+    pub status: ProposalStatus,
+    pub approvers: Vec<String>,
+    pub missing_owners: Vec<String>,
+    pub threshold: u64,
 }
 
-pub trait HandleError {
-    type Output;
+#[derive(Serialize, Deserialize)]
+pub struct WasmMultisigError {
+    pub code: u32,
+    pub name: String,
+    pub message: String,
+}
 
-    fn handle_error(self) -> Result<Self::Output, JsValue>;
+#[derive(Serialize, Deserialize)]
+pub struct WasmTransactionMeta {
+    pub multisig: Pubkey,
+    pub index: u64,
+    pub program_id: Pubkey,
+    pub signers: Vec<bool>,
+    pub accounts: Vec<TransactionAccount>,
+    pub did_execute: bool,
+    pub is_cancelled: bool,
+    pub data: Vec<u8>,
+    pub executed_slot: u64,
+    pub executor: Pubkey,
+    pub return_data: Vec<u8>,
+    pub proposer: Pubkey,
+    pub revision: u64,
+    pub lookup_tables: Vec<Pubkey>,
+    pub lookup_accounts: Vec<TransactionLookupAccount>,
+    pub lookup_addresses: Vec<Pubkey>,
+    pub owners_epoch: u64,
 }
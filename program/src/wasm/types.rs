@@ -0,0 +1,512 @@
+use std::str::FromStr;
+
+use js_sys::{Array, Function, Reflect, Uint8Array};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::{JsCast, JsValue};
+
+use solana_program::address_lookup_table::AddressLookupTableAccount;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+
+use crate::TRANSACTION_BUFFER_CHUNK_SIZE;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TYPESCRIPT_DEFINITIONS: &str = r#"
+/** Base58 string, 32 bytes, or an object with `toBase58` such as `PublicKey` of `@solana/web3.js` */
+export type PublicKeyInput = string | Uint8Array | number[] | { toBase58(): string };
+
+/** Multisig seed, either a 128-bit unsigned bigint or a UUID string */
+export type Seed = bigint | string;
+
+export type Bytes = Uint8Array | number[];
+
+/** Public key as returned in decoded accounts and instructions */
+export type PublicKeyBytes = number[];
+
+export interface AccountMeta {
+    pubkey: PublicKeyBytes;
+    is_signer: boolean;
+    is_writable: boolean;
+}
+
+export interface Instruction {
+    program_id: PublicKeyBytes;
+    accounts: AccountMeta[];
+    data: number[];
+}
+
+/** Instruction as returned by the builders or a `TransactionInstruction` of `@solana/web3.js` */
+export type InstructionInput =
+    | {
+          program_id: PublicKeyInput;
+          accounts: { pubkey: PublicKeyInput; is_signer: boolean; is_writable: boolean }[];
+          data: Bytes;
+      }
+    | {
+          programId: PublicKeyInput;
+          keys: { pubkey: PublicKeyInput; isSigner: boolean; isWritable: boolean }[];
+          data: Bytes;
+      };
+
+export interface LookupTableInput {
+    key: PublicKeyInput;
+    addresses: PublicKeyInput[];
+}
+
+export interface Ed25519SignatureInput {
+    pubkey: PublicKeyInput;
+    signature: Bytes;
+}
+
+export interface EthSignatureInput {
+    address: Bytes;
+    r: Bytes;
+    s: Bytes;
+    v: number;
+}
+
+export interface Multisig {
+    threshold: number;
+    owners: PublicKeyBytes[];
+    pending_transaction_count: number;
+    transaction_index: number;
+    max_signers: number;
+    allowed_programs: PublicKeyBytes[];
+    admin_threshold?: number;
+    upgrade_threshold?: number;
+    owners_epoch: number;
+}
+
+export interface TransactionLookupAccount {
+    table_index: number;
+    address_index: number;
+    is_writable: boolean;
+}
+
+export interface Transaction {
+    multisig: PublicKeyBytes;
+    index: number;
+    program_id: PublicKeyBytes;
+    signers: boolean[];
+    accounts: AccountMeta[];
+    did_execute: boolean;
+    is_cancelled: boolean;
+    data: number[];
+    executed_slot: number;
+    executor: PublicKeyBytes;
+    return_data: number[];
+    proposer: PublicKeyBytes;
+    revision: number;
+    lookup_tables: PublicKeyBytes[];
+    lookup_accounts: TransactionLookupAccount[];
+    lookup_addresses: PublicKeyBytes[];
+    owners_epoch: number;
+}
+
+export interface PendingRecovery {
+    owners: PublicKeyBytes[];
+    threshold: number;
+    executable_at: number;
+}
+
+export interface Recovery {
+    multisig: PublicKeyBytes;
+    guardians: PublicKeyBytes[];
+    guardian_threshold: number;
+    delay: number;
+    pending?: PendingRecovery;
+}
+
+export type BlockingReason =
+    | "NotEnoughSigners"
+    | "AlreadyExecuted"
+    | "Cancelled"
+    | "ProgramNotAllowed"
+    | "MissingAccount"
+    | "AccountNotWritable"
+    | "AccountNotSigner"
+    | "OwnersChanged"
+    | "LookupTableChanged";
+
+export interface TransactionStatus {
+    approvals: number;
+    threshold: number;
+    missing_owners: PublicKeyBytes[];
+    blocking_reasons: BlockingReason[];
+    timelock_remaining?: number;
+}
+
+export interface DecodedField {
+    name: string;
+    value: string;
+}
+
+export interface DecodedAccount {
+    pubkey: string;
+    is_signer: boolean;
+    is_writable: boolean;
+    role?: string;
+}
+
+export interface DecodedInstruction {
+    program_id: string;
+    program?: string;
+    name?: string;
+    fields: DecodedField[];
+    accounts: DecodedAccount[];
+    /** Hex encoded data of instructions which could not be decoded */
+    data?: string;
+}
+
+export type ProposalStatus = "Draft" | "Active" | "Approved" | "Executed" | "Cancelled" | "Stale";
+
+export interface ProposalInfo {
+    status: ProposalStatus;
+    approvers: string[];
+    missing_owners: string[];
+    threshold: number;
+}
+
+/** Multisig program error behind a `custom program error` code */
+export interface ProgramErrorInfo {
+    code: number;
+    name: string;
+    message: string;
+}
+
+export interface ProposalApproval {
+    owner: string;
+    approved: boolean;
+}
+
+export interface ProposalBundle {
+    format: string;
+    version: number;
+    multisig: string;
+    transaction: string;
+    index: number;
+    revision: number;
+    program_id: string;
+    data: string;
+    lookup_tables: string[];
+    lookup_accounts: TransactionLookupAccount[];
+    lookup_addresses: string[];
+    instruction: DecodedInstruction;
+    instruction_hash: string;
+    approvals: ProposalApproval[];
+    threshold: number;
+    did_execute: boolean;
+    is_cancelled: boolean;
+    message: string;
+}
+
+export type MultisigWasmErrorCode =
+    | "InvalidPubkey"
+    | "InvalidSeed"
+    | "InvalidHash"
+    | "InvalidBytes"
+    | "InvalidInstruction"
+    | "InvalidSignature"
+    | "InvalidLookupTable"
+    | "InvalidAccountData"
+    | "InvalidProposalBundle"
+    | "InvalidArgument"
+    | "SerializationFailed";
+
+/** Error thrown by every function of the module on invalid input */
+export interface MultisigWasmError extends Error {
+    name: "MultisigWasmError";
+    code: MultisigWasmErrorCode;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "PublicKeyInput")]
+    pub type JsPublicKeyInput;
+
+    #[wasm_bindgen(typescript_type = "PublicKeyInput[]")]
+    pub type JsPublicKeyInputArray;
+
+    #[wasm_bindgen(typescript_type = "Seed")]
+    pub type JsSeed;
+
+    #[wasm_bindgen(typescript_type = "InstructionInput")]
+    pub type JsInstructionInput;
+
+    #[wasm_bindgen(typescript_type = "LookupTableInput[]")]
+    pub type JsLookupTableInputArray;
+
+    #[wasm_bindgen(typescript_type = "Ed25519SignatureInput[]")]
+    pub type JsEd25519SignatureInputArray;
+
+    #[wasm_bindgen(typescript_type = "EthSignatureInput[]")]
+    pub type JsEthSignatureInputArray;
+
+    #[wasm_bindgen(typescript_type = "string[]")]
+    pub type JsStringArray;
+
+    #[wasm_bindgen(typescript_type = "Instruction")]
+    pub type JsInstruction;
+
+    #[wasm_bindgen(typescript_type = "Instruction[]")]
+    pub type JsInstructionArray;
+
+    #[wasm_bindgen(typescript_type = "Multisig")]
+    pub type JsMultisig;
+
+    #[wasm_bindgen(typescript_type = "Transaction")]
+    pub type JsTransaction;
+
+    #[wasm_bindgen(typescript_type = "Recovery")]
+    pub type JsRecovery;
+
+    #[wasm_bindgen(typescript_type = "TransactionStatus")]
+    pub type JsTransactionStatus;
+
+    #[wasm_bindgen(typescript_type = "DecodedInstruction")]
+    pub type JsDecodedInstruction;
+
+    #[wasm_bindgen(typescript_type = "ProposalInfo")]
+    pub type JsProposalInfo;
+
+    #[wasm_bindgen(typescript_type = "ProgramErrorInfo")]
+    pub type JsProgramErrorInfo;
+
+    #[wasm_bindgen(typescript_type = "ProposalBundle")]
+    pub type JsProposalBundle;
+}
+
+/// Value of the `code` property of thrown errors
+#[derive(Debug, Clone, Copy)]
+pub enum ErrorCode {
+    InvalidPubkey,
+    InvalidSeed,
+    InvalidHash,
+    InvalidBytes,
+    InvalidInstruction,
+    InvalidSignature,
+    InvalidLookupTable,
+    InvalidAccountData,
+    InvalidProposalBundle,
+    InvalidArgument,
+    SerializationFailed,
+}
+
+/// `MultisigWasmError` to be thrown on the JS side
+pub fn error(code: ErrorCode, message: &str) -> JsValue {
+    let error = js_sys::Error::new(message);
+    error.set_name("MultisigWasmError");
+
+    // Setting a property of a fresh error object cannot fail
+    let _ = Reflect::set(
+        &error,
+        &JsValue::from_str("code"),
+        &JsValue::from_str(&format!("{:?}", code)),
+    );
+
+    error.into()
+}
+
+pub trait HandleError {
+    type Output;
+
+    fn handle_error(self, code: ErrorCode) -> Result<Self::Output, JsValue>;
+}
+
+impl<T, E> HandleError for Result<T, E>
+where
+    E: ToString,
+{
+    type Output = T;
+
+    fn handle_error(self, code: ErrorCode) -> Result<Self::Output, JsValue> {
+        self.map_err(|e| error(code, &e.to_string()))
+    }
+}
+
+/// Convert a value into the JS object described by the typed handle `T`
+pub fn to_js<S, T>(value: &S) -> Result<T, JsValue>
+where
+    S: Serialize + ?Sized,
+    T: JsCast,
+{
+    serde_wasm_bindgen::to_value(value)
+        .handle_error(ErrorCode::SerializationFailed)
+        .map(JsCast::unchecked_into)
+}
+
+pub fn parse_pubkey(value: &JsValue) -> Result<Pubkey, JsValue> {
+    if let Some(value) = value.as_string() {
+        return Pubkey::from_str(&value).handle_error(ErrorCode::InvalidPubkey);
+    }
+
+    if let Some(to_base58) = get_property(value, "toBase58").dyn_ref::<Function>() {
+        let value = to_base58
+            .call0(value)
+            .ok()
+            .and_then(|value| value.as_string())
+            .ok_or_else(|| error(ErrorCode::InvalidPubkey, "toBase58 did not return a string"))?;
+
+        return Pubkey::from_str(&value).handle_error(ErrorCode::InvalidPubkey);
+    }
+
+    match parse_bytes(value) {
+        Ok(bytes) => Pubkey::try_from(bytes.as_slice()).handle_error(ErrorCode::InvalidPubkey),
+        Err(_) => Err(error(
+            ErrorCode::InvalidPubkey,
+            "Expected a base58 string, 32 bytes or a PublicKey",
+        )),
+    }
+}
+
+pub fn parse_pubkeys(value: &JsValue) -> Result<Vec<Pubkey>, JsValue> {
+    parse_array(value, ErrorCode::InvalidPubkey)?
+        .iter()
+        .map(|value| parse_pubkey(&value))
+        .collect()
+}
+
+pub fn parse_seed(value: &JsValue) -> Result<u128, JsValue> {
+    if value.is_bigint() {
+        return u128::try_from(value.clone()).map_err(|_| {
+            error(
+                ErrorCode::InvalidSeed,
+                "Seed must be an unsigned 128-bit integer",
+            )
+        });
+    }
+
+    match value.as_string() {
+        Some(value) => Ok(uuid::Uuid::from_str(&value)
+            .handle_error(ErrorCode::InvalidSeed)?
+            .as_u128()),
+        None => Err(error(
+            ErrorCode::InvalidSeed,
+            "Expected a bigint or a UUID string",
+        )),
+    }
+}
+
+/// Chunk size of transaction buffer uploads, `TRANSACTION_BUFFER_CHUNK_SIZE` if not set
+pub fn parse_chunk_size(chunk_size: Option<usize>) -> Result<usize, JsValue> {
+    match chunk_size {
+        Some(0) => Err(error(
+            ErrorCode::InvalidArgument,
+            "Chunk size must be positive",
+        )),
+        Some(chunk_size) => Ok(chunk_size),
+        None => Ok(TRANSACTION_BUFFER_CHUNK_SIZE),
+    }
+}
+
+/// Bytes from a `Uint8Array`, including node `Buffer`, or an array of numbers
+pub fn parse_bytes(value: &JsValue) -> Result<Vec<u8>, JsValue> {
+    if let Some(bytes) = value.dyn_ref::<Uint8Array>() {
+        return Ok(bytes.to_vec());
+    }
+
+    parse_array(value, ErrorCode::InvalidBytes)?
+        .iter()
+        .map(|byte| {
+            byte.as_f64()
+                .filter(|byte| byte.fract() == 0.0 && (0.0..=255.0).contains(byte))
+                .map(|byte| byte as u8)
+                .ok_or_else(|| error(ErrorCode::InvalidBytes, "Expected a byte"))
+        })
+        .collect()
+}
+
+/// Instruction in either the builders' or the web3.js layout
+pub fn parse_instruction(value: &JsValue) -> Result<Instruction, JsValue> {
+    let code = ErrorCode::InvalidInstruction;
+
+    let program_id = get_field(value, &["program_id", "programId"], code)?;
+    let accounts = get_field(value, &["accounts", "keys"], code)?;
+    let data = get_field(value, &["data"], code)?;
+
+    let accounts = parse_array(&accounts, code)?
+        .iter()
+        .map(|account| {
+            let pubkey = parse_pubkey(&get_field(&account, &["pubkey"], code)?)?;
+            let is_signer = get_bool(&account, &["is_signer", "isSigner"], code)?;
+            let is_writable = get_bool(&account, &["is_writable", "isWritable"], code)?;
+
+            Ok(AccountMeta {
+                pubkey,
+                is_signer,
+                is_writable,
+            })
+        })
+        .collect::<Result<_, JsValue>>()?;
+
+    Ok(Instruction {
+        program_id: parse_pubkey(&program_id)?,
+        accounts,
+        data: parse_bytes(&data)?,
+    })
+}
+
+pub fn parse_lookup_tables(value: &JsValue) -> Result<Vec<AddressLookupTableAccount>, JsValue> {
+    let code = ErrorCode::InvalidLookupTable;
+
+    parse_array(value, code)?
+        .iter()
+        .map(|table| {
+            Ok(AddressLookupTableAccount {
+                key: parse_pubkey(&get_field(&table, &["key"], code)?)?,
+                addresses: parse_pubkeys(&get_field(&table, &["addresses"], code)?)?,
+            })
+        })
+        .collect()
+}
+
+/// Fixed size byte array of an object field
+pub fn get_byte_array<const N: usize>(
+    value: &JsValue,
+    name: &str,
+    code: ErrorCode,
+) -> Result<[u8; N], JsValue> {
+    let bytes = parse_bytes(&get_field(value, &[name], code)?)?;
+
+    <[u8; N]>::try_from(bytes.as_slice())
+        .map_err(|_| error(code, &format!("{} must be {} bytes long", name, N)))
+}
+
+pub fn get_number(value: &JsValue, name: &str, code: ErrorCode) -> Result<f64, JsValue> {
+    get_field(value, &[name], code)?
+        .as_f64()
+        .ok_or_else(|| error(code, &format!("{} must be a number", name)))
+}
+
+pub fn parse_array(value: &JsValue, code: ErrorCode) -> Result<Array, JsValue> {
+    if Array::is_array(value) {
+        Ok(value.clone().unchecked_into())
+    } else {
+        Err(error(code, "Expected an array"))
+    }
+}
+
+/// First of the alternative field names set on the object
+pub fn get_field(value: &JsValue, names: &[&str], code: ErrorCode) -> Result<JsValue, JsValue> {
+    names
+        .iter()
+        .map(|name| get_property(value, name))
+        .find(|field| !field.is_undefined())
+        .ok_or_else(|| error(code, &format!("Missing field {}", names.join(" or "))))
+}
+
+fn get_bool(value: &JsValue, names: &[&str], code: ErrorCode) -> Result<bool, JsValue> {
+    get_field(value, names, code)?
+        .as_bool()
+        .ok_or_else(|| error(code, &format!("{} must be a boolean", names[0])))
+}
+
+fn get_property(value: &JsValue, name: &str) -> JsValue {
+    if !value.is_object() {
+        return JsValue::UNDEFINED;
+    }
+
+    Reflect::get(value, &JsValue::from_str(name)).unwrap_or(JsValue::UNDEFINED)
+}
@@ -0,0 +1,464 @@
+#![cfg(feature = "bindings")]
+
+use std::collections::BTreeSet;
+
+use borsh::BorshDeserialize;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+use solana_program::system_instruction;
+
+use multisig::{MultisigInstruction, TransactionAccount, TRANSACTION_BUFFER_CHUNK_SIZE};
+
+/// One instruction of every kind built through the bindings, along with the
+/// name of the builder
+fn multisig_builders() -> Vec<(&'static str, Instruction)> {
+    let funder = Pubkey::new_unique();
+    let proposer = Pubkey::new_unique();
+    let multisig = multisig::get_multisig_address(1);
+    let transaction = multisig::get_transaction_address(&multisig, 1);
+    let owner = Pubkey::new_unique();
+
+    let ix = system_instruction::transfer(&multisig, &funder, 1);
+
+    vec![
+        (
+            "create_multisig",
+            multisig::create_multisig(&funder, 1, vec![owner], 1, multisig::MAX_SIGNERS as u32),
+        ),
+        ("add_owner", multisig::add_owner(&multisig, owner)),
+        ("delete_owner", multisig::delete_owner(&multisig, owner)),
+        ("update_threshold", multisig::update_threshold(&multisig, 1)),
+        (
+            "create_transaction",
+            multisig::create_transaction(&funder, &proposer, &multisig, 1, ix.clone()),
+        ),
+        (
+            "approve",
+            multisig::approve(&proposer, &multisig, &transaction),
+        ),
+        (
+            "execute_transaction",
+            multisig::execute_transaction(&funder, &multisig, &transaction, vec![]),
+        ),
+        (
+            "delete_pending_transaction",
+            multisig::delete_pending_transaction(&multisig, transaction),
+        ),
+        (
+            "resize",
+            multisig::resize(&multisig, &funder, multisig::MAX_SIGNERS as u32),
+        ),
+        (
+            "close_multisig",
+            multisig::close_multisig(&multisig, &funder, vec![]),
+        ),
+        (
+            "approve_with_signatures",
+            multisig::approve_with_signatures(&multisig, &transaction),
+        ),
+        (
+            "add_allowed_program",
+            multisig::add_allowed_program(&multisig, &funder, Pubkey::new_unique()),
+        ),
+        (
+            "remove_allowed_program",
+            multisig::remove_allowed_program(&multisig, Pubkey::new_unique()),
+        ),
+        (
+            "update_action_thresholds",
+            multisig::update_action_thresholds(&multisig, Some(1), None),
+        ),
+        (
+            "configure_recovery",
+            multisig::configure_recovery(
+                &multisig,
+                &funder,
+                vec![owner],
+                1,
+                multisig::MIN_RECOVERY_DELAY,
+            ),
+        ),
+        (
+            "start_recovery",
+            multisig::start_recovery(&multisig, &[owner], vec![proposer], 1),
+        ),
+        ("veto_recovery", multisig::veto_recovery(&multisig, &owner)),
+        ("finalize_recovery", multisig::finalize_recovery(&multisig)),
+        (
+            "check_transaction",
+            multisig::check_transaction(&funder, &multisig, &transaction, vec![]),
+        ),
+        (
+            "update_transaction",
+            multisig::update_transaction(&funder, &proposer, &multisig, &transaction, ix.clone()),
+        ),
+        (
+            "create_transaction_buffer",
+            multisig::create_transaction_buffer(
+                &funder,
+                &proposer,
+                &multisig,
+                &ix,
+                TRANSACTION_BUFFER_CHUNK_SIZE,
+            ),
+        ),
+        (
+            "extend_transaction_buffer",
+            multisig::extend_transaction_buffer(&funder, &proposer, &multisig, vec![0; 8]),
+        ),
+        (
+            "finalize_transaction",
+            multisig::finalize_transaction(&funder, &proposer, &multisig, 1, &funder),
+        ),
+        (
+            "create_transaction_with_lookups",
+            multisig::create_transaction_with_lookups(
+                &funder,
+                &proposer,
+                &multisig,
+                1,
+                ix.clone(),
+                &[],
+            ),
+        ),
+        ("migrate_multisig", multisig::migrate_multisig(&multisig)),
+        (
+            "close_transaction_buffer",
+            multisig::close_transaction_buffer(&proposer, &multisig, &funder),
+        ),
+        (
+            "update_transaction_with_lookups",
+            multisig::update_transaction_with_lookups(
+                &funder,
+                &proposer,
+                &multisig,
+                &transaction,
+                ix,
+                &[],
+            ),
+        ),
+    ]
+}
+
+#[test]
+fn test_every_instruction_has_builder() {
+    let builders = multisig_builders();
+
+    let mut variants = BTreeSet::new();
+    for (builder, ix) in &builders {
+        assert_eq!(ix.program_id, multisig::id(), "{}", builder);
+
+        let instruction = MultisigInstruction::try_from_slice(&ix.data).expect(builder);
+        let variant = ix.data[0];
+
+        // Every builder produces an instruction of its own kind
+        assert!(variants.insert(variant), "{}: {:?}", builder, instruction);
+    }
+
+    // Borsh enum variants are numbered sequentially, so the builders have to
+    // cover indices up to the first one that fails to decode as unknown
+    let count = variants.len();
+    assert!(variants.iter().copied().eq(0..count as u8));
+
+    let error = MultisigInstruction::try_from_slice(&[count as u8]).unwrap_err();
+    assert!(
+        error.to_string().contains("Unexpected variant index"),
+        "instruction {} has no builder",
+        count
+    );
+}
+
+#[test]
+fn test_transaction_buffer_chunk_size() {
+    let funder = Pubkey::new_unique();
+    let proposer = Pubkey::new_unique();
+    let multisig = multisig::get_multisig_address(1);
+
+    let ix = Instruction::new_with_bytes(Pubkey::new_unique(), &[1, 2, 3], vec![]);
+
+    let chunks = |chunk_size| {
+        multisig::create_transaction_with_buffer(
+            &funder,
+            &proposer,
+            &multisig,
+            1,
+            ix.clone(),
+            chunk_size,
+        )
+        .len()
+    };
+
+    // Create, one extension per remaining byte and finalize
+    assert_eq!(chunks(0), 4);
+    assert_eq!(chunks(1), 4);
+    assert_eq!(chunks(usize::MAX), 2);
+
+    let ix = Instruction::new_with_bytes(
+        Pubkey::new_unique(),
+        &vec![0; TRANSACTION_BUFFER_CHUNK_SIZE + 1],
+        vec![],
+    );
+
+    let create =
+        multisig::create_transaction_buffer(&funder, &proposer, &multisig, &ix, usize::MAX);
+
+    match MultisigInstruction::try_from_slice(&create.data).expect("instruction") {
+        MultisigInstruction::CreateTransactionBuffer { data, .. } => {
+            assert_eq!(data.len(), TRANSACTION_BUFFER_CHUNK_SIZE)
+        }
+        instruction => panic!("unexpected {:?}", instruction),
+    }
+}
+
+fn decode(ix: &Instruction) -> multisig::DecodedInstruction {
+    let accounts = ix
+        .accounts
+        .iter()
+        .map(|acc| TransactionAccount {
+            pubkey: acc.pubkey,
+            is_signer: acc.is_signer,
+            is_writable: acc.is_writable,
+        })
+        .collect::<Vec<_>>();
+
+    multisig::decode_instruction(&ix.program_id, &accounts, &ix.data)
+}
+
+fn roles(decoded: &multisig::DecodedInstruction) -> Vec<&str> {
+    decoded
+        .accounts
+        .iter()
+        .map(|acc| acc.role.as_deref().unwrap_or_default())
+        .collect()
+}
+
+#[test]
+fn test_decode_multisig_instructions() {
+    for (builder, ix) in multisig_builders() {
+        let decoded = decode(&ix);
+
+        assert_eq!(decoded.program.as_deref(), Some("Multisig"), "{}", builder);
+        assert!(decoded.name.is_some(), "{}", builder);
+        assert!(decoded.data.is_none(), "{}", builder);
+        assert!(
+            decoded.accounts.iter().all(|acc| acc.role.is_some()),
+            "{}: {:?}",
+            builder,
+            decoded.accounts
+        );
+    }
+
+    let multisig = multisig::get_multisig_address(1);
+    let owner = Pubkey::new_unique();
+
+    let decoded = decode(&multisig::add_owner(&multisig, owner));
+    assert_eq!(decoded.name.as_deref(), Some("AddOwner"));
+    assert_eq!(decoded.field("owner"), Some(owner.to_string().as_str()));
+    assert_eq!(roles(&decoded), vec!["multisig"]);
+
+    let decoded = decode(&multisig::update_action_thresholds(
+        &multisig,
+        Some(2),
+        None,
+    ));
+    assert_eq!(decoded.field("admin_threshold"), Some("2"));
+    assert_eq!(decoded.field("upgrade_threshold"), Some("none"));
+}
+
+#[test]
+fn test_decode_known_programs() {
+    let multisig = multisig::get_multisig_address(1);
+    let program = Pubkey::new_unique();
+    let recipient = Pubkey::new_unique();
+
+    // System
+    let decoded = decode(&system_instruction::transfer(&multisig, &recipient, 42));
+    assert_eq!(decoded.program.as_deref(), Some("System"));
+    assert_eq!(decoded.name.as_deref(), Some("Transfer"));
+    assert_eq!(decoded.field("lamports"), Some("42"));
+    assert_eq!(roles(&decoded), vec!["from", "to"]);
+
+    // Upgradeable loader
+    let decoded = decode(&multisig::upgrade_program(
+        &program,
+        &Pubkey::new_unique(),
+        &multisig,
+        &recipient,
+    ));
+    assert_eq!(decoded.name.as_deref(), Some("Upgrade"));
+    assert_eq!(decoded.accounts[6].pubkey, multisig.to_string());
+    assert_eq!(decoded.accounts[6].role.as_deref(), Some("authority"));
+
+    let decoded = decode(&multisig::set_program_authority(
+        &multisig, &recipient, &program,
+    ));
+    assert_eq!(decoded.name.as_deref(), Some("SetAuthority"));
+    assert_eq!(
+        roles(&decoded),
+        vec!["account", "current authority", "new authority"]
+    );
+
+    let decoded = decode(&multisig::close_program(&program, &multisig, &recipient));
+    assert_eq!(decoded.name.as_deref(), Some("Close"));
+    assert_eq!(decoded.accounts[3].pubkey, program.to_string());
+    assert_eq!(decoded.accounts[3].role.as_deref(), Some("program"));
+
+    // SPL Token
+    let mut data = vec![12];
+    data.extend_from_slice(&1_000_000u64.to_le_bytes());
+    data.push(6);
+
+    for program_id in [multisig::TOKEN_PROGRAM_ID, multisig::TOKEN_2022_PROGRAM_ID] {
+        let ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(Pubkey::new_unique(), false),
+                AccountMeta::new_readonly(Pubkey::new_unique(), false),
+                AccountMeta::new(Pubkey::new_unique(), false),
+                AccountMeta::new_readonly(multisig, true),
+            ],
+            data: data.clone(),
+        };
+
+        let decoded = decode(&ix);
+        assert_eq!(decoded.name.as_deref(), Some("TransferChecked"));
+        assert_eq!(decoded.field("amount"), Some("1000000"));
+        assert_eq!(decoded.field("decimals"), Some("6"));
+        assert_eq!(
+            roles(&decoded),
+            vec!["source", "mint", "destination", "authority"]
+        );
+    }
+
+    // Truncated token instruction
+    let ix = Instruction {
+        program_id: multisig::TOKEN_PROGRAM_ID,
+        accounts: vec![],
+        data: vec![3, 1, 2],
+    };
+    let decoded = decode(&ix);
+    assert_eq!(decoded.program.as_deref(), Some("SPL Token"));
+    assert!(decoded.name.is_none());
+    assert_eq!(decoded.data.as_deref(), Some("030102"));
+
+    // Memo
+    let ix = Instruction {
+        program_id: multisig::MEMO_PROGRAM_ID,
+        accounts: vec![AccountMeta::new_readonly(multisig, true)],
+        data: b"hello".to_vec(),
+    };
+    let decoded = decode(&ix);
+    assert_eq!(decoded.field("memo"), Some("hello"));
+    assert_eq!(roles(&decoded), vec!["signer"]);
+
+    // Unknown program
+    let ix = Instruction {
+        program_id: program,
+        accounts: vec![AccountMeta::new(recipient, false)],
+        data: vec![0xde, 0xad],
+    };
+    let decoded = decode(&ix);
+    assert!(decoded.program.is_none());
+    assert!(decoded.name.is_none());
+    assert_eq!(decoded.data.as_deref(), Some("dead"));
+    assert_eq!(decoded.accounts[0].role, None);
+}
+
+fn multisig_with_seed(seed: u128, owners: Vec<Pubkey>, threshold: u64) -> multisig::Multisig {
+    multisig::Multisig {
+        is_initialized: true,
+        owners,
+        threshold,
+        pending_transaction_count: 0,
+        transaction_index: 0,
+        seed,
+        max_signers: multisig::MAX_SIGNERS as u32,
+        allowed_programs: vec![],
+        admin_threshold: None,
+        upgrade_threshold: None,
+        owners_epoch: 0,
+    }
+}
+
+#[test]
+fn test_multisig_seeds() {
+    let seed = multisig::get_multisig_seed_from_label("octusbridge", "bridge-ops-mainnet");
+
+    // Seeds are part of the addresses, the derivation must not change
+    assert_eq!(seed, 238008758630560011039212440474490484433);
+    assert_eq!(
+        seed,
+        multisig::get_multisig_seed_from_label("octusbridge", "bridge-ops-mainnet")
+    );
+
+    // Namespace and label are not simply concatenated
+    assert_ne!(
+        seed,
+        multisig::get_multisig_seed_from_label("octusbridge-", "bridge-ops-mainnet")
+    );
+    assert_ne!(
+        multisig::get_multisig_seed_from_label("a", "bc"),
+        multisig::get_multisig_seed_from_label("ab", "c")
+    );
+
+    let owners = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+    let multisig = multisig_with_seed(seed, owners.clone(), 2);
+    let labels = [
+        "bridge-ops-devnet",
+        "bridge-ops-mainnet",
+        "bridge-ops-testnet",
+    ];
+
+    assert_eq!(
+        multisig::find_multisig_label(&multisig, &owners, 2, "octusbridge", labels),
+        Some("bridge-ops-mainnet")
+    );
+    assert_eq!(
+        multisig::find_multisig_label(&multisig, &owners, 2, "other", labels),
+        None
+    );
+
+    // Created first by someone else with other members
+    let front_run = multisig_with_seed(seed, vec![Pubkey::new_unique()], 1);
+    assert_eq!(
+        multisig::find_multisig_label(&front_run, &owners, 2, "octusbridge", labels),
+        None
+    );
+    assert_eq!(
+        multisig::find_multisig_label(&multisig, &owners, 1, "octusbridge", labels),
+        None
+    );
+
+    // Creator and nonce
+    let creator = Pubkey::new_unique();
+
+    let seed = multisig::get_multisig_seed_from_creator(&creator, 3);
+    assert_ne!(seed, multisig::get_multisig_seed_from_creator(&creator, 4));
+    assert_ne!(
+        seed,
+        multisig::get_multisig_seed_from_creator(&Pubkey::new_unique(), 3)
+    );
+
+    let multisig = multisig_with_seed(seed, owners.clone(), 2);
+    assert_eq!(
+        multisig::find_multisig_creator_nonce(&multisig, &owners, 2, &creator, 10),
+        Some(3)
+    );
+    assert_eq!(
+        multisig::find_multisig_creator_nonce(&multisig, &owners, 2, &creator, 3),
+        None
+    );
+    assert_eq!(
+        multisig::find_multisig_creator_nonce(&multisig, &owners[..1], 2, &creator, 10),
+        None
+    );
+
+    // The search is capped whatever the requested bound
+    let seed =
+        multisig::get_multisig_seed_from_creator(&creator, multisig::MAX_MULTISIG_CREATOR_NONCE);
+    let multisig = multisig_with_seed(seed, owners.clone(), 2);
+    assert_eq!(
+        multisig::find_multisig_creator_nonce(&multisig, &owners, 2, &creator, u64::MAX),
+        None
+    );
+}
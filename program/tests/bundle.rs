@@ -0,0 +1,244 @@
+#![cfg(feature = "bindings")]
+
+use solana_program::pubkey::Pubkey;
+use solana_program::system_instruction;
+
+use multisig::{
+    Multisig, ProposalBundle, ProposalBundleError, Transaction, TransactionAccount,
+    TransactionLookupAccount, PROPOSAL_BUNDLE_COMPACT_PREFIX, PROPOSAL_BUNDLE_VERSION,
+};
+
+fn proposal() -> (Multisig, Transaction) {
+    let owners = vec![
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    ];
+
+    let multisig_pubkey = multisig::get_multisig_address(1);
+    let ix = system_instruction::transfer(&multisig_pubkey, &Pubkey::new_unique(), 1_000);
+
+    let multisig = Multisig {
+        is_initialized: true,
+        owners: owners.clone(),
+        threshold: 2,
+        pending_transaction_count: 1,
+        transaction_index: 3,
+        seed: 1,
+        max_signers: multisig::MAX_SIGNERS as u32,
+        allowed_programs: vec![],
+        admin_threshold: Some(3),
+        upgrade_threshold: None,
+        owners_epoch: 0,
+    };
+
+    let transaction = Transaction {
+        is_initialized: true,
+        multisig: multisig_pubkey,
+        index: 3,
+        program_id: ix.program_id,
+        accounts: ix
+            .accounts
+            .iter()
+            .map(|acc| TransactionAccount {
+                pubkey: acc.pubkey,
+                is_signer: acc.is_signer,
+                is_writable: acc.is_writable,
+            })
+            .collect(),
+        data: ix.data,
+        signers: vec![true, false, true],
+        did_execute: false,
+        is_cancelled: false,
+        executed_slot: 0,
+        executor: Pubkey::default(),
+        return_data: vec![],
+        proposer: owners[0],
+        revision: 2,
+        lookup_tables: vec![Pubkey::new_unique()],
+        lookup_accounts: vec![TransactionLookupAccount {
+            table_index: 0,
+            address_index: 5,
+            is_writable: true,
+        }],
+        lookup_addresses: vec![Pubkey::new_unique()],
+        owners_epoch: 0,
+    };
+
+    (multisig, transaction)
+}
+
+fn bundle() -> ProposalBundle {
+    let (multisig, transaction) = proposal();
+    multisig::create_proposal_bundle(&multisig, &transaction)
+}
+
+#[test]
+fn test_create_proposal_bundle() {
+    let (multisig, transaction) = proposal();
+    let bundle = multisig::create_proposal_bundle(&multisig, &transaction);
+
+    let transaction_pubkey = multisig::get_transaction_address(&transaction.multisig, 3);
+
+    assert_eq!(bundle.version, PROPOSAL_BUNDLE_VERSION);
+    assert_eq!(bundle.multisig, transaction.multisig.to_string());
+    assert_eq!(bundle.transaction, transaction_pubkey.to_string());
+    assert_eq!(bundle.revision, 2);
+    assert_eq!(bundle.threshold, 2);
+    assert_eq!(bundle.instruction.name.as_deref(), Some("Transfer"));
+    assert_eq!(bundle.instruction.field("lamports"), Some("1000"));
+
+    // Accounts resolved through the lookup tables follow the static ones
+    let lookup_account = bundle.instruction.accounts.last().unwrap();
+    assert_eq!(bundle.instruction.accounts.len(), 3);
+    assert_eq!(
+        lookup_account.pubkey,
+        transaction.lookup_addresses[0].to_string()
+    );
+    assert!(lookup_account.is_writable);
+
+    let approved = bundle
+        .approvals
+        .iter()
+        .map(|approval| approval.approved)
+        .collect::<Vec<_>>();
+    assert_eq!(approved, transaction.signers);
+    assert_eq!(bundle.approvals[1].owner, multisig.owners[1].to_string());
+
+    let message = multisig::get_approval_message(
+        &transaction.multisig,
+        &transaction_pubkey,
+        2,
+        &transaction.get_instruction_hash(),
+    );
+    assert_eq!(
+        base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &bundle.message)
+            .unwrap(),
+        message
+    );
+
+    multisig::verify_proposal_bundle(&bundle).expect("verify");
+}
+
+#[test]
+fn test_proposal_bundle_round_trip() {
+    let bundle = bundle();
+
+    let json = multisig::serialize_proposal_bundle(&bundle);
+    assert_eq!(
+        multisig::parse_proposal_bundle(&json).expect("json"),
+        bundle
+    );
+
+    let compact = multisig::serialize_proposal_bundle_compact(&bundle).expect("compact");
+    assert!(compact.starts_with(&format!(
+        "{}{}:",
+        PROPOSAL_BUNDLE_COMPACT_PREFIX, PROPOSAL_BUNDLE_VERSION
+    )));
+    assert!(!compact.contains(char::is_whitespace));
+    assert!(compact.len() < json.len());
+    assert_eq!(
+        multisig::parse_proposal_bundle(&compact).expect("compact"),
+        bundle
+    );
+
+    // Both forms are canonical
+    let parsed = multisig::parse_proposal_bundle(&compact).unwrap();
+    assert_eq!(multisig::serialize_proposal_bundle(&parsed), json);
+    assert_eq!(
+        multisig::serialize_proposal_bundle_compact(&parsed).unwrap(),
+        compact
+    );
+}
+
+#[test]
+fn test_proposal_bundle_tampering() {
+    let bundle = bundle();
+
+    let check = |bundle: ProposalBundle, expected: &str| {
+        let error = multisig::verify_proposal_bundle(&bundle).unwrap_err();
+        assert!(
+            error.to_string().contains(expected),
+            "{} does not mention {}",
+            error,
+            expected
+        );
+
+        let json = multisig::serialize_proposal_bundle(&bundle);
+        assert!(multisig::parse_proposal_bundle(&json).is_err());
+    };
+
+    let mut tampered = bundle.clone();
+    tampered.transaction = Pubkey::new_unique().to_string();
+    check(tampered, "transaction");
+
+    let mut tampered = bundle.clone();
+    tampered.index += 1;
+    check(tampered, "transaction");
+
+    let mut tampered = bundle.clone();
+    tampered.instruction.fields[0].value = "1".to_string();
+    check(tampered, "instruction");
+
+    let mut tampered = bundle.clone();
+    tampered.instruction.accounts[1].role = Some("from".to_string());
+    check(tampered, "instruction");
+
+    let mut tampered = bundle.clone();
+    tampered.lookup_addresses[0] = Pubkey::new_unique().to_string();
+    check(tampered, "instruction");
+
+    let mut tampered = bundle.clone();
+    tampered.instruction_hash =
+        base64::Engine::encode(&base64::engine::general_purpose::STANDARD, [0; 32]);
+    check(tampered, "instruction hash");
+
+    let mut tampered = bundle.clone();
+    tampered.revision += 1;
+    check(tampered, "message");
+
+    let mut tampered = bundle.clone();
+    tampered.version = PROPOSAL_BUNDLE_VERSION + 1;
+    assert!(matches!(
+        multisig::verify_proposal_bundle(&tampered),
+        Err(ProposalBundleError::UnsupportedVersion(_))
+    ));
+
+    let mut tampered = bundle.clone();
+    tampered.format = "something-else".to_string();
+    assert!(matches!(
+        multisig::verify_proposal_bundle(&tampered),
+        Err(ProposalBundleError::UnknownFormat)
+    ));
+
+    // Compact form
+    let compact = multisig::serialize_proposal_bundle_compact(&bundle).unwrap();
+
+    let future = compact.replacen(
+        &format!(
+            "{}{}:",
+            PROPOSAL_BUNDLE_COMPACT_PREFIX, PROPOSAL_BUNDLE_VERSION
+        ),
+        &format!(
+            "{}{}:",
+            PROPOSAL_BUNDLE_COMPACT_PREFIX,
+            PROPOSAL_BUNDLE_VERSION + 1
+        ),
+        1,
+    );
+    assert!(matches!(
+        multisig::parse_proposal_bundle(&future),
+        Err(ProposalBundleError::UnsupportedVersion(_))
+    ));
+
+    let truncated = &compact[..compact.len() - 4];
+    assert!(matches!(
+        multisig::parse_proposal_bundle(truncated),
+        Err(ProposalBundleError::Encoding)
+    ));
+
+    assert!(matches!(
+        multisig::parse_proposal_bundle("not a bundle"),
+        Err(ProposalBundleError::Json(_))
+    ));
+}
@@ -0,0 +1,67 @@
+use solana_program::program_error::ProgramError;
+
+use multisig::MultisigError::{self, *};
+
+/// Codes are part of the program interface, new errors go to the end
+fn expected_code(error: MultisigError) -> u32 {
+    match error {
+        InvalidOwner => 0,
+        InvalidOwnersLen => 1,
+        NotEnoughSigners => 2,
+        TransactionAlreadySigned => 3,
+        Overflow => 4,
+        UnableToDelete => 5,
+        AlreadyExecuted => 6,
+        InvalidThreshold => 7,
+        UniqueOwners => 8,
+        PendingTransactionLimit => 9,
+        InvalidTransaction => 10,
+        PendingTransactionExist => 11,
+        OwnersOverflow => 12,
+        OwnersLackOff => 13,
+        InvalidLastTransaction => 14,
+        OwnerAlreadyExist => 15,
+        VaultNotDrained => 16,
+        ProgramNotAllowed => 17,
+        RecoveryNotConfigured => 18,
+        InvalidRecoveryDelay => 19,
+        RecoveryInProgress => 20,
+        NoPendingRecovery => 21,
+        RecoveryDelayNotPassed => 22,
+        TransactionBufferOverflow => 23,
+        TransactionBufferMismatch => 24,
+        InvalidLookupAccount => 25,
+        OwnersChanged => 26,
+    }
+}
+
+#[test]
+fn test_error_codes_are_stable() {
+    let mut code = 0;
+    while let Ok(error) = MultisigError::try_from(code) {
+        assert_eq!(expected_code(error), code, "{:?}", error);
+        assert_eq!(ProgramError::from(error), ProgramError::Custom(code));
+        code += 1;
+    }
+
+    assert_eq!(code, 27, "not every error is listed");
+    assert_eq!(
+        MultisigError::try_from(code).unwrap_err(),
+        ProgramError::Custom(code)
+    );
+}
+
+#[test]
+fn test_error_messages() {
+    let error = MultisigError::try_from(7).expect("known code");
+    assert_eq!(error, InvalidThreshold);
+    assert_eq!(
+        error.to_string(),
+        "Threshold must be less than or equal to the number of owners."
+    );
+
+    assert_eq!(
+        <MultisigError as solana_program::decode_error::DecodeError<MultisigError>>::type_of(),
+        "MultisigError"
+    );
+}
@@ -1,11 +1,21 @@
 #![cfg(feature = "test-bpf")]
 
-use solana_program::program_pack::Pack;
+use std::str::FromStr;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::address_lookup_table::{self, AddressLookupTableAccount};
+use solana_program::clock::Clock;
+use solana_program::instruction::Instruction;
+use solana_program::keccak;
 use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::{system_instruction, system_program};
 use solana_program_test::{processor, tokio, ProgramTest};
-use solana_sdk::account::ReadableAccount;
+use solana_sdk::account::{Account, ReadableAccount};
 use solana_sdk::signature::{Keypair, Signer};
-use solana_sdk::transaction::Transaction;
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+
+const PENDING_TRANSACTIONS: u64 = 20;
 
 #[tokio::test]
 async fn test() {
@@ -37,6 +47,7 @@ async fn test() {
                 custodian_3.pubkey(),
             ],
             threshold,
+            multisig::MAX_SIGNERS as u32,
         )],
         Some(&funder.pubkey()),
     );
@@ -68,9 +79,11 @@ async fn test() {
         ]
     );
 
+    let mut transaction_index = 0;
+
     // Add owners
     for _ in 0..7 {
-        let seed = uuid::Uuid::new_v4().as_u128();
+        transaction_index += 1;
 
         let owner = Pubkey::new_unique();
 
@@ -80,7 +93,7 @@ async fn test() {
                 &funder.pubkey(),
                 &custodian_1.pubkey(),
                 &multisig_address,
-                seed,
+                transaction_index,
                 multisig::add_owner(&multisig_address, owner),
             )],
             Some(&funder.pubkey()),
@@ -92,7 +105,8 @@ async fn test() {
             .await
             .expect("process_transaction");
 
-        let transaction_address = multisig::get_transaction_address(seed);
+        let transaction_address =
+            multisig::get_transaction_address(&multisig_address, transaction_index);
 
         let transaction_info = banks_client
             .get_account(transaction_address)
@@ -141,6 +155,7 @@ async fn test() {
 
         let mut transaction = Transaction::new_with_payer(
             &[multisig::execute_transaction(
+                &funder.pubkey(),
                 &multisig_address,
                 &transaction_address,
                 accounts,
@@ -178,12 +193,12 @@ async fn test() {
 
     assert_eq!(multisig_data.owners.len(), multisig::MAX_SIGNERS);
 
-    // Create pending transactions
+    // Create pending transactions, more than the former fixed limit
     let mut pending_transactions = Vec::new();
 
-    for _ in 0..multisig::MAX_TRANSACTIONS - 1 {
+    for _ in 0..PENDING_TRANSACTIONS {
         let owner = Pubkey::new_unique();
-        let seed = uuid::Uuid::new_v4().as_u128();
+        transaction_index += 1;
 
         // Create Transaction instruction
         let mut transaction = Transaction::new_with_payer(
@@ -191,7 +206,7 @@ async fn test() {
                 &funder.pubkey(),
                 &custodian_1.pubkey(),
                 &multisig_address,
-                seed,
+                transaction_index,
                 multisig::add_owner(&multisig_address, owner),
             )],
             Some(&funder.pubkey()),
@@ -203,12 +218,13 @@ async fn test() {
             .await
             .expect("process_transaction");
 
-        let transaction_address = multisig::get_transaction_address(seed);
+        let transaction_address =
+            multisig::get_transaction_address(&multisig_address, transaction_index);
         pending_transactions.push(transaction_address);
     }
 
     // Delete last pending transaction
-    let seed = uuid::Uuid::new_v4().as_u128();
+    transaction_index += 1;
     let pending_transaction = *pending_transactions.last().unwrap();
 
     // Create Transaction instruction
@@ -217,7 +233,7 @@ async fn test() {
             &funder.pubkey(),
             &custodian_1.pubkey(),
             &multisig_address,
-            seed,
+            transaction_index,
             multisig::delete_pending_transaction(&multisig_address, pending_transaction),
         )],
         Some(&funder.pubkey()),
@@ -239,12 +255,13 @@ async fn test() {
     let multisig_data = multisig::Multisig::unpack(multisig_info.data()).expect("multisig unpack");
 
     assert_eq!(
-        multisig_data.pending_transactions.len(),
-        multisig::MAX_TRANSACTIONS
+        multisig_data.pending_transaction_count,
+        PENDING_TRANSACTIONS + 1
     );
 
     // Approve
-    let transaction_address = multisig::get_transaction_address(seed);
+    let transaction_address =
+        multisig::get_transaction_address(&multisig_address, transaction_index);
 
     let mut transaction = Transaction::new_with_payer(
         &[multisig::approve(
@@ -275,6 +292,7 @@ async fn test() {
 
     let mut transaction = Transaction::new_with_payer(
         &[multisig::execute_transaction(
+            &funder.pubkey(),
             &multisig_address,
             &transaction_address,
             accounts,
@@ -299,7 +317,3242 @@ async fn test() {
     let multisig_data = multisig::Multisig::unpack(multisig_info.data()).expect("multisig unpack");
 
     assert_eq!(
-        multisig_data.pending_transactions.len(),
-        multisig::MAX_TRANSACTIONS - 2
+        multisig_data.pending_transaction_count,
+        PENDING_TRANSACTIONS - 1
+    );
+
+    // Check deleted transaction
+    let transaction_info = banks_client
+        .get_account(pending_transaction)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    assert!(transaction_data.is_cancelled);
+
+    // Deleted transaction can not be approved anymore
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::approve(
+            &custodian_2.pubkey(),
+            &multisig_address,
+            &pending_transaction,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_2], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("transaction is deleted");
+
+    // Walk all transactions by index
+    let transaction_addresses =
+        multisig::get_transaction_addresses(&multisig_address, multisig_data.transaction_index);
+
+    assert_eq!(transaction_addresses.len() as u64, transaction_index);
+
+    for (index, transaction_address) in (1..).zip(transaction_addresses) {
+        let transaction_info = banks_client
+            .get_account(transaction_address)
+            .await
+            .expect("get_account")
+            .expect("account");
+
+        let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+            .expect("transaction unpack");
+
+        assert_eq!(transaction_data.index, index);
+        assert_eq!(transaction_data.multisig, multisig_address);
+    }
+}
+
+#[tokio::test]
+async fn test_resize() {
+    let program_test = ProgramTest::new(
+        "multisig",
+        multisig::id(),
+        processor!(multisig::Processor::process),
+    );
+
+    // Start Program Test
+    let mut context = program_test.start_with_context().await;
+
+    let mut banks_client = context.banks_client.clone();
+    let funder = context.payer.insecure_clone();
+    let recent_blockhash = context.last_blockhash;
+
+    // Create Multisig
+    let seed = uuid::Uuid::new_v4().as_u128();
+
+    let threshold = 1;
+    let max_signers = 2;
+
+    let custodian_1 = Keypair::new();
+    let custodian_2 = Keypair::new();
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_multisig(
+            &funder.pubkey(),
+            seed,
+            vec![custodian_1.pubkey(), custodian_2.pubkey()],
+            threshold,
+            max_signers,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let multisig_address = multisig::get_multisig_address(seed);
+
+    let multisig_info = banks_client
+        .get_account(multisig_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    assert_eq!(
+        multisig_info.data().len(),
+        multisig::Multisig::get_len(max_signers, 0)
+    );
+
+    // Adding an owner above capacity fails
+    let add_owner_index = 1;
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_transaction(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            add_owner_index,
+            multisig::add_owner(&multisig_address, Pubkey::new_unique()),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let add_owner_address = multisig::get_transaction_address(&multisig_address, add_owner_index);
+
+    let transaction_info = banks_client
+        .get_account(add_owner_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let add_owner_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &multisig_address,
+            &add_owner_address,
+            add_owner_data.accounts.clone(),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("owners overflow");
+
+    // Resize
+    let max_signers = 25;
+    let resize_index = 2;
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_transaction(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            resize_index,
+            multisig::resize(&multisig_address, &funder.pubkey(), max_signers),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let resize_address = multisig::get_transaction_address(&multisig_address, resize_index);
+
+    let transaction_info = banks_client
+        .get_account(resize_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let resize_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &multisig_address,
+            &resize_address,
+            resize_data.accounts,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let multisig_info = banks_client
+        .get_account(multisig_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    assert_eq!(
+        multisig_info.data().len(),
+        multisig::Multisig::get_len(max_signers, 0)
+    );
+
+    let multisig_data = multisig::Multisig::unpack(multisig_info.data()).expect("multisig unpack");
+
+    assert_eq!(multisig_data.max_signers, max_signers);
+
+    // Retry adding an owner
+    let recent_blockhash = context
+        .get_new_latest_blockhash()
+        .await
+        .expect("get_new_latest_blockhash");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &multisig_address,
+            &add_owner_address,
+            add_owner_data.accounts,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let multisig_info = banks_client
+        .get_account(multisig_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let multisig_data = multisig::Multisig::unpack(multisig_info.data()).expect("multisig unpack");
+
+    assert_eq!(multisig_data.owners.len(), 3);
+}
+
+#[tokio::test]
+async fn test_close_multisig() {
+    let mut program_test = ProgramTest::new(
+        "multisig",
+        multisig::id(),
+        processor!(multisig::Processor::process),
+    );
+
+    let seed = uuid::Uuid::new_v4().as_u128();
+    let multisig_address = multisig::get_multisig_address(seed);
+
+    // Recovery configured for the multisig
+    let recovery_address = multisig::get_recovery_address(&multisig_address);
+
+    let mut recovery_data = vec![0; multisig::Recovery::get_len(1, multisig::MAX_SIGNERS as u32)];
+    multisig::Recovery::pack(
+        multisig::Recovery {
+            is_initialized: true,
+            multisig: multisig_address,
+            guardians: vec![Pubkey::new_unique()],
+            guardian_threshold: 1,
+            delay: multisig::MIN_RECOVERY_DELAY,
+            pending: None,
+        },
+        &mut recovery_data,
+    )
+    .expect("pack");
+
+    let recovery_lamports = Rent::default().minimum_balance(recovery_data.len());
+
+    program_test.add_account(
+        recovery_address,
+        Account {
+            lamports: recovery_lamports,
+            data: recovery_data,
+            owner: multisig::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Token account held by the multisig
+    let token_program = Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
+    let vault = Pubkey::new_unique();
+
+    let token_account = |amount: u64| {
+        let mut data = vec![0; 165];
+        data[32..64].copy_from_slice(multisig_address.as_ref());
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: token_program,
+            executable: false,
+            rent_epoch: 0,
+        }
+    };
+
+    program_test.add_account(vault, token_account(100));
+
+    // Lamports held by the multisig
+    let sol_vault = multisig::get_vault_address(&multisig_address);
+    program_test.add_account(
+        sol_vault,
+        Account {
+            lamports: 1_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    // Start Program Test
+    let mut context = program_test.start_with_context().await;
+
+    let mut banks_client = context.banks_client.clone();
+    let funder = context.payer.insecure_clone();
+    let recent_blockhash = context.last_blockhash;
+
+    // Create Multisig
+    let custodian_1 = Keypair::new();
+    let recipient = Pubkey::new_unique();
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_multisig(
+            &funder.pubkey(),
+            seed,
+            vec![custodian_1.pubkey()],
+            1,
+            multisig::MAX_SIGNERS as u32,
+        )],
+        Some(&funder.pubkey()),
     );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    // Create pending transaction and close proposals
+    let proposals = [
+        multisig::add_owner(&multisig_address, Pubkey::new_unique()),
+        multisig::close_multisig(&multisig_address, &recipient, vec![vault]),
+        multisig::delete_pending_transaction(
+            &multisig_address,
+            multisig::get_transaction_address(&multisig_address, 1),
+        ),
+    ];
+
+    for (index, ix) in (1..).zip(proposals) {
+        let mut transaction = Transaction::new_with_payer(
+            &[multisig::create_transaction(
+                &funder.pubkey(),
+                &custodian_1.pubkey(),
+                &multisig_address,
+                index,
+                ix,
+            )],
+            Some(&funder.pubkey()),
+        );
+        transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process_transaction");
+    }
+
+    let close_address = multisig::get_transaction_address(&multisig_address, 2);
+    let delete_address = multisig::get_transaction_address(&multisig_address, 3);
+
+    let close_info = banks_client
+        .get_account(close_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let close_data =
+        multisig::Transaction::unpack_from_slice(close_info.data()).expect("transaction unpack");
+
+    // Close fails while other transactions are pending
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &multisig_address,
+            &close_address,
+            close_data.accounts.clone(),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("pending transaction exist");
+
+    // Delete the remaining pending transaction
+    let delete_info = banks_client
+        .get_account(delete_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let delete_data =
+        multisig::Transaction::unpack_from_slice(delete_info.data()).expect("transaction unpack");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &multisig_address,
+            &delete_address,
+            delete_data.accounts,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    // Close fails while the vault holds tokens
+    let recent_blockhash = context
+        .get_new_latest_blockhash()
+        .await
+        .expect("get_new_latest_blockhash");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &multisig_address,
+            &close_address,
+            close_data.accounts.clone(),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("vault not drained");
+
+    // Token vault drained, the lamports are still there
+    context.set_account(&vault, &token_account(0).into());
+
+    let recent_blockhash = context
+        .get_new_latest_blockhash()
+        .await
+        .expect("get_new_latest_blockhash");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &multisig_address,
+            &close_address,
+            close_data.accounts.clone(),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("vault not drained");
+
+    // Close
+    context.set_account(&sol_vault, &Account::default().into());
+
+    let multisig_info = banks_client
+        .get_account(multisig_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let recent_blockhash = context
+        .get_new_latest_blockhash()
+        .await
+        .expect("get_new_latest_blockhash");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &multisig_address,
+            &close_address,
+            close_data.accounts,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    // Multisig account is kept empty with the rent of an empty account
+    let tombstone_lamports = Rent::default().minimum_balance(0);
+
+    let closed_multisig_info = banks_client
+        .get_account(multisig_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    assert!(closed_multisig_info.data.is_empty());
+    assert_eq!(closed_multisig_info.lamports, tombstone_lamports);
+
+    let closed_recovery_info = banks_client
+        .get_account(recovery_address)
+        .await
+        .expect("get_account");
+
+    assert!(closed_recovery_info.is_none());
+
+    let recipient_balance = banks_client
+        .get_balance(recipient)
+        .await
+        .expect("get_balance");
+
+    assert_eq!(
+        recipient_balance,
+        multisig_info.lamports - tombstone_lamports + recovery_lamports
+    );
+
+    // Transaction accounts of the closed multisig are left behind, so it can
+    // not be created again to start over at their indices
+    let recent_blockhash = context
+        .get_new_latest_blockhash()
+        .await
+        .expect("get_new_latest_blockhash");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_multisig(
+            &funder.pubkey(),
+            seed,
+            vec![custodian_1.pubkey()],
+            1,
+            multisig::MAX_SIGNERS as u32,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("multisig closed");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_transaction(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            1,
+            multisig::add_owner(&multisig_address, Pubkey::new_unique()),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("multisig closed");
+}
+
+#[tokio::test]
+async fn test_vault() {
+    let mut program_test = ProgramTest::new(
+        "multisig",
+        multisig::id(),
+        processor!(multisig::Processor::process),
+    );
+
+    let seed = uuid::Uuid::new_v4().as_u128();
+
+    let multisig_address = multisig::get_multisig_address(seed);
+
+    // Lamports held by the multisig
+    let vault = multisig::get_vault_address(&multisig_address);
+    program_test.add_account(
+        vault,
+        Account {
+            lamports: 2_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    // Start Program Test
+    let (banks_client, funder, _) = program_test.start().await;
+
+    let mut client = multisig::MultisigClient::new(banks_client, funder);
+
+    let custodian = Keypair::new();
+
+    client
+        .create_multisig(
+            seed,
+            vec![custodian.pubkey()],
+            1,
+            multisig::MAX_SIGNERS as u32,
+        )
+        .await
+        .expect("create_multisig");
+
+    let recipient = Pubkey::new_unique();
+
+    // Transfer signed by the vault
+    let transaction_address = client
+        .propose(
+            &custodian,
+            &multisig_address,
+            system_instruction::transfer(&vault, &recipient, 1_000_000_000),
+        )
+        .await
+        .expect("propose");
+
+    // Vault signature is provided by the program, as for the multisig
+    let transaction_data = client
+        .get_transaction(&transaction_address)
+        .await
+        .expect("get_transaction");
+
+    let payer = client.payer().insecure_clone();
+    let recent_blockhash = client
+        .backend()
+        .get_latest_blockhash()
+        .await
+        .expect("get_latest_blockhash");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::check_transaction(
+            &payer.pubkey(),
+            &multisig_address,
+            &transaction_address,
+            multisig::get_execute_accounts(&transaction_data),
+        )],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer], recent_blockhash);
+
+    let return_data = client
+        .backend()
+        .simulate_transaction(transaction)
+        .await
+        .expect("simulate_transaction")
+        .simulation_details
+        .expect("simulation_details")
+        .return_data
+        .expect("return_data");
+
+    let status = multisig::decode_transaction_status(&return_data.data).expect("status decode");
+    assert!(status.blocking_reasons.is_empty());
+
+    client
+        .execute(&multisig_address, &transaction_address)
+        .await
+        .expect("execute");
+
+    let recipient_balance = client
+        .backend()
+        .get_balance(recipient)
+        .await
+        .expect("get_balance");
+    assert_eq!(recipient_balance, 1_000_000_000);
+
+    let vault_balance = client
+        .backend()
+        .get_balance(vault)
+        .await
+        .expect("get_balance");
+    assert_eq!(vault_balance, 1_000_000_000);
+}
+
+#[tokio::test]
+async fn test_approve_with_signatures() {
+    let program_test = ProgramTest::new(
+        "multisig",
+        multisig::id(),
+        processor!(multisig::Processor::process),
+    );
+
+    // Start Program Test
+    let (mut banks_client, funder, recent_blockhash) = program_test.start().await;
+
+    // Create Multisig
+    let seed = uuid::Uuid::new_v4().as_u128();
+
+    let custodian_1 = Keypair::new();
+    let custodian_2 = Keypair::new();
+    let custodian_3 = Keypair::new();
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_multisig(
+            &funder.pubkey(),
+            seed,
+            vec![
+                custodian_1.pubkey(),
+                custodian_2.pubkey(),
+                custodian_3.pubkey(),
+            ],
+            3,
+            multisig::MAX_SIGNERS as u32,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let multisig_address = multisig::get_multisig_address(seed);
+
+    // Create Transaction instruction
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_transaction(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            1,
+            multisig::add_owner(&multisig_address, Pubkey::new_unique()),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let transaction_address = multisig::get_transaction_address(&multisig_address, 1);
+
+    let transaction_info = banks_client
+        .get_account(transaction_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let proposal = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    // Sign approval message offline
+    let message = multisig::get_approval_message(
+        &multisig_address,
+        &transaction_address,
+        0,
+        &proposal.get_instruction_hash(),
+    );
+
+    let signatures = [&custodian_2, &custodian_3, &Keypair::new()]
+        .into_iter()
+        .map(|keypair| (keypair.pubkey(), keypair.sign_message(&message).into()))
+        .collect::<Vec<_>>();
+
+    // Approve
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            multisig::ed25519_approval(
+                &signatures,
+                &multisig_address,
+                &transaction_address,
+                &proposal,
+            ),
+            multisig::approve_with_signatures(&multisig_address, &transaction_address),
+        ],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let transaction_info = banks_client
+        .get_account(transaction_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    assert_eq!(transaction_data.signers, vec![true, true, true]);
+
+    // Forged signatures are rejected
+    let signatures = vec![(custodian_2.pubkey(), [1; multisig::ED25519_SIGNATURE_SIZE])];
+
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            multisig::ed25519_approval(
+                &signatures,
+                &multisig_address,
+                &transaction_address,
+                &proposal,
+            ),
+            multisig::approve_with_signatures(&multisig_address, &transaction_address),
+        ],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("forged signature");
+
+    // Signatures over another message are rejected
+    let message = multisig::get_approval_message(
+        &multisig_address,
+        &Pubkey::new_unique(),
+        0,
+        &proposal.get_instruction_hash(),
+    );
+    let signatures = vec![(
+        custodian_2.pubkey(),
+        custodian_2.sign_message(&message).into(),
+    )];
+
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            multisig::ed25519_approval(
+                &signatures,
+                &multisig_address,
+                &transaction_address,
+                &proposal,
+            ),
+            multisig::approve_with_signatures(&multisig_address, &transaction_address),
+        ],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("invalid signature");
+
+    // Signatures over another instruction are rejected
+    let message =
+        multisig::get_approval_message(&multisig_address, &transaction_address, 0, &[0; 32]);
+    let signatures = vec![(
+        custodian_2.pubkey(),
+        custodian_2.sign_message(&message).into(),
+    )];
+
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            multisig::ed25519_approval(
+                &signatures,
+                &multisig_address,
+                &transaction_address,
+                &proposal,
+            ),
+            multisig::approve_with_signatures(&multisig_address, &transaction_address),
+        ],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("signature over another instruction");
+
+    // Execute
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &multisig_address,
+            &transaction_address,
+            transaction_data.accounts,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+}
+
+#[tokio::test]
+async fn test_eth_owner() {
+    let program_test = ProgramTest::new(
+        "multisig",
+        multisig::id(),
+        processor!(multisig::Processor::process),
+    );
+
+    // Start Program Test
+    let (mut banks_client, funder, recent_blockhash) = program_test.start().await;
+
+    // Ethereum custodian
+    let secret_key = libsecp256k1::SecretKey::parse(&[7; 32]).expect("secret key");
+    let public_key = libsecp256k1::PublicKey::from_secret_key(&secret_key);
+
+    let mut eth_address = [0; multisig::ETH_ADDRESS_SIZE];
+    eth_address.copy_from_slice(&keccak::hash(&public_key.serialize()[1..]).0[12..]);
+
+    // Create Multisig
+    let seed = uuid::Uuid::new_v4().as_u128();
+
+    let custodian_1 = Keypair::new();
+    let custodian_2 = multisig::get_eth_owner(&eth_address);
+
+    assert_eq!(multisig::get_eth_address(&custodian_2), Some(eth_address));
+    assert_eq!(multisig::get_eth_address(&custodian_1.pubkey()), None);
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_multisig(
+            &funder.pubkey(),
+            seed,
+            vec![custodian_1.pubkey(), custodian_2],
+            2,
+            multisig::MAX_SIGNERS as u32,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let multisig_address = multisig::get_multisig_address(seed);
+
+    // Create Transaction instruction
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_transaction(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            1,
+            multisig::add_owner(&multisig_address, Pubkey::new_unique()),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let transaction_address = multisig::get_transaction_address(&multisig_address, 1);
+
+    let transaction_info = banks_client
+        .get_account(transaction_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let proposal = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    // Sign EIP-191 approval message offline
+    let message = multisig::get_eth_approval_message(
+        &multisig_address,
+        &transaction_address,
+        0,
+        &proposal.get_instruction_hash(),
+    );
+
+    let (signature, recovery_id) = libsecp256k1::sign(
+        &libsecp256k1::Message::parse(&keccak::hash(&message).0),
+        &secret_key,
+    );
+    let signature = signature.serialize();
+
+    let eth_signature = multisig::EthSignature {
+        address: eth_address,
+        r: signature[..32].try_into().unwrap(),
+        s: signature[32..].try_into().unwrap(),
+        v: recovery_id.serialize() + 27,
+    };
+
+    // Approve
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            multisig::secp256k1_approval(
+                &[eth_signature],
+                &multisig_address,
+                &transaction_address,
+                &proposal,
+                0,
+            ),
+            multisig::approve_with_signatures(&multisig_address, &transaction_address),
+        ],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let transaction_info = banks_client
+        .get_account(transaction_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    assert_eq!(transaction_data.signers, vec![true, true]);
+
+    // Execute
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &multisig_address,
+            &transaction_address,
+            transaction_data.accounts,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+}
+
+#[tokio::test]
+async fn test_nested_multisig() {
+    let program_test = ProgramTest::new(
+        "multisig",
+        multisig::id(),
+        processor!(multisig::Processor::process),
+    );
+
+    // Start Program Test
+    let (mut banks_client, funder, recent_blockhash) = program_test.start().await;
+
+    let custodian_1 = Keypair::new();
+    let custodian_2 = Keypair::new();
+
+    // Create parent Multisig
+    let parent_seed = uuid::Uuid::new_v4().as_u128();
+    let parent_address = multisig::get_multisig_address(parent_seed);
+
+    // Create child Multisig owned by the parent one
+    let child_seed = uuid::Uuid::new_v4().as_u128();
+    let child_address = multisig::get_multisig_address(child_seed);
+
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            multisig::create_multisig(
+                &funder.pubkey(),
+                parent_seed,
+                vec![custodian_1.pubkey()],
+                1,
+                multisig::MAX_SIGNERS as u32,
+            ),
+            multisig::create_multisig(
+                &funder.pubkey(),
+                child_seed,
+                vec![parent_address, custodian_2.pubkey()],
+                2,
+                multisig::MAX_SIGNERS as u32,
+            ),
+        ],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    // Parent proposes a child transaction
+    let new_owner = Pubkey::new_unique();
+
+    let child_transaction_address = multisig::get_transaction_address(&child_address, 1);
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_transaction(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &parent_address,
+            1,
+            multisig::create_transaction(
+                &funder.pubkey(),
+                &parent_address,
+                &child_address,
+                1,
+                multisig::add_owner(&child_address, new_owner),
+            ),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let parent_transaction_address = multisig::get_transaction_address(&parent_address, 1);
+
+    let transaction_info = banks_client
+        .get_account(parent_transaction_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    // Execute on behalf of the parent, the funder pays for the child transaction
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &parent_address,
+            &parent_transaction_address,
+            transaction_data.accounts,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let transaction_info = banks_client
+        .get_account(child_transaction_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    assert_eq!(transaction_data.signers, vec![true, false]);
+
+    // Child custodian approves and executes
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            multisig::approve(
+                &custodian_2.pubkey(),
+                &child_address,
+                &child_transaction_address,
+            ),
+            multisig::execute_transaction(
+                &funder.pubkey(),
+                &child_address,
+                &child_transaction_address,
+                transaction_data.accounts,
+            ),
+        ],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_2], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    // Child custodian proposes, parent approves
+    let other_owner = Pubkey::new_unique();
+
+    let child_transaction_address = multisig::get_transaction_address(&child_address, 2);
+
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            multisig::create_transaction(
+                &funder.pubkey(),
+                &custodian_2.pubkey(),
+                &child_address,
+                2,
+                multisig::add_owner(&child_address, other_owner),
+            ),
+            multisig::create_transaction(
+                &funder.pubkey(),
+                &custodian_1.pubkey(),
+                &parent_address,
+                2,
+                multisig::approve(&parent_address, &child_address, &child_transaction_address),
+            ),
+        ],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1, &custodian_2], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let parent_transaction_address = multisig::get_transaction_address(&parent_address, 2);
+
+    let transaction_info = banks_client
+        .get_account(parent_transaction_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &parent_address,
+            &parent_transaction_address,
+            transaction_data.accounts,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let transaction_info = banks_client
+        .get_account(child_transaction_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    assert_eq!(transaction_data.signers, vec![true, true, false]);
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &child_address,
+            &child_transaction_address,
+            transaction_data.accounts,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let multisig_info = banks_client
+        .get_account(child_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let multisig_data = multisig::Multisig::unpack(multisig_info.data()).expect("multisig unpack");
+
+    assert_eq!(
+        multisig_data.owners,
+        vec![parent_address, custodian_2.pubkey(), new_owner, other_owner]
+    );
+}
+
+#[tokio::test]
+async fn test_allowed_programs() {
+    let program_test = ProgramTest::new(
+        "multisig",
+        multisig::id(),
+        processor!(multisig::Processor::process),
+    );
+
+    // Start Program Test
+    let (mut banks_client, funder, recent_blockhash) = program_test.start().await;
+
+    // Create Multisig
+    let seed = uuid::Uuid::new_v4().as_u128();
+    let max_signers = multisig::MAX_SIGNERS as u32;
+
+    let custodian_1 = Keypair::new();
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_multisig(
+            &funder.pubkey(),
+            seed,
+            vec![custodian_1.pubkey()],
+            1,
+            max_signers,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let multisig_address = multisig::get_multisig_address(seed);
+
+    // Allow System Program
+    let add_allowed_program_index = 1;
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_transaction(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            add_allowed_program_index,
+            multisig::add_allowed_program(
+                &multisig_address,
+                &funder.pubkey(),
+                system_program::id(),
+            ),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let add_allowed_program_address =
+        multisig::get_transaction_address(&multisig_address, add_allowed_program_index);
+
+    let transaction_info = banks_client
+        .get_account(add_allowed_program_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &multisig_address,
+            &add_allowed_program_address,
+            transaction_data.accounts,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let multisig_info = banks_client
+        .get_account(multisig_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    assert_eq!(
+        multisig_info.data().len(),
+        multisig::Multisig::get_len(max_signers, 1)
+    );
+
+    let multisig_data = multisig::Multisig::unpack(multisig_info.data()).expect("multisig unpack");
+
+    assert_eq!(multisig_data.allowed_programs, vec![system_program::id()]);
+
+    // Proposals to other programs are rejected
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_transaction(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            2,
+            Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![]),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("program not allowed");
+
+    // Proposals to allowed programs and to the multisig itself pass
+    let proposals = [
+        system_instruction::transfer(&multisig_address, &Pubkey::new_unique(), 1),
+        multisig::remove_allowed_program(&multisig_address, system_program::id()),
+    ];
+
+    for (index, ix) in (2..).zip(proposals) {
+        let mut transaction = Transaction::new_with_payer(
+            &[multisig::create_transaction(
+                &funder.pubkey(),
+                &custodian_1.pubkey(),
+                &multisig_address,
+                index,
+                ix,
+            )],
+            Some(&funder.pubkey()),
+        );
+        transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process_transaction");
+    }
+
+    // Remove System Program
+    let remove_allowed_program_address = multisig::get_transaction_address(&multisig_address, 3);
+
+    let transaction_info = banks_client
+        .get_account(remove_allowed_program_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &multisig_address,
+            &remove_allowed_program_address,
+            transaction_data.accounts,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let multisig_info = banks_client
+        .get_account(multisig_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let multisig_data = multisig::Multisig::unpack(multisig_info.data()).expect("multisig unpack");
+
+    assert!(multisig_data.allowed_programs.is_empty());
+}
+
+#[tokio::test]
+async fn test_action_thresholds() {
+    let program_test = ProgramTest::new(
+        "multisig",
+        multisig::id(),
+        processor!(multisig::Processor::process),
+    );
+
+    // Start Program Test
+    let (mut banks_client, funder, recent_blockhash) = program_test.start().await;
+
+    // Create Multisig
+    let seed = uuid::Uuid::new_v4().as_u128();
+
+    let custodian_1 = Keypair::new();
+    let custodian_2 = Keypair::new();
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_multisig(
+            &funder.pubkey(),
+            seed,
+            vec![custodian_1.pubkey(), custodian_2.pubkey()],
+            1,
+            multisig::MAX_SIGNERS as u32,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let multisig_address = multisig::get_multisig_address(seed);
+
+    // Create proposals, each approved by the proposer only
+    let proposals = [
+        multisig::update_action_thresholds(&multisig_address, Some(2), None),
+        multisig::add_owner(&multisig_address, Pubkey::new_unique()),
+        multisig::delete_pending_transaction(
+            &multisig_address,
+            multisig::get_transaction_address(&multisig_address, 4),
+        ),
+        multisig::add_owner(&multisig_address, Pubkey::new_unique()),
+    ];
+
+    for (index, ix) in (1..).zip(proposals) {
+        let mut transaction = Transaction::new_with_payer(
+            &[multisig::create_transaction(
+                &funder.pubkey(),
+                &custodian_1.pubkey(),
+                &multisig_address,
+                index,
+                ix,
+            )],
+            Some(&funder.pubkey()),
+        );
+        transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process_transaction");
+    }
+
+    let mut transactions = Vec::new();
+    for transaction_address in multisig::get_transaction_addresses(&multisig_address, 3) {
+        let transaction_info = banks_client
+            .get_account(transaction_address)
+            .await
+            .expect("get_account")
+            .expect("account");
+
+        let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+            .expect("transaction unpack");
+
+        transactions.push((transaction_address, transaction_data));
+    }
+
+    let execute = |(transaction_address, transaction_data): &(Pubkey, multisig::Transaction)| {
+        let mut transaction = Transaction::new_with_payer(
+            &[multisig::execute_transaction(
+                &funder.pubkey(),
+                &multisig_address,
+                transaction_address,
+                transaction_data.accounts.clone(),
+            )],
+            Some(&funder.pubkey()),
+        );
+        transaction.sign(&[&funder], recent_blockhash);
+        transaction
+    };
+
+    // Admin threshold is not set yet
+    banks_client
+        .process_transaction(execute(&transactions[0]))
+        .await
+        .expect("process_transaction");
+
+    let multisig_info = banks_client
+        .get_account(multisig_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let multisig_data = multisig::Multisig::unpack(multisig_info.data()).expect("multisig unpack");
+
+    assert_eq!(multisig_data.admin_threshold, Some(2));
+    assert_eq!(multisig_data.upgrade_threshold, None);
+
+    // Configuration changes require the admin threshold
+    banks_client
+        .process_transaction(execute(&transactions[1]))
+        .await
+        .expect_err("not enough signers");
+
+    // Ordinary transactions still require the default one
+    banks_client
+        .process_transaction(execute(&transactions[2]))
+        .await
+        .expect("process_transaction");
+
+    // Approve by the second custodian
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::approve(
+            &custodian_2.pubkey(),
+            &multisig_address,
+            &transactions[1].0,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_2], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let recent_blockhash = banks_client
+        .get_latest_blockhash()
+        .await
+        .expect("get_latest_blockhash");
+
+    let mut transaction = execute(&transactions[1]);
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let multisig_info = banks_client
+        .get_account(multisig_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let multisig_data = multisig::Multisig::unpack(multisig_info.data()).expect("multisig unpack");
+
+    assert_eq!(multisig_data.owners.len(), 3);
+}
+
+#[tokio::test]
+async fn test_recovery() {
+    let program_test = ProgramTest::new(
+        "multisig",
+        multisig::id(),
+        processor!(multisig::Processor::process),
+    );
+
+    // Start Program Test
+    let mut context = program_test.start_with_context().await;
+
+    let mut banks_client = context.banks_client.clone();
+    let funder = context.payer.insecure_clone();
+    let recent_blockhash = context.last_blockhash;
+
+    // Create Multisig
+    let seed = uuid::Uuid::new_v4().as_u128();
+
+    let custodian_1 = Keypair::new();
+    let custodian_2 = Keypair::new();
+
+    let guardian_1 = Keypair::new();
+    let guardian_2 = Keypair::new();
+    let guardian_3 = Keypair::new();
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_multisig(
+            &funder.pubkey(),
+            seed,
+            vec![custodian_1.pubkey(), custodian_2.pubkey()],
+            1,
+            multisig::MAX_SIGNERS as u32,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let multisig_address = multisig::get_multisig_address(seed);
+    let recovery_address = multisig::get_recovery_address(&multisig_address);
+
+    // Configure Recovery
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_transaction(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            1,
+            multisig::configure_recovery(
+                &multisig_address,
+                &funder.pubkey(),
+                vec![
+                    guardian_1.pubkey(),
+                    guardian_2.pubkey(),
+                    guardian_3.pubkey(),
+                ],
+                2,
+                multisig::MIN_RECOVERY_DELAY,
+            ),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let configure_address = multisig::get_transaction_address(&multisig_address, 1);
+
+    let transaction_info = banks_client
+        .get_account(configure_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &multisig_address,
+            &configure_address,
+            transaction_data.accounts,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let recovery_info = banks_client
+        .get_account(recovery_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let recovery_data = multisig::Recovery::unpack(recovery_info.data()).expect("recovery unpack");
+
+    assert_eq!(recovery_data.multisig, multisig_address);
+    assert_eq!(recovery_data.guardians.len(), 3);
+    assert_eq!(recovery_data.guardian_threshold, 2);
+    assert!(recovery_data.pending.is_none());
+
+    // Resize reallocates the recovery account along with the multisig
+    let max_signers = multisig::MAX_SIGNERS as u32 + 2;
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_transaction(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            2,
+            multisig::resize(&multisig_address, &funder.pubkey(), max_signers),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let resize_address = multisig::get_transaction_address(&multisig_address, 2);
+
+    let transaction_info = banks_client
+        .get_account(resize_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &multisig_address,
+            &resize_address,
+            transaction_data.accounts,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let recovery_info = banks_client
+        .get_account(recovery_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    assert_eq!(
+        recovery_info.data().len(),
+        multisig::Recovery::get_len(3, max_signers)
+    );
+
+    // Transaction approved by the owners being replaced
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_transaction(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            3,
+            multisig::update_threshold(&multisig_address, 1),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let stale_address = multisig::get_transaction_address(&multisig_address, 3);
+
+    let new_owners = (0..max_signers)
+        .map(|_| Pubkey::new_unique())
+        .collect::<Vec<_>>();
+
+    // Start fails without enough guardians
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::start_recovery(
+            &multisig_address,
+            &[guardian_1.pubkey()],
+            new_owners.clone(),
+            2,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &guardian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("not enough guardians");
+
+    // Start and veto
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            multisig::start_recovery(
+                &multisig_address,
+                &[guardian_1.pubkey(), guardian_2.pubkey()],
+                new_owners.clone(),
+                2,
+            ),
+            multisig::veto_recovery(&multisig_address, &custodian_2.pubkey()),
+        ],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(
+        &[&funder, &guardian_1, &guardian_2, &custodian_2],
+        recent_blockhash,
+    );
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let recovery_info = banks_client
+        .get_account(recovery_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let recovery_data = multisig::Recovery::unpack(recovery_info.data()).expect("recovery unpack");
+
+    assert!(recovery_data.pending.is_none());
+
+    // Start again
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::start_recovery(
+            &multisig_address,
+            &[guardian_2.pubkey(), guardian_3.pubkey()],
+            new_owners.clone(),
+            2,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &guardian_2, &guardian_3], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    // Finalize fails before the delay has passed
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::finalize_recovery(&multisig_address)],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("delay has not passed");
+
+    // Finalize after the delay
+    let mut clock: Clock = banks_client.get_sysvar().await.expect("get_sysvar");
+    clock.unix_timestamp += multisig::MIN_RECOVERY_DELAY;
+    context.set_sysvar(&clock);
+
+    let recent_blockhash = context
+        .get_new_latest_blockhash()
+        .await
+        .expect("get_new_latest_blockhash");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::finalize_recovery(&multisig_address)],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let multisig_info = banks_client
+        .get_account(multisig_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let multisig_data = multisig::Multisig::unpack(multisig_info.data()).expect("multisig unpack");
+
+    assert_eq!(multisig_data.owners, new_owners);
+    assert_eq!(multisig_data.threshold, 2);
+    assert_eq!(multisig_data.owners_epoch, 1);
+
+    // Approvals of the previous owners no longer count
+    let transaction_info = banks_client
+        .get_account(stale_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &multisig_address,
+            &stale_address,
+            transaction_data.accounts,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("owners changed");
+
+    let recovery_info = banks_client
+        .get_account(recovery_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let recovery_data = multisig::Recovery::unpack(recovery_info.data()).expect("recovery unpack");
+
+    assert!(recovery_data.pending.is_none());
+}
+
+#[tokio::test]
+async fn test_add_owner_keeps_proposals() {
+    let program_test = ProgramTest::new(
+        "multisig",
+        multisig::id(),
+        processor!(multisig::Processor::process),
+    );
+
+    // Start Program Test
+    let (banks_client, funder, _) = program_test.start().await;
+
+    let mut client = multisig::MultisigClient::new(banks_client, funder);
+
+    let seed = uuid::Uuid::new_v4().as_u128();
+
+    let custodian_1 = Keypair::new();
+    let custodian_2 = Keypair::new();
+    let custodian_3 = Keypair::new();
+
+    client
+        .create_multisig(
+            seed,
+            vec![custodian_1.pubkey(), custodian_2.pubkey()],
+            2,
+            multisig::MAX_SIGNERS as u32,
+        )
+        .await
+        .expect("create_multisig");
+
+    let multisig_address = multisig::get_multisig_address(seed);
+
+    let add_owner_address = client
+        .propose(
+            &custodian_1,
+            &multisig_address,
+            multisig::add_owner(&multisig_address, custodian_3.pubkey()),
+        )
+        .await
+        .expect("propose");
+
+    let pending_address = client
+        .propose(
+            &custodian_1,
+            &multisig_address,
+            multisig::update_threshold(&multisig_address, 3),
+        )
+        .await
+        .expect("propose");
+
+    // Add owner
+    client
+        .approve(&custodian_2, &multisig_address, &add_owner_address)
+        .await
+        .expect("approve");
+
+    client
+        .execute(&multisig_address, &add_owner_address)
+        .await
+        .expect("execute");
+
+    let multisig_data = client
+        .get_multisig(&multisig_address)
+        .await
+        .expect("get_multisig");
+    assert_eq!(multisig_data.owners.len(), 3);
+    assert_eq!(multisig_data.owners_epoch, 0);
+
+    // Added owner has no approval slot in the pending transaction
+    let pending_data = client
+        .get_transaction(&pending_address)
+        .await
+        .expect("get_transaction");
+    assert_eq!(
+        pending_data.get_status(&multisig_data),
+        multisig::ProposalStatus::Active
+    );
+    assert_eq!(
+        pending_data.get_missing_owners(&multisig_data),
+        vec![custodian_2.pubkey()]
+    );
+
+    client
+        .approve(&custodian_3, &multisig_address, &pending_address)
+        .await
+        .expect_err("owner added after the proposal");
+
+    // Approvals collected before the owner was added stay valid
+    client
+        .approve(&custodian_2, &multisig_address, &pending_address)
+        .await
+        .expect("approve");
+
+    client
+        .execute(&multisig_address, &pending_address)
+        .await
+        .expect("execute");
+
+    let multisig_data = client
+        .get_multisig(&multisig_address)
+        .await
+        .expect("get_multisig");
+    assert_eq!(multisig_data.threshold, 3);
+}
+
+#[tokio::test]
+async fn test_check_transaction() {
+    let program_test = ProgramTest::new(
+        "multisig",
+        multisig::id(),
+        processor!(multisig::Processor::process),
+    );
+
+    // Start Program Test
+    let mut context = program_test.start_with_context().await;
+
+    let mut banks_client = context.banks_client.clone();
+    let funder = context.payer.insecure_clone();
+    let recent_blockhash = context.last_blockhash;
+
+    // Create Multisig
+    let seed = uuid::Uuid::new_v4().as_u128();
+
+    let custodian_1 = Keypair::new();
+    let custodian_2 = Keypair::new();
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_multisig(
+            &funder.pubkey(),
+            seed,
+            vec![custodian_1.pubkey(), custodian_2.pubkey()],
+            2,
+            multisig::MAX_SIGNERS as u32,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let multisig_address = multisig::get_multisig_address(seed);
+
+    // Create Transaction
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_transaction(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            1,
+            multisig::add_owner(&multisig_address, Pubkey::new_unique()),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let transaction_address = multisig::get_transaction_address(&multisig_address, 1);
+
+    let transaction_info = banks_client
+        .get_account(transaction_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    let check = |accounts: Vec<multisig::TransactionAccount>, recent_blockhash| {
+        let mut transaction = Transaction::new_with_payer(
+            &[multisig::check_transaction(
+                &funder.pubkey(),
+                &multisig_address,
+                &transaction_address,
+                accounts,
+            )],
+            Some(&funder.pubkey()),
+        );
+        transaction.sign(&[&funder], recent_blockhash);
+        transaction
+    };
+
+    let simulator = banks_client.clone();
+    let simulate = move |transaction| {
+        let mut banks_client = simulator.clone();
+        async move {
+            let result = banks_client
+                .simulate_transaction(transaction)
+                .await
+                .expect("simulate_transaction");
+
+            let return_data = result
+                .simulation_details
+                .expect("simulation_details")
+                .return_data
+                .expect("return_data");
+
+            assert_eq!(return_data.program_id, multisig::id());
+
+            multisig::decode_transaction_status(&return_data.data).expect("status decode")
+        }
+    };
+
+    // Not enough signers
+    let status = simulate(check(transaction_data.accounts.clone(), recent_blockhash)).await;
+
+    assert_eq!(status.approvals, 1);
+    assert_eq!(status.threshold, 2);
+    assert_eq!(status.missing_owners, vec![custodian_2.pubkey()]);
+    assert_eq!(
+        status.blocking_reasons,
+        vec![multisig::BlockingReason::NotEnoughSigners]
+    );
+
+    // Approve
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::approve(
+            &custodian_2.pubkey(),
+            &multisig_address,
+            &transaction_address,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_2], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    // Missing accounts
+    let status = simulate(check(vec![], recent_blockhash)).await;
+
+    assert_eq!(
+        status.blocking_reasons,
+        vec![multisig::BlockingReason::MissingAccount]
+    );
+
+    // Executable
+    let status = simulate(check(transaction_data.accounts.clone(), recent_blockhash)).await;
+
+    assert_eq!(status.approvals, 2);
+    assert!(status.missing_owners.is_empty());
+    assert!(status.blocking_reasons.is_empty());
+    assert_eq!(status.timelock_remaining, None);
+
+    // Execute
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &multisig_address,
+            &transaction_address,
+            transaction_data.accounts.clone(),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let recent_blockhash = context
+        .get_new_latest_blockhash()
+        .await
+        .expect("get_new_latest_blockhash");
+
+    let status = simulate(check(transaction_data.accounts, recent_blockhash)).await;
+
+    assert_eq!(
+        status.blocking_reasons,
+        vec![multisig::BlockingReason::AlreadyExecuted]
+    );
+}
+
+#[tokio::test]
+async fn test_return_data() {
+    let program_test = ProgramTest::new(
+        "multisig",
+        multisig::id(),
+        processor!(multisig::Processor::process),
+    );
+
+    // Start Program Test
+    let (mut banks_client, funder, recent_blockhash) = program_test.start().await;
+
+    // Create Multisig
+    let seed = uuid::Uuid::new_v4().as_u128();
+
+    let custodian_1 = Keypair::new();
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_multisig(
+            &funder.pubkey(),
+            seed,
+            vec![custodian_1.pubkey()],
+            1,
+            multisig::MAX_SIGNERS as u32,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let multisig_address = multisig::get_multisig_address(seed);
+
+    // Propose an owner and a check of that proposal, which returns data
+    let add_owner_address = multisig::get_transaction_address(&multisig_address, 1);
+    let check_address = multisig::get_transaction_address(&multisig_address, 2);
+
+    let add_owner_ix = multisig::add_owner(&multisig_address, Pubkey::new_unique());
+    let add_owner_accounts = add_owner_ix
+        .accounts
+        .iter()
+        .map(|acc| multisig::TransactionAccount {
+            pubkey: acc.pubkey,
+            is_signer: acc.is_signer,
+            is_writable: acc.is_writable,
+        })
+        .collect();
+
+    let proposals = [
+        add_owner_ix,
+        multisig::check_transaction(
+            &multisig_address,
+            &multisig_address,
+            &add_owner_address,
+            add_owner_accounts,
+        ),
+    ];
+
+    for (index, ix) in (1..).zip(proposals) {
+        let mut transaction = Transaction::new_with_payer(
+            &[multisig::create_transaction(
+                &funder.pubkey(),
+                &custodian_1.pubkey(),
+                &multisig_address,
+                index,
+                ix,
+            )],
+            Some(&funder.pubkey()),
+        );
+        transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process_transaction");
+    }
+
+    let transaction_info = banks_client
+        .get_account(check_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    assert_eq!(transaction_data.executor, Pubkey::default());
+    assert!(transaction_data.return_data.is_empty());
+
+    // Execute
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &multisig_address,
+            &check_address,
+            transaction_data.accounts,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let transaction_info = banks_client
+        .get_account(check_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    let slot = banks_client.get_root_slot().await.expect("get_root_slot");
+
+    assert!(transaction_data.did_execute);
+    assert_eq!(transaction_data.executor, funder.pubkey());
+    assert!(transaction_data.executed_slot >= slot);
+
+    let status =
+        multisig::decode_transaction_status(&transaction_data.return_data).expect("status decode");
+
+    assert_eq!(status.approvals, 1);
+    assert!(status.blocking_reasons.is_empty());
+}
+
+#[tokio::test]
+async fn test_update_transaction() {
+    let program_test = ProgramTest::new(
+        "multisig",
+        multisig::id(),
+        processor!(multisig::Processor::process),
+    );
+
+    // Start Program Test
+    let (mut banks_client, funder, recent_blockhash) = program_test.start().await;
+
+    // Create Multisig
+    let seed = uuid::Uuid::new_v4().as_u128();
+
+    let custodian_1 = Keypair::new();
+    let custodian_2 = Keypair::new();
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_multisig(
+            &funder.pubkey(),
+            seed,
+            vec![custodian_1.pubkey(), custodian_2.pubkey()],
+            2,
+            multisig::MAX_SIGNERS as u32,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let multisig_address = multisig::get_multisig_address(seed);
+
+    // Create Transaction
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_transaction(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            1,
+            multisig::add_owner(&multisig_address, Pubkey::new_unique()),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let transaction_address = multisig::get_transaction_address(&multisig_address, 1);
+
+    // Only the proposer can update
+    let update_ix =
+        multisig::add_allowed_program(&multisig_address, &funder.pubkey(), Pubkey::new_unique());
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::update_transaction(
+            &funder.pubkey(),
+            &custodian_2.pubkey(),
+            &multisig_address,
+            &transaction_address,
+            update_ix.clone(),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_2], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("not a proposer");
+
+    // Update to a larger instruction
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::update_transaction(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            &transaction_address,
+            update_ix.clone(),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let transaction_info = banks_client
+        .get_account(transaction_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    assert_eq!(transaction_data.proposer, custodian_1.pubkey());
+    assert_eq!(transaction_data.revision, 1);
+    assert_eq!(transaction_data.data, update_ix.data);
+    assert_eq!(transaction_data.signers, vec![true, false]);
+    assert_eq!(
+        transaction_info.data().len(),
+        transaction_data.try_to_vec().expect("pack").len() + multisig::MAX_TRANSACTION_RETURN_DATA
+    );
+
+    // No updates after another owner approved
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::approve(
+            &custodian_2.pubkey(),
+            &multisig_address,
+            &transaction_address,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_2], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::update_transaction(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            &transaction_address,
+            multisig::add_owner(&multisig_address, Pubkey::new_unique()),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("already approved");
+}
+
+#[tokio::test]
+async fn test_transaction_buffer() {
+    let program_test = ProgramTest::new(
+        "multisig",
+        multisig::id(),
+        processor!(multisig::Processor::process),
+    );
+
+    // Start Program Test
+    let mut context = program_test.start_with_context().await;
+
+    let mut banks_client = context.banks_client.clone();
+    let funder = context.payer.insecure_clone();
+    let recent_blockhash = context.last_blockhash;
+
+    // Create Multisig
+    let seed = uuid::Uuid::new_v4().as_u128();
+
+    let custodian_1 = Keypair::new();
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_multisig(
+            &funder.pubkey(),
+            seed,
+            vec![custodian_1.pubkey()],
+            1,
+            multisig::MAX_SIGNERS as u32,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let multisig_address = multisig::get_multisig_address(seed);
+    let buffer_address =
+        multisig::get_transaction_buffer_address(&multisig_address, &custodian_1.pubkey());
+
+    // Upload instruction data too large for a single transaction
+    let data = (0..4000).map(|i| i as u8).collect::<Vec<_>>();
+    let ix = Instruction::new_with_bytes(Pubkey::new_unique(), &data, vec![]);
+
+    let mut ixs = multisig::create_transaction_with_buffer(
+        &funder.pubkey(),
+        &custodian_1.pubkey(),
+        &multisig_address,
+        1,
+        ix,
+        multisig::TRANSACTION_BUFFER_CHUNK_SIZE,
+    );
+
+    assert_eq!(ixs.len(), 6);
+
+    // Finalize fails on an incomplete buffer
+    let finalize_ix = ixs.pop().expect("instruction");
+    let last_upload_ix = ixs.pop().expect("instruction");
+
+    for ix in ixs {
+        let mut transaction = Transaction::new_with_payer(&[ix], Some(&funder.pubkey()));
+        transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process_transaction");
+    }
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::finalize_transaction(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            1,
+            &funder.pubkey(),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("incomplete buffer");
+
+    let recent_blockhash = context
+        .get_new_latest_blockhash()
+        .await
+        .expect("get_new_latest_blockhash");
+
+    for ix in [last_upload_ix, finalize_ix] {
+        let mut transaction = Transaction::new_with_payer(&[ix], Some(&funder.pubkey()));
+        transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process_transaction");
+    }
+
+    // Buffer is closed
+    let buffer_info = banks_client
+        .get_account(buffer_address)
+        .await
+        .expect("get_account");
+
+    assert!(buffer_info.is_none());
+
+    let transaction_info = banks_client
+        .get_account(multisig::get_transaction_address(&multisig_address, 1))
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    assert_eq!(transaction_data.index, 1);
+    assert_eq!(transaction_data.data, data);
+    assert_eq!(transaction_data.proposer, custodian_1.pubkey());
+    assert_eq!(transaction_data.signers, vec![true]);
+
+    // Abandoned upload is closed by the proposer and refunded to its funder
+    let buffer_funder = Keypair::new();
+    let buffer_funder_balance = 1_000_000_000;
+
+    let ix = Instruction::new_with_bytes(Pubkey::new_unique(), &data, vec![]);
+
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            system_instruction::transfer(
+                &funder.pubkey(),
+                &buffer_funder.pubkey(),
+                buffer_funder_balance,
+            ),
+            multisig::create_transaction_buffer(
+                &buffer_funder.pubkey(),
+                &custodian_1.pubkey(),
+                &multisig_address,
+                &ix,
+                multisig::TRANSACTION_BUFFER_CHUNK_SIZE,
+            ),
+        ],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &buffer_funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let buffer_info = banks_client
+        .get_account(buffer_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let buffer_data =
+        multisig::TransactionBuffer::unpack(buffer_info.data()).expect("buffer unpack");
+
+    assert_eq!(buffer_data.funder, buffer_funder.pubkey());
+
+    // Only the recorded funder is refunded
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::close_transaction_buffer(
+            &custodian_1.pubkey(),
+            &multisig_address,
+            &funder.pubkey(),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("not the buffer funder");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::close_transaction_buffer(
+            &custodian_1.pubkey(),
+            &multisig_address,
+            &buffer_funder.pubkey(),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let buffer_info = banks_client
+        .get_account(buffer_address)
+        .await
+        .expect("get_account");
+
+    assert!(buffer_info.is_none());
+
+    let balance = banks_client
+        .get_balance(buffer_funder.pubkey())
+        .await
+        .expect("get_balance");
+
+    assert_eq!(balance, buffer_funder_balance);
+}
+
+#[tokio::test]
+async fn test_lookup_tables() {
+    let program_test = ProgramTest::new(
+        "multisig",
+        multisig::id(),
+        processor!(multisig::Processor::process),
+    );
+
+    // Start Program Test
+    let mut context = program_test.start_with_context().await;
+
+    let mut banks_client = context.banks_client.clone();
+    let funder = context.payer.insecure_clone();
+    let recent_blockhash = context.last_blockhash;
+
+    // Create Multisig
+    let seed = uuid::Uuid::new_v4().as_u128();
+
+    let custodian_1 = Keypair::new();
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_multisig(
+            &funder.pubkey(),
+            seed,
+            vec![custodian_1.pubkey()],
+            1,
+            multisig::MAX_SIGNERS as u32,
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let multisig_address = multisig::get_multisig_address(seed);
+    let pending_address = multisig::get_transaction_address(&multisig_address, 1);
+    let delete_address = multisig::get_transaction_address(&multisig_address, 2);
+
+    // Create Lookup Table
+    context.warp_to_slot(2).expect("warp_to_slot");
+
+    let (create_table_ix, table_address) =
+        address_lookup_table::instruction::create_lookup_table(funder.pubkey(), funder.pubkey(), 1);
+
+    let addresses = vec![Pubkey::new_unique(), pending_address];
+
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            create_table_ix,
+            address_lookup_table::instruction::extend_lookup_table(
+                table_address,
+                funder.pubkey(),
+                Some(funder.pubkey()),
+                addresses.clone(),
+            ),
+        ],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], context.last_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    // Addresses can be looked up starting from the next slot
+    context.warp_to_slot(3).expect("warp_to_slot");
+
+    let recent_blockhash = context.last_blockhash;
+
+    let lookup_table = AddressLookupTableAccount {
+        key: table_address,
+        addresses,
+    };
+
+    // Create pending transaction and its deletion referencing it through the table
+    let proposals = [
+        multisig::create_transaction(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            1,
+            multisig::add_owner(&multisig_address, Pubkey::new_unique()),
+        ),
+        multisig::create_transaction_with_lookups(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            2,
+            multisig::delete_pending_transaction(&multisig_address, pending_address),
+            std::slice::from_ref(&lookup_table),
+        ),
+    ];
+
+    for ix in proposals {
+        let mut transaction = Transaction::new_with_payer(&[ix], Some(&funder.pubkey()));
+        transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process_transaction");
+    }
+
+    let transaction_info = banks_client
+        .get_account(delete_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    assert_eq!(transaction_data.accounts.len(), 1);
+    assert_eq!(transaction_data.lookup_tables, vec![table_address]);
+    assert_eq!(transaction_data.lookup_accounts.len(), 1);
+    assert_eq!(transaction_data.lookup_accounts[0].address_index, 1);
+    assert_eq!(transaction_data.lookup_addresses, vec![pending_address]);
+
+    // Updating keeps the accounts referenced through the table
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::update_transaction_with_lookups(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            &delete_address,
+            multisig::delete_pending_transaction(&multisig_address, pending_address),
+            std::slice::from_ref(&lookup_table),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let transaction_info = banks_client
+        .get_account(delete_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    assert_eq!(transaction_data.revision, 1);
+    assert_eq!(transaction_data.lookup_tables, vec![table_address]);
+    assert_eq!(transaction_data.lookup_addresses, vec![pending_address]);
+
+    // Lookup tables are required to check the resolved accounts
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::execute_transaction(
+            &funder.pubkey(),
+            &multisig_address,
+            &delete_address,
+            transaction_data.get_accounts(),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("lookup table missing");
+
+    // Execute through a v0 message
+    let message = multisig::execute_transaction_message(
+        &funder.pubkey(),
+        &multisig_address,
+        &delete_address,
+        &transaction_data,
+        &[lookup_table],
+        recent_blockhash,
+    )
+    .expect("execute_transaction_message");
+
+    let transaction = VersionedTransaction::try_new(message, &[&funder]).expect("sign");
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let transaction_info = banks_client
+        .get_account(pending_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    let transaction_data = multisig::Transaction::unpack_from_slice(transaction_info.data())
+        .expect("transaction unpack");
+
+    assert!(transaction_data.is_cancelled);
+}
+
+#[tokio::test]
+async fn test_migrate_multisig() {
+    let mut program_test = ProgramTest::new(
+        "multisig",
+        multisig::id(),
+        processor!(multisig::Processor::process),
+    );
+
+    // Multisig created by an earlier version of the program
+    let seed = uuid::Uuid::new_v4().as_u128();
+
+    let custodian_1 = Keypair::new();
+    let custodian_2 = Keypair::new();
+
+    let multisig_address = multisig::get_multisig_address(seed);
+
+    let mut legacy_data = vec![0; multisig::Multisig::LEGACY_LEN];
+    multisig::LegacyMultisig::pack(
+        multisig::LegacyMultisig {
+            is_initialized: true,
+            owners: vec![custodian_1.pubkey(), custodian_2.pubkey()],
+            threshold: 1,
+            pending_transactions: vec![Pubkey::new_unique()],
+            seed,
+        },
+        &mut legacy_data,
+    )
+    .expect("pack");
+
+    program_test.add_account(
+        multisig_address,
+        Account {
+            lamports: Rent::default().minimum_balance(legacy_data.len()),
+            data: legacy_data,
+            owner: multisig::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Start Program Test
+    let mut context = program_test.start_with_context().await;
+
+    let mut banks_client = context.banks_client.clone();
+    let funder = context.payer.insecure_clone();
+    let recent_blockhash = context.last_blockhash;
+
+    // Proposing fails until the account is migrated
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_transaction(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            1,
+            multisig::add_owner(&multisig_address, Pubkey::new_unique()),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("legacy layout");
+
+    // Migrate Multisig
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::migrate_multisig(&multisig_address)],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+
+    let multisig_info = banks_client
+        .get_account(multisig_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    assert_eq!(multisig_info.data().len(), multisig::Multisig::LEN);
+
+    let multisig_data = multisig::Multisig::unpack(multisig_info.data()).expect("multisig unpack");
+
+    assert_eq!(
+        multisig_data.owners,
+        vec![custodian_1.pubkey(), custodian_2.pubkey()]
+    );
+    assert_eq!(multisig_data.threshold, 1);
+    assert_eq!(multisig_data.max_signers, multisig::MAX_SIGNERS as u32);
+    assert_eq!(multisig_data.pending_transaction_count, 0);
+
+    // Migrating twice fails
+    let recent_blockhash = context
+        .get_new_latest_blockhash()
+        .await
+        .expect("get_new_latest_blockhash");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::migrate_multisig(&multisig_address)],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("already migrated");
+
+    // Migrated multisig accepts proposals
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_transaction(
+            &funder.pubkey(),
+            &custodian_1.pubkey(),
+            &multisig_address,
+            1,
+            multisig::add_owner(&multisig_address, Pubkey::new_unique()),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian_1], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+}
+
+#[tokio::test]
+async fn test_legacy_multisig_rejects_proposals() {
+    let mut program_test = ProgramTest::new(
+        "multisig",
+        multisig::id(),
+        processor!(multisig::Processor::process),
+    );
+
+    // Multisig created by an earlier version of the program
+    let seed = uuid::Uuid::new_v4().as_u128();
+
+    let custodian = Keypair::new();
+
+    let multisig_address = multisig::get_multisig_address(seed);
+
+    let mut legacy_data = vec![0; multisig::Multisig::LEGACY_LEN];
+    multisig::LegacyMultisig::pack(
+        multisig::LegacyMultisig {
+            is_initialized: true,
+            owners: vec![custodian.pubkey()],
+            threshold: 1,
+            pending_transactions: vec![],
+            seed,
+        },
+        &mut legacy_data,
+    )
+    .expect("pack");
+
+    // The legacy layout decodes as the current one, with the transaction
+    // index taken from the bytes of the seed
+    let misread_data =
+        multisig::Multisig::deserialize(&mut legacy_data.as_slice()).expect("deserialize");
+    assert!(multisig::Multisig::unpack(&legacy_data).is_err());
+
+    program_test.add_account(
+        multisig_address,
+        Account {
+            lamports: Rent::default().minimum_balance(legacy_data.len()),
+            data: legacy_data.clone(),
+            owner: multisig::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Start Program Test
+    let (mut banks_client, funder, recent_blockhash) = program_test.start().await;
+
+    // Create Transaction at the index the misread multisig points to
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::create_transaction(
+            &funder.pubkey(),
+            &custodian.pubkey(),
+            &multisig_address,
+            misread_data.transaction_index + 1,
+            multisig::add_owner(&multisig_address, Pubkey::new_unique()),
+        )],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder, &custodian], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("legacy layout");
+
+    let multisig_info = banks_client
+        .get_account(multisig_address)
+        .await
+        .expect("get_account")
+        .expect("account");
+
+    assert_eq!(multisig_info.data(), legacy_data.as_slice());
+
+    // The untouched account can still be migrated
+    let mut transaction = Transaction::new_with_payer(
+        &[multisig::migrate_multisig(&multisig_address)],
+        Some(&funder.pubkey()),
+    );
+    transaction.sign(&[&funder], recent_blockhash);
+
+    banks_client
+        .process_transaction(transaction)
+        .await
+        .expect("process_transaction");
+}
+
+#[tokio::test]
+async fn test_client() {
+    let program_test = ProgramTest::new(
+        "multisig",
+        multisig::id(),
+        processor!(multisig::Processor::process),
+    );
+
+    // Start Program Test
+    let (banks_client, funder, _) = program_test.start().await;
+
+    let mut client = multisig::MultisigClient::new(banks_client, funder);
+
+    // Create Multisig
+    let seed = uuid::Uuid::new_v4().as_u128();
+
+    let custodian_1 = Keypair::new();
+    let custodian_2 = Keypair::new();
+
+    let multisig_address = client
+        .create_multisig(
+            seed,
+            vec![custodian_1.pubkey(), custodian_2.pubkey()],
+            2,
+            multisig::MAX_SIGNERS as u32,
+        )
+        .await
+        .expect("create_multisig");
+
+    // Propose, approve and execute
+    let new_owner = Pubkey::new_unique();
+
+    let transaction_address = client
+        .propose(
+            &custodian_1,
+            &multisig_address,
+            multisig::add_owner(&multisig_address, new_owner),
+        )
+        .await
+        .expect("propose");
+
+    // Not enough signers yet
+    assert!(client
+        .execute(&multisig_address, &transaction_address)
+        .await
+        .is_err());
+
+    client
+        .approve(&custodian_2, &multisig_address, &transaction_address)
+        .await
+        .expect("approve");
+
+    client
+        .execute(&multisig_address, &transaction_address)
+        .await
+        .expect("execute");
+
+    let multisig_data = client
+        .get_multisig(&multisig_address)
+        .await
+        .expect("get_multisig");
+
+    assert_eq!(multisig_data.owners.len(), 3);
+    assert!(multisig_data.owners.contains(&new_owner));
+
+    let proposals = client
+        .get_proposals(&multisig_address)
+        .await
+        .expect("get_proposals");
+
+    assert_eq!(proposals.len(), 1);
+    assert_eq!(proposals[0].0, transaction_address);
+    assert!(proposals[0].1.did_execute);
+    assert_eq!(proposals[0].1.executor, client.payer().pubkey());
 }
@@ -0,0 +1,177 @@
+#![cfg(feature = "bindings")]
+
+use borsh::BorshSerialize;
+use solana_program::pubkey::Pubkey;
+
+use multisig::{MultisigAccountKind, MultisigIndex, MultisigIndexError, Recovery, Transaction};
+
+/// Accounts of two multisigs, dumped in `solana account --output json` format,
+/// along with a multisig at a foreign address and an account of another program
+const FIXTURE: &str = include_str!("fixtures/accounts.json");
+
+/// Owners used in the fixture
+fn key(n: u8) -> Pubkey {
+    Pubkey::new_from_array([n; 32])
+}
+
+fn indices(proposals: &[(&Pubkey, &Transaction)]) -> Vec<(Pubkey, u64)> {
+    proposals
+        .iter()
+        .map(|(_, transaction)| (transaction.multisig, transaction.index))
+        .collect()
+}
+
+#[test]
+fn test_index_fixture() {
+    let index = MultisigIndex::from_json(FIXTURE).expect("from_json");
+
+    let multisig_1 = multisig::get_multisig_address(1);
+    let multisig_2 = multisig::get_multisig_address(2);
+
+    assert_eq!(index.multisigs().count(), 2);
+    assert_eq!(index.transactions().count(), 4);
+    assert!(index.get_multisig(&key(7)).is_none());
+    assert!(index
+        .get_multisig(&multisig::get_multisig_address(3))
+        .is_none());
+
+    // Multisigs by owner
+    let multisigs = |owner| {
+        let mut multisigs = index
+            .get_multisigs_by_owner(&owner)
+            .into_iter()
+            .map(|(pubkey, _)| *pubkey)
+            .collect::<Vec<_>>();
+        multisigs.sort();
+        multisigs
+    };
+
+    let mut both = vec![multisig_1, multisig_2];
+    both.sort();
+
+    assert_eq!(multisigs(key(1)), vec![multisig_1]);
+    assert_eq!(multisigs(key(2)), both);
+    assert!(multisigs(Pubkey::new_unique()).is_empty());
+
+    // Proposals by multisig
+    let proposals = index.get_proposals_by_multisig(&multisig_1);
+    assert_eq!(
+        indices(&proposals),
+        vec![(multisig_1, 1), (multisig_1, 2), (multisig_1, 3)]
+    );
+    assert_eq!(
+        *proposals[1].0,
+        multisig::get_transaction_address(&multisig_1, 2)
+    );
+    assert!(proposals[0].1.did_execute);
+
+    // Proposals awaiting approval, executed and fully approved ones are skipped
+    assert!(index.get_proposals_awaiting(&key(1)).is_empty());
+    assert_eq!(
+        indices(&index.get_proposals_awaiting(&key(2))),
+        vec![(multisig_1, 2)]
+    );
+
+    let mut awaiting = indices(&index.get_proposals_awaiting(&key(3)));
+    awaiting.sort();
+    let mut expected = vec![(multisig_1, 2), (multisig_2, 1)];
+    expected.sort();
+    assert_eq!(awaiting, expected);
+}
+
+#[test]
+fn test_index_json_formats() {
+    let accounts = FIXTURE.trim().trim_start_matches('[').trim_end_matches(']');
+
+    // Single account
+    let first = accounts.split("\n  },").next().unwrap().to_string() + "}";
+    let index = MultisigIndex::from_json(&first).expect("single account");
+    assert_eq!(index.multisigs().count(), 1);
+
+    // `getProgramAccounts` response
+    let response = format!(
+        r#"{{ "jsonrpc": "2.0", "id": 1, "result": [{}] }}"#,
+        accounts
+    );
+    let index = MultisigIndex::from_json(&response).expect("response");
+    assert_eq!(index.transactions().count(), 4);
+
+    let base58 = FIXTURE.replace("\"base64\"", "\"base58\"");
+    assert!(matches!(
+        MultisigIndex::from_json(&base58),
+        Err(MultisigIndexError::UnsupportedEncoding(_))
+    ));
+
+    assert!(matches!(
+        MultisigIndex::from_json("{}"),
+        Err(MultisigIndexError::Json(_))
+    ));
+}
+
+#[test]
+fn test_index_accounts() {
+    let index = MultisigIndex::from_json(FIXTURE).unwrap();
+
+    let multisig_pubkey = multisig::get_multisig_address(1);
+    let transaction_pubkey = multisig::get_transaction_address(&multisig_pubkey, 1);
+
+    // Multisig accounts are allocated for their capacity
+    let mut multisig_data = index
+        .get_multisig(&multisig_pubkey)
+        .unwrap()
+        .try_to_vec()
+        .unwrap();
+    multisig_data.resize(multisig::Multisig::get_len(5, 0), 0);
+
+    let transaction_data = index
+        .get_transaction(&transaction_pubkey)
+        .unwrap()
+        .try_to_vec()
+        .unwrap();
+
+    let recovery = Recovery {
+        is_initialized: true,
+        multisig: multisig_pubkey,
+        guardians: vec![key(1)],
+        guardian_threshold: 1,
+        delay: multisig::MIN_RECOVERY_DELAY,
+        pending: None,
+    };
+
+    let mut index = MultisigIndex::new();
+
+    assert_eq!(
+        index.insert(multisig_pubkey, &multisig_data),
+        Some(MultisigAccountKind::Multisig)
+    );
+    assert_eq!(
+        index.insert(transaction_pubkey, &transaction_data),
+        Some(MultisigAccountKind::Transaction)
+    );
+
+    // Valid data at an unexpected address
+    assert_eq!(index.insert(Pubkey::new_unique(), &multisig_data), None);
+    assert_eq!(index.insert(multisig_pubkey, &transaction_data), None);
+
+    // Other program accounts and garbage
+    assert_eq!(
+        index.insert(
+            multisig::get_recovery_address(&multisig_pubkey),
+            &recovery.try_to_vec().unwrap()
+        ),
+        None
+    );
+    assert_eq!(index.insert(Pubkey::new_unique(), &[]), None);
+    assert_eq!(index.insert(Pubkey::new_unique(), &[1; 8]), None);
+
+    assert_eq!(index.multisigs().count(), 1);
+    assert_eq!(index.transactions().count(), 1);
+
+    let index = MultisigIndex::from_accounts([
+        (multisig_pubkey, multisig_data),
+        (transaction_pubkey, transaction_data),
+    ]);
+    let proposals = index.get_proposals_by_multisig(&multisig_pubkey);
+    assert_eq!(proposals.len(), 1);
+    assert_eq!(proposals[0].1.accounts[0].pubkey, multisig_pubkey);
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "bindings")]
+
+use solana_program::pubkey::Pubkey;
+use solana_program::system_instruction;
+
+use multisig::{Multisig, ProposalStatus, Transaction, TransactionAccount};
+
+fn multisig(owners: usize, threshold: u64) -> Multisig {
+    Multisig {
+        is_initialized: true,
+        owners: (0..owners).map(|_| Pubkey::new_unique()).collect(),
+        threshold,
+        pending_transaction_count: 1,
+        transaction_index: 1,
+        seed: 1,
+        max_signers: multisig::MAX_SIGNERS as u32,
+        allowed_programs: vec![],
+        admin_threshold: None,
+        upgrade_threshold: None,
+        owners_epoch: 0,
+    }
+}
+
+fn transaction(multisig: &Multisig, signers: Vec<bool>) -> Transaction {
+    let multisig_pubkey = multisig::get_multisig_address(multisig.seed);
+    let ix = system_instruction::transfer(&multisig_pubkey, &Pubkey::new_unique(), 1);
+
+    Transaction {
+        is_initialized: true,
+        multisig: multisig_pubkey,
+        index: 1,
+        program_id: ix.program_id,
+        accounts: ix
+            .accounts
+            .iter()
+            .map(|acc| TransactionAccount {
+                pubkey: acc.pubkey,
+                is_signer: acc.is_signer,
+                is_writable: acc.is_writable,
+            })
+            .collect(),
+        data: ix.data,
+        signers,
+        did_execute: false,
+        is_cancelled: false,
+        executed_slot: 0,
+        executor: Pubkey::default(),
+        return_data: vec![],
+        proposer: multisig.owners[0],
+        revision: 0,
+        lookup_tables: vec![],
+        lookup_accounts: vec![],
+        lookup_addresses: vec![],
+        owners_epoch: multisig.owners_epoch,
+    }
+}
+
+#[test]
+fn test_proposal_status() {
+    let mut multisig = multisig(3, 2);
+
+    let mut tx = transaction(&multisig, vec![false, false, false]);
+    assert_eq!(tx.get_status(&multisig), ProposalStatus::Draft);
+
+    tx.signers[0] = true;
+    assert_eq!(tx.get_status(&multisig), ProposalStatus::Active);
+
+    tx.signers[2] = true;
+    assert_eq!(tx.get_status(&multisig), ProposalStatus::Approved);
+
+    // Threshold raised after the approvals
+    multisig.threshold = 3;
+    assert_eq!(tx.get_status(&multisig), ProposalStatus::Active);
+    multisig.threshold = 2;
+
+    // Terminal states take precedence
+    tx.is_cancelled = true;
+    assert_eq!(tx.get_status(&multisig), ProposalStatus::Cancelled);
+    tx.is_cancelled = false;
+
+    tx.did_execute = true;
+    assert_eq!(tx.get_status(&multisig), ProposalStatus::Executed);
+    tx.did_execute = false;
+
+    // Owner set changed since the proposal
+    multisig.owners_epoch += 1;
+    assert_eq!(tx.get_status(&multisig), ProposalStatus::Stale);
+    multisig.owners_epoch -= 1;
+
+    // Target program removed from the allowlist
+    multisig.allowed_programs = vec![Pubkey::new_unique()];
+    assert_eq!(tx.get_status(&multisig), ProposalStatus::Stale);
+}
+
+#[test]
+fn test_proposal_info() {
+    let mut multisig = multisig(3, 2);
+    multisig.admin_threshold = Some(3);
+
+    let tx = transaction(&multisig, vec![true, false, true]);
+
+    let info = multisig::get_proposal_info(&multisig, &tx);
+    assert_eq!(info.status, ProposalStatus::Approved);
+    assert_eq!(info.approvers, vec![multisig.owners[0], multisig.owners[2]]);
+    assert_eq!(info.missing_owners, vec![multisig.owners[1]]);
+    assert_eq!(info.threshold, 2);
+
+    // Reconfiguration requires the admin threshold
+    let mut tx = tx;
+    tx.program_id = multisig::id();
+    tx.data = multisig::update_threshold(&tx.multisig, 1).data;
+
+    let info = multisig::get_proposal_info(&multisig, &tx);
+    assert_eq!(info.status, ProposalStatus::Active);
+    assert_eq!(info.threshold, 3);
+}
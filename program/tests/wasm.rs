@@ -0,0 +1,394 @@
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use std::collections::BTreeSet;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use js_sys::{Array, Object, Reflect};
+use solana_program::pubkey::Pubkey;
+use solana_program::{system_instruction, system_program};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use multisig::{wasm, MultisigInstruction};
+
+/// Asserts that the input was rejected with a `MultisigWasmError` of the code
+fn assert_error<T: std::fmt::Debug>(result: Result<T, JsValue>, code: &str) {
+    let error = result.expect_err(code);
+    let error = error.dyn_into::<js_sys::Error>().expect("Error instance");
+
+    assert_eq!(error.name(), "MultisigWasmError");
+    assert_eq!(
+        Reflect::get(&error, &JsValue::from_str("code")).expect("code"),
+        JsValue::from_str(code)
+    );
+}
+
+fn object(fields: &[(&str, JsValue)]) -> JsValue {
+    let object = Object::new();
+    for (name, value) in fields {
+        Reflect::set(&object, &JsValue::from_str(name), value).expect("field");
+    }
+
+    object.into()
+}
+
+#[wasm_bindgen_test]
+fn test_parse_pubkey_errors() {
+    assert_error(
+        wasm::parse_pubkey(&JsValue::from_str("bad")),
+        "InvalidPubkey",
+    );
+    assert_error(wasm::parse_pubkey(&JsValue::from_f64(1.0)), "InvalidPubkey");
+    assert_error(
+        wasm::parse_pubkey(&Array::of1(&JsValue::from_f64(1.0)).into()),
+        "InvalidPubkey",
+    );
+    assert_error(wasm::parse_pubkeys(&JsValue::NULL), "InvalidPubkey");
+
+    let pubkey = Pubkey::new_unique();
+    assert_eq!(
+        wasm::parse_pubkey(&JsValue::from_str(&pubkey.to_string())),
+        Ok(pubkey)
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_parse_seed_errors() {
+    assert_error(wasm::parse_seed(&JsValue::from_str("bad")), "InvalidSeed");
+    assert_error(wasm::parse_seed(&JsValue::from_f64(1.0)), "InvalidSeed");
+    assert_error(
+        wasm::parse_seed(&JsValue::bigint_from_str("-1")),
+        "InvalidSeed",
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_parse_chunk_size_errors() {
+    assert_error(wasm::parse_chunk_size(Some(0)), "InvalidArgument");
+    assert_eq!(wasm::parse_chunk_size(Some(1)), Ok(1));
+    assert_eq!(
+        wasm::parse_chunk_size(None),
+        Ok(multisig::TRANSACTION_BUFFER_CHUNK_SIZE)
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_parse_bytes_errors() {
+    assert_error(wasm::parse_bytes(&JsValue::from_str("bad")), "InvalidBytes");
+    assert_error(
+        wasm::parse_bytes(&Array::of1(&JsValue::from_f64(256.0)).into()),
+        "InvalidBytes",
+    );
+    assert_error(
+        wasm::parse_bytes(&Array::of1(&JsValue::from_f64(0.5)).into()),
+        "InvalidBytes",
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_parse_instruction_errors() {
+    let program_id = JsValue::from_str(&multisig::id().to_string());
+
+    assert_error(
+        wasm::parse_instruction(&JsValue::NULL),
+        "InvalidInstruction",
+    );
+    assert_error(
+        wasm::parse_instruction(&object(&[("programId", program_id.clone())])),
+        "InvalidInstruction",
+    );
+
+    // Accounts have to be an array of metas with boolean flags
+    let account = object(&[
+        ("pubkey", program_id.clone()),
+        ("isSigner", JsValue::from_str("yes")),
+        ("isWritable", JsValue::FALSE),
+    ]);
+    assert_error(
+        wasm::parse_instruction(&object(&[
+            ("programId", program_id.clone()),
+            ("keys", Array::of1(&account).into()),
+            ("data", Array::new().into()),
+        ])),
+        "InvalidInstruction",
+    );
+
+    // Invalid fields are reported with the code of their own kind
+    assert_error(
+        wasm::parse_instruction(&object(&[
+            ("programId", JsValue::from_str("bad")),
+            ("keys", Array::new().into()),
+            ("data", Array::new().into()),
+        ])),
+        "InvalidPubkey",
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_parse_lookup_tables_errors() {
+    assert_error(
+        wasm::parse_lookup_tables(&JsValue::from_str("bad")),
+        "InvalidLookupTable",
+    );
+    assert_error(
+        wasm::parse_lookup_tables(&Array::of1(&object(&[])).into()),
+        "InvalidLookupTable",
+    );
+}
+
+/// Instructions of every wasm export building a multisig instruction
+fn multisig_exports() -> Vec<(&'static str, Result<wasm::JsInstruction, JsValue>)> {
+    let funder = Pubkey::new_unique();
+    let proposer = Pubkey::new_unique();
+    let multisig = multisig::get_multisig_address(1);
+    let transaction = multisig::get_transaction_address(&multisig, 1);
+    let owner = Pubkey::new_unique();
+
+    let pubkey = |pubkey: &Pubkey| JsValue::from_str(&pubkey.to_string()).unchecked_into();
+    let pubkeys =
+        |pubkey: &Pubkey| Array::of1(&JsValue::from_str(&pubkey.to_string())).unchecked_into();
+    let instruction =
+        || wasm::to_js(&system_instruction::transfer(&multisig, &funder, 1)).expect("instruction");
+    let lookup_tables = || Array::new().unchecked_into();
+
+    let transaction_data = multisig::Transaction {
+        is_initialized: true,
+        multisig,
+        index: 1,
+        program_id: system_program::id(),
+        accounts: vec![],
+        data: vec![],
+        signers: vec![true],
+        did_execute: false,
+        is_cancelled: false,
+        executed_slot: 0,
+        executor: Pubkey::default(),
+        return_data: vec![],
+        proposer,
+        revision: 0,
+        lookup_tables: vec![],
+        lookup_accounts: vec![],
+        lookup_addresses: vec![],
+        owners_epoch: 0,
+    }
+    .try_to_vec()
+    .expect("transaction pack");
+
+    vec![
+        (
+            "createMultisig",
+            wasm::create_multisig_ix(
+                pubkey(&funder),
+                JsValue::bigint_from_str("1").unchecked_into(),
+                pubkeys(&owner),
+                1,
+                multisig::MAX_SIGNERS as u32,
+            ),
+        ),
+        (
+            "addOwner",
+            wasm::add_owner_ix(pubkey(&multisig), pubkey(&owner)),
+        ),
+        (
+            "deleteOwner",
+            wasm::delete_owner_ix(pubkey(&multisig), pubkey(&owner)),
+        ),
+        (
+            "updateThreshold",
+            wasm::update_threshold_ix(pubkey(&multisig), 1),
+        ),
+        (
+            "createTransaction",
+            wasm::create_transaction_ix(
+                pubkey(&funder),
+                pubkey(&proposer),
+                pubkey(&multisig),
+                1,
+                instruction(),
+            ),
+        ),
+        (
+            "approve",
+            wasm::approve_ix(pubkey(&proposer), pubkey(&multisig), pubkey(&transaction)),
+        ),
+        (
+            "execute",
+            wasm::execute_ix(
+                pubkey(&funder),
+                pubkey(&multisig),
+                pubkey(&transaction),
+                transaction_data.clone(),
+            ),
+        ),
+        (
+            "deletePendingTransaction",
+            wasm::delete_pending_transaction_ix(pubkey(&multisig), pubkey(&transaction)),
+        ),
+        (
+            "resize",
+            wasm::resize_ix(
+                pubkey(&multisig),
+                pubkey(&funder),
+                multisig::MAX_SIGNERS as u32,
+            ),
+        ),
+        (
+            "closeMultisig",
+            wasm::close_multisig_ix(
+                pubkey(&multisig),
+                pubkey(&funder),
+                Array::new().unchecked_into(),
+            ),
+        ),
+        (
+            "approveWithSignatures",
+            wasm::approve_with_signatures_ix(pubkey(&multisig), pubkey(&transaction)),
+        ),
+        (
+            "addAllowedProgram",
+            wasm::add_allowed_program_ix(
+                pubkey(&multisig),
+                pubkey(&funder),
+                pubkey(&Pubkey::new_unique()),
+            ),
+        ),
+        (
+            "removeAllowedProgram",
+            wasm::remove_allowed_program_ix(pubkey(&multisig), pubkey(&Pubkey::new_unique())),
+        ),
+        (
+            "updateActionThresholds",
+            wasm::update_action_thresholds_ix(pubkey(&multisig), Some(1), None),
+        ),
+        (
+            "configureRecovery",
+            wasm::configure_recovery_ix(
+                pubkey(&multisig),
+                pubkey(&funder),
+                pubkeys(&owner),
+                1,
+                multisig::MIN_RECOVERY_DELAY,
+            ),
+        ),
+        (
+            "startRecovery",
+            wasm::start_recovery_ix(pubkey(&multisig), pubkeys(&owner), pubkeys(&proposer), 1),
+        ),
+        (
+            "vetoRecovery",
+            wasm::veto_recovery_ix(pubkey(&multisig), pubkey(&owner)),
+        ),
+        (
+            "finalizeRecovery",
+            wasm::finalize_recovery_ix(pubkey(&multisig)),
+        ),
+        (
+            "checkTransaction",
+            wasm::check_transaction_ix(
+                pubkey(&funder),
+                pubkey(&multisig),
+                pubkey(&transaction),
+                transaction_data,
+            ),
+        ),
+        (
+            "updateTransaction",
+            wasm::update_transaction_ix(
+                pubkey(&funder),
+                pubkey(&proposer),
+                pubkey(&multisig),
+                pubkey(&transaction),
+                instruction(),
+            ),
+        ),
+        (
+            "createTransactionBuffer",
+            wasm::create_transaction_buffer_ix(
+                pubkey(&funder),
+                pubkey(&proposer),
+                pubkey(&multisig),
+                instruction(),
+                None,
+            ),
+        ),
+        (
+            "extendTransactionBuffer",
+            wasm::extend_transaction_buffer_ix(
+                pubkey(&funder),
+                pubkey(&proposer),
+                pubkey(&multisig),
+                vec![1, 2, 3],
+            ),
+        ),
+        (
+            "finalizeTransaction",
+            wasm::finalize_transaction_ix(
+                pubkey(&funder),
+                pubkey(&proposer),
+                pubkey(&multisig),
+                1,
+                pubkey(&funder),
+            ),
+        ),
+        (
+            "createTransactionWithLookups",
+            wasm::create_transaction_with_lookups_ix(
+                pubkey(&funder),
+                pubkey(&proposer),
+                pubkey(&multisig),
+                1,
+                instruction(),
+                lookup_tables(),
+            ),
+        ),
+        (
+            "migrateMultisig",
+            wasm::migrate_multisig_ix(pubkey(&multisig)),
+        ),
+        (
+            "closeTransactionBuffer",
+            wasm::close_transaction_buffer_ix(
+                pubkey(&proposer),
+                pubkey(&multisig),
+                pubkey(&funder),
+            ),
+        ),
+        (
+            "updateTransactionWithLookups",
+            wasm::update_transaction_with_lookups_ix(
+                pubkey(&funder),
+                pubkey(&proposer),
+                pubkey(&multisig),
+                pubkey(&transaction),
+                instruction(),
+                lookup_tables(),
+            ),
+        ),
+    ]
+}
+
+#[wasm_bindgen_test]
+fn test_every_instruction_has_export() {
+    let mut variants = BTreeSet::new();
+    for (export, ix) in multisig_exports() {
+        let ix = wasm::parse_instruction(&ix.expect(export).into()).expect(export);
+        assert_eq!(ix.program_id, multisig::id(), "{}", export);
+
+        let instruction = MultisigInstruction::try_from_slice(&ix.data).expect(export);
+        let variant = ix.data[0];
+
+        // Every export produces an instruction of its own kind
+        assert!(variants.insert(variant), "{}: {:?}", export, instruction);
+    }
+
+    // Same rule as the builders, the exports have to cover every variant
+    let count = variants.len();
+    assert!(variants.iter().copied().eq(0..count as u8));
+
+    let error = MultisigInstruction::try_from_slice(&[count as u8]).unwrap_err();
+    assert!(
+        error.to_string().contains("Unexpected variant index"),
+        "instruction {} has no export",
+        count
+    );
+}